@@ -0,0 +1,2641 @@
+//! In-memory graph cache module.
+//!
+//! This module keeps the most recently built knowledge graph in memory so that
+//! analytical commands (rankings, link queries, etc.) can be answered without
+//! rescanning and reparsing the whole note collection on every request.
+//!
+//! # Lifecycle
+//!
+//! The cache starts empty, is populated at startup (restored from disk or built
+//! by a full scan) and is replaced as a whole every time a full scan completes
+//! (see the `scan_folder` command). Queries always operate on the last complete
+//! graph snapshot.
+//!
+//! # Thread Safety
+//!
+//! `GraphCache` itself is not synchronized. It is stored in `AppState` behind an
+//! `Arc<Mutex<>>`, which serializes access from concurrent command handlers.
+//!
+//! # Persistence
+//!
+//! The cache can be saved to a JSON file in the app-data directory and restored
+//! at startup. A persisted cache records the settings it was built with
+//! (`CacheKey`) and a `VaultFingerprint` of the scanned files; it is only
+//! restored if both still match, so stale caches trigger a full rescan instead.
+//!
+//! # Tag Index
+//!
+//! Alongside the graph the cache maintains a reverse index from each hashtag to
+//! the notes carrying it. The index is built when the cache is created and kept
+//! in sync by the incremental update methods (`add_file`, `update_hashtags`,
+//! `remove_file`), so tag queries never have to walk every node.
+
+use crate::config::AppConfig;
+use crate::graph::{
+    self, is_excluded_from_graph, note_aliases, note_node, phantom_node, BuildOptions, Edge,
+    GraphData, Node, NodeSizeScale,
+};
+use crate::helpers;
+use crate::parser;
+use crate::scanner::{content_hash, MarkdownFile, VaultFingerprint};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the persisted cache inside the app-data directory.
+pub const CACHE_FILE_NAME: &str = "graph-cache.json";
+
+/// Cluster of notes without hashtags in `GraphCache::get_tag_clusters`.
+pub const UNTAGGED_CLUSTER: &str = "untagged";
+
+/// A node identifier paired with a ranking score.
+///
+/// Returned by ranking queries such as `GraphCache::get_hubs`. The meaning of
+/// `score` depends on the query that produced it.
+///
+/// # Fields
+///
+/// * `id` - Node ID (file name without extension)
+/// * `score` - Ranking score of the node (higher ranks first)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeRank {
+    pub id: String,
+    pub score: usize,
+}
+
+/// A node identifier paired with a fractional score.
+///
+/// Returned by `GraphCache::pagerank`, where the scores of all nodes sum to 1.
+///
+/// # Fields
+///
+/// * `id` - Node ID (file name without extension)
+/// * `score` - Score of the node (higher ranks first)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeScore {
+    pub id: String,
+    pub score: f64,
+}
+
+/// A wiki-link that doesn't resolve to any existing note.
+///
+/// # Fields
+///
+/// * `source` - Node ID of the note containing the link
+/// * `link` - Link text as written between the brackets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrokenLink {
+    pub source: String,
+    pub link: String,
+}
+
+/// A broken wiki-link that matches an existing note when case is ignored.
+///
+/// # Fields
+///
+/// * `source` - Node ID of the note containing the link
+/// * `link` - Link target as written, e.g. `note`
+/// * `note` - ID of the existing note it matches ignoring case, e.g. `Note`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaseMismatchLink {
+    pub source: String,
+    pub link: String,
+    pub note: String,
+}
+
+/// A wiki-link target of a note, as listed by `get_outgoing_links`.
+///
+/// # Fields
+///
+/// * `target` - Node ID the link points to
+/// * `exists` - `true` if the target is an existing note, `false` if it is a phantom
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutgoingLink {
+    pub target: String,
+    pub exists: bool,
+}
+
+/// Edges attached to a single node, split by direction.
+///
+/// # Fields
+///
+/// * `outgoing` - Edges starting at the node (its forward links)
+/// * `incoming` - Edges ending at the node (its backlinks)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeSet {
+    pub outgoing: Vec<Edge>,
+    pub incoming: Vec<Edge>,
+}
+
+/// Number of links attached to a single node, split by direction.
+///
+/// # Fields
+///
+/// * `incoming` - Number of links pointing to the node (its backlinks)
+/// * `outgoing` - Number of links starting at the node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkCounts {
+    pub incoming: usize,
+    pub outgoing: usize,
+}
+
+/// Differences between two graph states.
+///
+/// Produced by `GraphCache::diff`, describing how to get from the older state
+/// to the newer one. Edges are compared as a multiset: if a note links to the
+/// same target twice where it used to link once, one added edge is reported.
+///
+/// # Fields
+///
+/// * `added_nodes` - Nodes only present in the newer state
+/// * `removed_nodes` - IDs of nodes only present in the older state
+/// * `updated_nodes` - Nodes present in both states whose data changed (newer version)
+/// * `added_edges` - Edges only present in the newer state
+/// * `removed_edges` - Edges only present in the older state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphDelta {
+    pub added_nodes: Vec<Node>,
+    pub removed_nodes: Vec<String>,
+    pub updated_nodes: Vec<Node>,
+    pub added_edges: Vec<Edge>,
+    pub removed_edges: Vec<Edge>,
+}
+
+impl GraphDelta {
+    /// Returns `true` if the delta contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.updated_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Sparse adjacency matrix of the graph.
+///
+/// Rows and columns are indexed by position in `node_ids`. Only non-zero cells
+/// are listed, which keeps the size proportional to the number of distinct
+/// links instead of the square of the node count.
+///
+/// # Fields
+///
+/// * `node_ids` - All node IDs (real and phantom), sorted alphabetically
+/// * `entries` - Non-zero cells, sorted by row then column
+/// * `directed` - If `false`, every link is counted in both directions, so the
+///   matrix is symmetric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdjacencyMatrix {
+    pub node_ids: Vec<String>,
+    pub entries: Vec<MatrixEntry>,
+    pub directed: bool,
+}
+
+/// A non-zero cell of an `AdjacencyMatrix`.
+///
+/// # Fields
+///
+/// * `row` - Index of the linking node in `node_ids`
+/// * `col` - Index of the linked node in `node_ids`
+/// * `count` - Number of links from the row node to the column node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatrixEntry {
+    pub row: usize,
+    pub col: usize,
+    pub count: usize,
+}
+
+/// A note matching a search query.
+///
+/// # Fields
+///
+/// * `id` - Node ID of the note
+/// * `label` - Display label of the note
+/// * `score` - Fuzzy match score (see `helpers::fuzzy_score`); higher is better
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub id: String,
+    pub label: String,
+    pub score: i64,
+}
+
+/// A node in the hierarchical tag tree.
+///
+/// Hashtags such as `project/frontend/ui` are split at `/`; each segment becomes
+/// a node nested under its parent. The root node has an empty name and path.
+///
+/// # Fields
+///
+/// * `name` - Last segment of the tag (e.g. `ui`)
+/// * `path` - Full tag path without the `#` (e.g. `project/frontend/ui`)
+/// * `note_count` - Number of notes tagged with exactly this path
+/// * `total_count` - Number of distinct notes tagged with this path or any child
+///   tag; a note carrying several tags of the subtree is counted once
+/// * `children` - Child tags, sorted by name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagNode {
+    pub name: String,
+    pub path: String,
+    pub note_count: usize,
+    pub total_count: usize,
+    pub children: Vec<TagNode>,
+}
+
+/// Settings that determine the graph built from a vault.
+///
+/// A persisted cache is only valid for the exact settings it was built with:
+/// changing the scanned directories, the scan patterns, symlink or hidden path
+/// handling, the link syntax, label formatting, the stub threshold, the group
+/// colors or the link direction all produce a different graph.
+///
+/// # Fields
+///
+/// * `root_dirs` - Scanned root directories, in priority order
+/// * `include_patterns` - Configured include globs
+/// * `ignore_patterns` - Configured ignore globs
+/// * `wiki_link_pattern` - Configured custom link syntax, if any
+/// * `group_colors` - Configured node group colors
+/// * `skip_symlinks` - Configured symlink handling
+/// * `skip_hidden` - Configured hidden path handling
+/// * `undirected` - Configured link direction
+/// * `label_max_length` - Configured label truncation
+/// * `strip_date_prefix` - Configured label date stripping
+/// * `namespaces` - Configured namespaces stripped from labels
+/// * `namespace_groups` - Configured namespace grouping
+/// * `min_content_chars` - Configured stub threshold
+/// * `allow_self_links` - Configured self-link handling
+/// * `node_size_scale` - Configured node size scale
+/// * `drop_excluded_links` - Configured handling of links to excluded notes
+/// * `skip_blockquote_links` - Configured handling of links in blockquotes
+/// * `file_metadata` - Configured file metadata on nodes
+/// * `heading_sections` - Configured heading sections on nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheKey {
+    pub root_dirs: Vec<String>,
+    pub include_patterns: Vec<String>,
+    pub ignore_patterns: Vec<String>,
+    pub wiki_link_pattern: Option<String>,
+    pub group_colors: HashMap<String, String>,
+    pub skip_symlinks: bool,
+    pub skip_hidden: bool,
+    pub undirected: bool,
+    pub label_max_length: Option<usize>,
+    pub strip_date_prefix: bool,
+    pub namespaces: Vec<String>,
+    pub namespace_groups: bool,
+    pub min_content_chars: Option<usize>,
+    pub allow_self_links: bool,
+    pub node_size_scale: Option<NodeSizeScale>,
+    pub drop_excluded_links: bool,
+    pub skip_blockquote_links: bool,
+    pub file_metadata: bool,
+    pub heading_sections: bool,
+}
+
+impl CacheKey {
+    /// Builds the key for scanning `root_dirs` with the given configuration.
+    pub fn new(root_dirs: &[String], config: &AppConfig) -> Self {
+        Self {
+            root_dirs: root_dirs.to_vec(),
+            include_patterns: config.include_patterns.clone(),
+            ignore_patterns: config.ignore_patterns.clone(),
+            wiki_link_pattern: config.wiki_link_pattern.clone(),
+            group_colors: config.group_colors.clone(),
+            skip_symlinks: config.skip_symlinks,
+            skip_hidden: config.skip_hidden,
+            undirected: config.undirected,
+            label_max_length: config.label_max_length,
+            strip_date_prefix: config.strip_date_prefix,
+            namespaces: config.namespaces.clone(),
+            namespace_groups: config.namespace_groups,
+            min_content_chars: config.min_content_chars,
+            allow_self_links: config.allow_self_links,
+            node_size_scale: config.node_size_scale,
+            drop_excluded_links: config.drop_excluded_links,
+            skip_blockquote_links: config.skip_blockquote_links,
+            file_metadata: config.file_metadata,
+            heading_sections: config.heading_sections,
+        }
+    }
+}
+
+/// On-disk representation of a persisted cache.
+///
+/// The tag index is not stored; it is rebuilt from the graph when loading.
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedCache {
+    key: CacheKey,
+    fingerprint: VaultFingerprint,
+    graph: GraphData,
+}
+
+/// Cached snapshot of the knowledge graph.
+///
+/// Wraps the `GraphData` produced by `build_graph_with` and provides query methods
+/// over it. The cache never touches the file system; it only reflects the state
+/// of the last scan, plus any incremental updates applied since.
+///
+/// # Fields
+///
+/// * `graph` - The cached graph
+/// * `tag_index` - Reverse index mapping each hashtag to the IDs of the notes carrying it
+/// * `content_hashes` - Hash of the content each note was last parsed from (see
+///   `handle_file_modified`); notes restored from disk have none until modified
+#[derive(Debug, Clone)]
+pub struct GraphCache {
+    graph: GraphData,
+    tag_index: TagIndex,
+    content_hashes: HashMap<String, u64>,
+}
+
+/// Reverse index from hashtags to the notes carrying them.
+///
+/// Tags are stored with their original casing, and additionally grouped by
+/// their lookup key (see `parser::tag_key`), so queries can match
+/// case-insensitively while results keep the casing used in the notes.
+///
+/// # Fields
+///
+/// * `notes` - Each tag, as written, mapped to the IDs of the notes carrying it
+/// * `spellings` - Each lookup key mapped to the tags (as written) sharing it
+#[derive(Debug, Clone, Default)]
+struct TagIndex {
+    notes: HashMap<String, BTreeSet<String>>,
+    spellings: HashMap<String, BTreeSet<String>>,
+}
+
+impl TagIndex {
+    /// Adds a note's tags to the index.
+    fn insert(&mut self, node_id: &str, tags: &[String]) {
+        for tag in tags {
+            self.notes
+                .entry(tag.clone())
+                .or_default()
+                .insert(node_id.to_string());
+            self.spellings
+                .entry(parser::tag_key(tag))
+                .or_default()
+                .insert(tag.clone());
+        }
+    }
+
+    /// Removes a note's tags from the index, dropping tags no note carries anymore.
+    fn remove(&mut self, node_id: &str, tags: &[String]) {
+        for tag in tags {
+            let Some(notes) = self.notes.get_mut(tag) else {
+                continue;
+            };
+
+            notes.remove(node_id);
+
+            if notes.is_empty() {
+                self.notes.remove(tag);
+
+                let key = parser::tag_key(tag);
+                if let Some(spellings) = self.spellings.get_mut(&key) {
+                    spellings.remove(tag);
+
+                    if spellings.is_empty() {
+                        self.spellings.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the notes carrying any spelling of a tag, matched case-insensitively.
+    fn notes_matching(&self, tag: &str) -> BTreeSet<&String> {
+        self.spellings
+            .get(&parser::tag_key(tag))
+            .into_iter()
+            .flatten()
+            .filter_map(|spelling| self.notes.get(spelling))
+            .flatten()
+            .collect()
+    }
+}
+
+impl Default for GraphCache {
+    /// Creates an empty cache containing no nodes or edges.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphCache {
+    /// Creates an empty cache.
+    ///
+    /// Used at application startup before any directory has been scanned.
+    pub fn new() -> Self {
+        Self::from_graph(GraphData::new())
+    }
+
+    /// Creates a cache from an already constructed graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - Complete graph as returned by `build_graph_with`
+    pub fn from_graph(graph: GraphData) -> Self {
+        let mut cache = Self {
+            graph,
+            tag_index: TagIndex::default(),
+            content_hashes: HashMap::new(),
+        };
+
+        for node in &cache.graph.nodes {
+            cache.tag_index.insert(&node.id, &node.hashtags);
+        }
+
+        cache
+    }
+
+    /// Records the content hashes of the notes the graph was built from.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_hashes` - Note ID to `scanner::content_hash` of its content
+    pub fn with_content_hashes(mut self, content_hashes: HashMap<String, u64>) -> Self {
+        self.content_hashes = content_hashes;
+        self
+    }
+
+    /// Returns the cached graph.
+    pub fn graph(&self) -> &GraphData {
+        &self.graph
+    }
+
+    /// Writes the cache to a JSON file, creating parent directories as needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Destination file
+    /// * `key` - Settings the cached graph was built with
+    /// * `fingerprint` - Fingerprint of the vault at the time of the scan
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the file can't be serialized or written.
+    pub fn save(
+        &self,
+        path: &Path,
+        key: &CacheKey,
+        fingerprint: &VaultFingerprint,
+    ) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create cache directory {:?}: {}", parent, e))?;
+        }
+
+        let persisted = PersistedCache {
+            key: key.clone(),
+            fingerprint: fingerprint.clone(),
+            graph: self.graph.clone(),
+        };
+
+        let json = serde_json::to_string(&persisted)
+            .map_err(|e| format!("Failed to serialize graph cache: {}", e))?;
+
+        fs::write(path, json).map_err(|e| format!("Failed to write graph cache {:?}: {}", path, e))
+    }
+
+    /// Loads a persisted cache if it is still up to date.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File written by `save`
+    /// * `key` - Current settings
+    /// * `fingerprint` - Current fingerprint of the vault
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(GraphCache))` - The persisted cache matches the key and fingerprint
+    /// * `Ok(None)` - No cache file exists, or it is stale and the vault must be rescanned
+    /// * `Err(String)` - The file exists but can't be read or parsed
+    pub fn load(
+        path: &Path,
+        key: &CacheKey,
+        fingerprint: &VaultFingerprint,
+    ) -> Result<Option<GraphCache>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read graph cache {:?}: {}", path, e))?;
+
+        let persisted: PersistedCache = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse graph cache {:?}: {}", path, e))?;
+
+        if persisted.key != *key || persisted.fingerprint != *fingerprint {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::from_graph(persisted.graph)))
+    }
+
+    /// Returns `true` if the node is a phantom (referenced but non-existent) node.
+    ///
+    /// Phantom nodes have no file; their group may be a namespace rather than
+    /// `phantom` (see `BuildOptions::phantom_group`).
+    fn is_phantom(node: &Node) -> bool {
+        node.file_path.is_empty()
+    }
+
+    /// Ranks real nodes by their total degree (incoming + outgoing links).
+    ///
+    /// Every edge contributes one point to both of its endpoints, so a note that
+    /// links to many notes and is linked from many notes ranks highest. Phantom
+    /// nodes are excluded from the result, although edges pointing to them still
+    /// count towards the degree of the linking note.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of nodes to return
+    ///
+    /// # Returns
+    ///
+    /// Up to `limit` nodes sorted by descending degree. Nodes with equal degree
+    /// are ordered by name so the output is deterministic.
+    pub fn get_hubs(&self, limit: usize) -> Vec<NodeRank> {
+        let mut degrees: HashMap<&str, usize> = HashMap::new();
+
+        for edge in &self.graph.edges {
+            *degrees.entry(edge.from.as_str()).or_insert(0) += 1;
+            *degrees.entry(edge.to.as_str()).or_insert(0) += 1;
+        }
+
+        let mut ranks: Vec<NodeRank> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| !Self::is_phantom(node))
+            .map(|node| NodeRank {
+                id: node.id.clone(),
+                score: *degrees.get(node.id.as_str()).unwrap_or(&0),
+            })
+            .collect();
+
+        ranks.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+        ranks.truncate(limit);
+
+        ranks
+    }
+
+    /// Computes PageRank importance scores over the directed link graph.
+    ///
+    /// Every node starts with rank `1 / n`. In each iteration a node passes
+    /// `damping` of its rank on to its link targets in equal shares, and the
+    /// remaining `1 - damping` is spread evenly over all nodes. Dangling nodes
+    /// (no outgoing links, including every phantom node) spread their whole rank
+    /// evenly over all nodes, so no rank is lost and the scores always sum to 1.
+    ///
+    /// Several links from one note to the same target count as one link. Phantom
+    /// nodes are ranked like any other node, since being linked to makes a
+    /// missing note important as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `iterations` - Number of update rounds; 20 to 50 is usually enough to converge
+    /// * `damping` - Probability of following a link, between 0.0 and 1.0 (commonly 0.85)
+    ///
+    /// # Returns
+    ///
+    /// Scores of all nodes sorted by descending score, ties broken by name. Empty
+    /// for an empty graph.
+    pub fn pagerank(&self, iterations: usize, damping: f64) -> Vec<NodeScore> {
+        let count = self.graph.nodes.len();
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let index: HashMap<&str, usize> = self
+            .graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), i))
+            .collect();
+
+        let links: BTreeSet<(usize, usize)> = self
+            .graph
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                Some((*index.get(edge.from.as_str())?, *index.get(edge.to.as_str())?))
+            })
+            .collect();
+
+        let mut out_degree = vec![0usize; count];
+        for &(from, _) in &links {
+            out_degree[from] += 1;
+        }
+
+        let n = count as f64;
+        let mut ranks = vec![1.0 / n; count];
+
+        for _ in 0..iterations {
+            let dangling: f64 = (0..count)
+                .filter(|&i| out_degree[i] == 0)
+                .map(|i| ranks[i])
+                .sum();
+
+            let mut next = vec![(1.0 - damping) / n + damping * dangling / n; count];
+
+            for &(from, to) in &links {
+                next[to] += damping * ranks[from] / out_degree[from] as f64;
+            }
+
+            ranks = next;
+        }
+
+        let mut scores: Vec<NodeScore> = self
+            .graph
+            .nodes
+            .iter()
+            .zip(ranks)
+            .map(|(node, score)| NodeScore {
+                id: node.id.clone(),
+                score,
+            })
+            .collect();
+
+        scores.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id)));
+
+        scores
+    }
+
+    /// Returns sink notes: real notes that are linked to but never link out.
+    ///
+    /// A sink has at least one incoming link and no outgoing links. Notes with
+    /// neither incoming nor outgoing links are orphans and are not included, and
+    /// phantom nodes are excluded because they have no content to link from.
+    ///
+    /// # Returns
+    ///
+    /// Node IDs of all sink notes, sorted alphabetically.
+    pub fn get_sinks(&self) -> Vec<String> {
+        let mut outgoing: HashMap<&str, usize> = HashMap::new();
+        let mut incoming: HashMap<&str, usize> = HashMap::new();
+
+        for edge in &self.graph.edges {
+            *outgoing.entry(edge.from.as_str()).or_insert(0) += 1;
+            *incoming.entry(edge.to.as_str()).or_insert(0) += 1;
+        }
+
+        let mut sinks: Vec<String> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| !Self::is_phantom(node))
+            .filter(|node| !outgoing.contains_key(node.id.as_str()))
+            .filter(|node| incoming.contains_key(node.id.as_str()))
+            .map(|node| node.id.clone())
+            .collect();
+
+        sinks.sort();
+
+        sinks
+    }
+
+    /// Returns every wiki-link whose target is a phantom node.
+    ///
+    /// Because the cache stores the graph produced by `build_graph_with`, a link is
+    /// considered broken under exactly the same resolution rules the graph uses.
+    /// A note linking to the same missing target several times is reported once.
+    ///
+    /// # Returns
+    ///
+    /// Broken links sorted by source note, then by link text.
+    pub fn get_broken_links(&self) -> Vec<BrokenLink> {
+        let phantoms: HashSet<&str> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| Self::is_phantom(node))
+            .map(|node| node.id.as_str())
+            .collect();
+
+        let mut broken: Vec<BrokenLink> = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| phantoms.contains(edge.to.as_str()))
+            .map(|edge| BrokenLink {
+                source: edge.from.clone(),
+                link: edge.to.clone(),
+            })
+            .collect();
+
+        broken.sort_by(|a, b| a.source.cmp(&b.source).then_with(|| a.link.cmp(&b.link)));
+        broken.dedup();
+
+        broken
+    }
+
+    /// Returns the real notes that link to at least one phantom node.
+    ///
+    /// These are the notes to visit when cleaning up broken links; see
+    /// `get_broken_links` for the individual links.
+    ///
+    /// # Returns
+    ///
+    /// Node IDs of the linking notes, sorted alphabetically and without duplicates.
+    pub fn get_notes_with_broken_links(&self) -> Vec<String> {
+        let sources: BTreeSet<String> = self
+            .get_broken_links()
+            .into_iter()
+            .map(|broken| broken.source)
+            .filter(|source| self.is_real_node(source))
+            .collect();
+
+        sources.into_iter().collect()
+    }
+
+    /// Returns the connected components consisting only of phantom nodes.
+    ///
+    /// Works on the phantom-only subgraph: the phantom nodes and the edges
+    /// between two of them. Phantom nodes have no file and thus no outgoing
+    /// links, so in a scanned graph no two phantoms are connected and every
+    /// cluster is a single phantom node.
+    ///
+    /// # Returns
+    ///
+    /// Clusters with their phantom node IDs sorted alphabetically, ordered by
+    /// their first node ID.
+    pub fn get_phantom_clusters(&self) -> Vec<Vec<String>> {
+        let phantoms: HashSet<&str> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| Self::is_phantom(node))
+            .map(|node| node.id.as_str())
+            .collect();
+
+        let links = self
+            .graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()));
+
+        Self::components(phantoms.iter().copied(), links)
+    }
+
+    /// Splits the given nodes into the components connected by `links`.
+    ///
+    /// Link direction is ignored and links referring to other nodes are
+    /// skipped. Components have their node IDs sorted alphabetically and are
+    /// ordered by their first node ID.
+    fn components<'a>(
+        nodes: impl Iterator<Item = &'a str>,
+        links: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> Vec<Vec<String>> {
+        let nodes: Vec<&str> = nodes.collect();
+        let mut adjacency: HashMap<&str, Vec<&str>> =
+            nodes.iter().map(|&node| (node, Vec::new())).collect();
+
+        for (from, to) in links {
+            if !adjacency.contains_key(from) || !adjacency.contains_key(to) {
+                continue;
+            }
+            adjacency.entry(from).or_default().push(to);
+            adjacency.entry(to).or_default().push(from);
+        }
+
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut components = Vec::new();
+
+        for node in nodes {
+            if !visited.insert(node) {
+                continue;
+            }
+
+            let mut component = vec![node.to_string()];
+            let mut stack = vec![node];
+
+            while let Some(current) = stack.pop() {
+                for &neighbor in &adjacency[current] {
+                    if visited.insert(neighbor) {
+                        component.push(neighbor.to_string());
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            component.sort();
+            components.push(component);
+        }
+
+        components.sort();
+
+        components
+    }
+
+    /// Returns the broken links that only differ from an existing note in case.
+    ///
+    /// Links resolve case-sensitively, so `[[note]]` next to a file `Note.md`
+    /// produces a phantom node. This finds such links so they can be fixed. If
+    /// several notes match a link ignoring case, the alphabetically first is
+    /// reported.
+    ///
+    /// # Returns
+    ///
+    /// The mismatched links, sorted by source and then link target, without
+    /// duplicates.
+    pub fn get_case_mismatch_links(&self) -> Vec<CaseMismatchLink> {
+        let mut notes: HashMap<String, &str> = HashMap::new();
+
+        for node in self.graph.nodes.iter().filter(|node| !Self::is_phantom(node)) {
+            notes
+                .entry(node.id.to_lowercase())
+                .and_modify(|note| *note = (*note).min(node.id.as_str()))
+                .or_insert(node.id.as_str());
+        }
+
+        self.get_broken_links()
+            .into_iter()
+            .filter_map(|broken| {
+                let note = notes.get(&broken.link.to_lowercase())?;
+
+                Some(CaseMismatchLink {
+                    source: broken.source,
+                    link: broken.link,
+                    note: note.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the distinct link targets of a note, resolved and broken alike.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of the linking note
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Vec<OutgoingLink>)` - Each target once, in the order the note first
+    ///   links to it (empty for phantom nodes)
+    /// * `None` - No node with this ID exists in the graph
+    pub fn get_outgoing_links(&self, node_id: &str) -> Option<Vec<OutgoingLink>> {
+        if !self.contains_node(node_id) {
+            return None;
+        }
+
+        let mut seen = HashSet::new();
+
+        Some(
+            self.graph
+                .edges
+                .iter()
+                .filter(|edge| edge.from == node_id && seen.insert(edge.to.as_str()))
+                .map(|edge| OutgoingLink {
+                    target: edge.to.clone(),
+                    exists: self.is_real_node(&edge.to),
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the IDs of all real notes, i.e. the file names without extension.
+    ///
+    /// Phantom nodes are excluded, since they have no file.
+    ///
+    /// # Returns
+    ///
+    /// Node IDs sorted alphabetically.
+    pub fn get_all_file_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| !Self::is_phantom(node))
+            .map(|node| node.id.clone())
+            .collect();
+        names.sort();
+
+        names
+    }
+
+    /// Returns the node (real or phantom) with this ID, if any.
+    pub fn get_node(&self, node_id: &str) -> Option<&Node> {
+        self.graph.nodes.iter().find(|node| node.id == node_id)
+    }
+
+    /// Returns the neighbors of a node.
+    ///
+    /// In directed mode the neighbors are the targets of the node's outgoing
+    /// links. With `undirected`, every edge counts in both directions, so notes
+    /// linking to the node are neighbors too. Self-links are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of the node
+    /// * `undirected` - Whether to follow edges in both directions
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Vec<String>)` - Sorted, deduplicated IDs of the neighboring nodes
+    /// * `None` - No node with this ID exists in the graph
+    pub fn get_neighbors(&self, node_id: &str, undirected: bool) -> Option<Vec<String>> {
+        if !self.contains_node(node_id) {
+            return None;
+        }
+
+        let mut neighbors: BTreeSet<&str> = BTreeSet::new();
+
+        for edge in &self.graph.edges {
+            if edge.from == node_id {
+                neighbors.insert(edge.to.as_str());
+            }
+
+            if undirected && edge.to == node_id {
+                neighbors.insert(edge.from.as_str());
+            }
+        }
+
+        neighbors.remove(node_id);
+
+        Some(neighbors.into_iter().map(str::to_string).collect())
+    }
+
+    /// Returns `true` if the graph contains a node (real or phantom) with this ID.
+    pub fn contains_node(&self, node_id: &str) -> bool {
+        self.graph.nodes.iter().any(|node| node.id == node_id)
+    }
+
+    /// Returns the outgoing and incoming edges of a node.
+    ///
+    /// A self-link appears in both lists. Edges are returned in graph order, so a
+    /// note linking to the same target several times yields several edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of the node to look up
+    ///
+    /// # Returns
+    ///
+    /// * `Some(EdgeSet)` - The node's edges (phantom nodes only have incoming edges)
+    /// * `None` - No node with this ID exists in the graph
+    pub fn get_edges_for(&self, node_id: &str) -> Option<EdgeSet> {
+        if !self.contains_node(node_id) {
+            return None;
+        }
+
+        let outgoing = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == node_id)
+            .cloned()
+            .collect();
+
+        let incoming = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.to == node_id)
+            .cloned()
+            .collect();
+
+        Some(EdgeSet { outgoing, incoming })
+    }
+
+    /// Replaces the hashtags of a node.
+    ///
+    /// Used to keep the cache in sync after a note's content changed on disk
+    /// without rebuilding the whole graph. The tag index is updated accordingly.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of the node to update
+    /// * `hashtags` - The node's new hashtags
+    ///
+    /// # Returns
+    ///
+    /// `true` if the node exists and was updated, `false` otherwise.
+    pub fn update_hashtags(&mut self, node_id: &str, hashtags: Vec<String>) -> bool {
+        let Some(node) = self.graph.nodes.iter_mut().find(|node| node.id == node_id) else {
+            return false;
+        };
+
+        self.tag_index.remove(node_id, &node.hashtags);
+        self.tag_index.insert(node_id, &hashtags);
+        node.hashtags = hashtags;
+        self.content_hashes.remove(node_id);
+
+        true
+    }
+
+    /// Adds a note to the cached graph without a full rescan.
+    ///
+    /// Applies the same rules as `build_graph_with` to a single file: the note becomes
+    /// a real node (replacing a phantom node or an older version of the same
+    /// note), each of its wiki-links becomes an edge, and link targets that don't
+    /// exist yet become phantom nodes. Node values (incoming link counts), their
+    /// scaled sizes, the alias map and the tag index are updated accordingly, and
+    /// the content hash is recorded for `handle_file_modified`.
+    /// Links resolve through relative paths and aliases like in `build_graph_with`; an
+    /// alias already claimed by another note keeps pointing to that note.
+    ///
+    /// Group colors are not resolved here; new nodes only have a `color` if their
+    /// frontmatter sets one, until the next full scan.
+    ///
+    /// A stub file or a note excluded through its frontmatter (see
+    /// `BuildOptions::skips_file`) is treated like a removed note: an older
+    /// version of it is removed with `remove_file`. With `drop_excluded_links`,
+    /// links to an excluded note are removed as well, so it disappears from the
+    /// graph; links added to it later show up as a phantom node until the next
+    /// full scan. A note whose exclusion flag is removed is added back.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The note to add
+    /// * `options` - Link syntax and label formatting, as used for the full build
+    pub fn add_file(&mut self, file: &MarkdownFile, options: &BuildOptions) {
+        if options.skips_file(&file.content) {
+            self.remove_file(&file.name, options);
+
+            if options.drop_excluded_links && is_excluded_from_graph(&file.content) {
+                self.drop_phantom(&file.name);
+            }
+
+            self.content_hashes.insert(file.name.clone(), content_hash(&file.content));
+            return;
+        }
+
+        if self.is_real_node(&file.name) {
+            self.remove_file(&file.name, options);
+        }
+
+        let parsed = parser::parse_markdown_with(&file.content, &options.link_syntax);
+        let incoming = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.to == file.name)
+            .count();
+
+        self.graph.nodes.retain(|node| node.id != file.name);
+        self.tag_index.insert(&file.name, &parsed.hashtags);
+        self.graph
+            .nodes
+            .push(note_node(file, &parsed, incoming, options));
+
+        for alias in note_aliases(parsed.frontmatter.as_ref()) {
+            if !self.is_real_node(&alias) {
+                self.graph.aliases.entry(alias).or_insert_with(|| file.name.clone());
+            }
+        }
+
+        for link in self.resolve_links(&file.path, &parsed.wiki_links) {
+            if !options.keeps_link(&file.name, &link) {
+                continue;
+            }
+
+            match self.graph.nodes.iter_mut().find(|node| node.id == link) {
+                Some(target) => target.value += 1,
+                None => self
+                    .graph
+                    .nodes
+                    .push(phantom_node(&link, 1, options)),
+            }
+
+            self.graph.edges.push(Edge {
+                from: file.name.clone(),
+                to: link,
+            });
+        }
+
+        self.content_hashes.insert(file.name.clone(), content_hash(&file.content));
+        self.graph.rescale_nodes();
+    }
+
+    /// Applies a modified note to the cached graph, skipping no-op saves.
+    ///
+    /// Editors that autosave write the same content again and again, and a
+    /// touch only changes metadata. If the content hash equals the one the note
+    /// was last parsed from (by the full scan or `add_file`), the note is not
+    /// reparsed and an empty delta is returned. Otherwise the note is applied
+    /// with `add_file`.
+    ///
+    /// Only the note, its link targets before and after the change and the
+    /// edges from or to it can change, so the delta is computed from copies of
+    /// just these (see `neighborhood`) rather than of the whole cache. The note
+    /// is parsed once more up front to know its new link targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The modified note
+    /// * `options` - Link syntax and label formatting, as used for the full build
+    ///
+    /// # Returns
+    ///
+    /// The changes the modification made to the graph.
+    pub fn handle_file_modified(
+        &mut self,
+        file: &MarkdownFile,
+        options: &BuildOptions,
+    ) -> GraphDelta {
+        if self.content_hashes.get(&file.name) == Some(&content_hash(&file.content)) {
+            println!("[Cache] Skipping unchanged note {}", file.name);
+            return GraphDelta::default();
+        }
+
+        let parsed = parser::parse_markdown_with(&file.content, &options.link_syntax);
+        let mut scope: HashSet<String> =
+            self.resolve_links(&file.path, &parsed.wiki_links).into_iter().collect();
+        scope.extend(self.link_targets(&file.name));
+        scope.insert(file.name.clone());
+
+        let previous = self.neighborhood(&file.name, &scope);
+        self.add_file(file, options);
+
+        previous.diff(&self.neighborhood(&file.name, &scope))
+    }
+
+    /// Removes a deleted note from the cached graph, returning the changes.
+    ///
+    /// The counterpart of `handle_file_modified` for notes whose file is gone:
+    /// the note is removed with `remove_file`, and the note and its link targets
+    /// before and after are diffed (see `neighborhood`). Unknown notes and
+    /// phantom nodes yield an empty delta.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of the deleted note
+    /// * `options` - Label and group of the note if it stays as a phantom node
+    pub fn handle_file_removed(&mut self, node_id: &str, options: &BuildOptions) -> GraphDelta {
+        if !self.is_real_node(node_id) {
+            self.content_hashes.remove(node_id);
+            return GraphDelta::default();
+        }
+
+        let mut scope: HashSet<String> = self.link_targets(node_id).into_iter().collect();
+        scope.insert(node_id.to_string());
+
+        let previous = self.neighborhood(node_id, &scope);
+        self.remove_file(node_id, options);
+
+        previous.diff(&self.neighborhood(node_id, &scope))
+    }
+
+    /// Returns the IDs of the nodes a note links to.
+    fn link_targets(&self, node_id: &str) -> Vec<String> {
+        self.graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == node_id)
+            .map(|edge| edge.to.clone())
+            .collect()
+    }
+
+    /// Copies the part of the graph an update of a single note can change.
+    ///
+    /// Keeps the nodes in `node_ids` and the edges from or to `node_id`, so
+    /// diffing the copies taken before and after the update yields the same
+    /// delta as diffing the whole cache, as long as `node_ids` holds the note
+    /// and its link targets before and after the update.
+    fn neighborhood(&self, node_id: &str, node_ids: &HashSet<String>) -> GraphCache {
+        let mut graph = GraphData::new();
+        graph.nodes = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| node_ids.contains(&node.id))
+            .cloned()
+            .collect();
+        graph.edges = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == node_id || edge.to == node_id)
+            .cloned()
+            .collect();
+
+        GraphCache::from_graph(graph)
+    }
+
+    /// Removes a note from the cached graph without a full rescan.
+    ///
+    /// The note's outgoing edges are removed, and phantom nodes that are no longer
+    /// linked from anywhere disappear with them. If other notes still link to the
+    /// removed note, it stays in the graph as a phantom node, exactly as a full
+    /// rescan would produce. The note's tags are removed from the tag index and
+    /// its aliases from the alias map; links already resolved through one of its
+    /// aliases keep pointing to the removed note until the next full scan.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of the note to remove
+    /// * `options` - Label and group of the note if it stays as a phantom node
+    ///
+    /// # Returns
+    ///
+    /// `true` if a real note with this ID existed and was removed, `false` otherwise.
+    pub fn remove_file(&mut self, node_id: &str, options: &BuildOptions) -> bool {
+        self.content_hashes.remove(node_id);
+
+        if !self.is_real_node(node_id) {
+            return false;
+        }
+
+        let (outgoing, edges): (Vec<Edge>, Vec<Edge>) = std::mem::take(&mut self.graph.edges)
+            .into_iter()
+            .partition(|edge| edge.from == node_id);
+        self.graph.edges = edges;
+
+        for edge in &outgoing {
+            if let Some(target) = self.graph.nodes.iter_mut().find(|node| node.id == edge.to) {
+                target.value = target.value.saturating_sub(1);
+            }
+        }
+
+        self.graph.aliases.retain(|_, owner| owner != node_id);
+
+        let still_linked: HashSet<&str> =
+            self.graph.edges.iter().map(|edge| edge.to.as_str()).collect();
+
+        if let Some(node) = self.graph.nodes.iter_mut().find(|node| node.id == node_id) {
+            self.tag_index.remove(node_id, &node.hashtags);
+            node.label = options.label_format.apply(&node.id);
+            node.group = Some(options.phantom_group(&node.id));
+            node.file_path = String::new();
+            node.hashtags = Vec::new();
+            node.color = None;
+        }
+
+        self.graph
+            .nodes
+            .retain(|node| !Self::is_phantom(node) || still_linked.contains(node.id.as_str()));
+        self.graph.rescale_nodes();
+
+        true
+    }
+
+    /// Updates the file path of a note after its file was moved.
+    ///
+    /// Node IDs are file stems, so moving a note to another folder keeps its ID
+    /// and links; only `file_path` changes.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a real note with this ID existed and was updated, `false` otherwise.
+    pub fn set_file_path(&mut self, node_id: &str, file_path: String) -> bool {
+        match self
+            .graph
+            .nodes
+            .iter_mut()
+            .find(|node| node.id == node_id && !Self::is_phantom(node))
+        {
+            Some(node) => {
+                node.file_path = file_path;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Resolves a link target to a node ID, following aliases.
+    ///
+    /// See `graph::resolve_link_target`: an existing note with this name wins,
+    /// then a note declaring it as an alias; otherwise the target is returned
+    /// unchanged.
+    pub fn resolve_link_target(&self, target: &str) -> String {
+        graph::resolve_link_target(target, |name| self.is_real_node(name), &self.graph.aliases)
+    }
+
+    /// Resolves the links of a note to node IDs, like when building the graph.
+    ///
+    /// A path link is resolved relative to the directory of the linking note
+    /// (see `graph::resolve_relative_link`); every other link, and a path link
+    /// to a missing file, by name and alias (see `resolve_link_target`).
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Path of the note containing the links
+    /// * `links` - Link targets as extracted by the parser
+    ///
+    /// # Returns
+    ///
+    /// The node ID of each link, in the order of `links`.
+    pub fn resolve_links(&self, source: &Path, links: &[String]) -> Vec<String> {
+        let note_paths = if links.iter().any(|link| graph::is_path_link(link)) {
+            self.note_path_index()
+        } else {
+            HashMap::new()
+        };
+
+        links
+            .iter()
+            .map(|link| {
+                graph::resolve_relative_link(source, link, &note_paths)
+                    .unwrap_or_else(|| self.resolve_link_target(link))
+            })
+            .collect()
+    }
+
+    /// Indexes the real notes by normalized file path (see `graph::note_path_index`).
+    fn note_path_index(&self) -> HashMap<PathBuf, String> {
+        let paths: Vec<(PathBuf, &String)> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| !Self::is_phantom(node))
+            .map(|node| (PathBuf::from(&node.file_path), &node.id))
+            .collect();
+
+        graph::note_path_index(paths.iter().map(|(path, id)| (path.as_path(), *id)))
+    }
+
+    /// Removes a phantom node together with every edge pointing to it.
+    ///
+    /// Does nothing if the node is a real note.
+    fn drop_phantom(&mut self, node_id: &str) {
+        if self.is_real_node(node_id) {
+            return;
+        }
+
+        self.graph.edges.retain(|edge| edge.to != node_id);
+        self.graph.nodes.retain(|node| node.id != node_id);
+    }
+
+    /// Returns `true` if the graph contains a real (non-phantom) node with this ID.
+    fn is_real_node(&self, node_id: &str) -> bool {
+        self.graph
+            .nodes
+            .iter()
+            .any(|node| node.id == node_id && !Self::is_phantom(node))
+    }
+
+    /// Returns every hashtag used by at least one note.
+    ///
+    /// # Returns
+    ///
+    /// Tag names without the `#` in the casing used in the notes, sorted
+    /// alphabetically. Tags differing only in case are listed separately.
+    pub fn get_all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self.tag_index.notes.keys().cloned().collect();
+        tags.sort();
+
+        tags
+    }
+
+    /// Returns the notes carrying a hashtag.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - Tag name without the `#` (matched case-insensitively)
+    ///
+    /// # Returns
+    ///
+    /// Node IDs of all notes with the tag, sorted alphabetically. Empty if no
+    /// note carries the tag.
+    pub fn notes_with_tag(&self, tag: &str) -> Vec<String> {
+        self.tag_index.notes_matching(tag).into_iter().cloned().collect()
+    }
+
+    /// Returns the notes carrying a hashtag, optionally including its child tags.
+    ///
+    /// With `include_children`, notes tagged with any hierarchical child of `tag`
+    /// match as well: querying `project` also returns notes tagged
+    /// `project/frontend` or `project/frontend/ui`, but not `projects`.
+    ///
+    /// Tags are matched case-insensitively, so `Project` finds `project/Frontend`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - Tag name without the `#`
+    /// * `include_children` - Whether notes with child tags of `tag` match too
+    ///
+    /// # Returns
+    ///
+    /// Node IDs of all matching notes, sorted alphabetically and without duplicates.
+    pub fn get_notes_by_tag(&self, tag: &str, include_children: bool) -> Vec<String> {
+        if !include_children {
+            return self.notes_with_tag(tag);
+        }
+
+        let key = parser::tag_key(tag);
+        let prefix = format!("{}/", key);
+        let notes: BTreeSet<&String> = self
+            .tag_index
+            .spellings
+            .iter()
+            .filter(|(name, _)| **name == key || name.starts_with(&prefix))
+            .flat_map(|(_, spellings)| spellings)
+            .filter_map(|spelling| self.tag_index.notes.get(spelling))
+            .flatten()
+            .collect();
+
+        notes.into_iter().cloned().collect()
+    }
+
+    /// Groups the notes into clusters by their dominant hashtag.
+    ///
+    /// Each note joins the cluster of the one tag it carries that is used by
+    /// the most notes in the vault, so notes gather around widely shared topics
+    /// rather than niche tags. Ties go to the tag listed first in the note.
+    /// Tags are compared case-insensitively; a cluster is named after the
+    /// alphabetically first spelling of its tag. Notes without hashtags form the
+    /// `UNTAGGED_CLUSTER`. Phantom nodes are not clustered.
+    ///
+    /// # Returns
+    ///
+    /// Cluster names mapped to the IDs of their notes, sorted alphabetically.
+    pub fn get_tag_clusters(&self) -> HashMap<String, Vec<String>> {
+        let mut clusters: HashMap<String, Vec<String>> = HashMap::new();
+
+        for node in self.graph.nodes.iter().filter(|node| !Self::is_phantom(node)) {
+            let mut dominant: Option<(&String, usize)> = None;
+
+            for tag in &node.hashtags {
+                let count = self.tag_index.notes_matching(tag).len();
+
+                if dominant.is_none_or(|(_, best)| count > best) {
+                    dominant = Some((tag, count));
+                }
+            }
+
+            let cluster = match dominant {
+                Some((tag, _)) => self
+                    .tag_index
+                    .spellings
+                    .get(&parser::tag_key(tag))
+                    .and_then(|spellings| spellings.first())
+                    .unwrap_or(tag)
+                    .clone(),
+                None => UNTAGGED_CLUSTER.to_string(),
+            };
+
+            clusters.entry(cluster).or_default().push(node.id.clone());
+        }
+
+        for notes in clusters.values_mut() {
+            notes.sort();
+        }
+
+        clusters
+    }
+
+    /// Searches nodes by fuzzy matching their ID and label.
+    ///
+    /// Each node is scored with `helpers::fuzzy_score` against both its ID and
+    /// its label, keeping the better score. Phantom nodes are included so that
+    /// links to notes that don't exist yet can be found too.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Search query; `grh` matches `graph`
+    /// * `limit` - Maximum number of results
+    ///
+    /// # Returns
+    ///
+    /// Matching nodes ordered by descending score, ties broken by shorter ID and
+    /// then alphabetically.
+    pub fn search_notes(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let mut results: Vec<SearchResult> = self
+            .graph
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let score = helpers::fuzzy_score(query, &node.id)
+                    .max(helpers::fuzzy_score(query, &node.label))?;
+
+                Some(SearchResult {
+                    id: node.id.clone(),
+                    label: node.label.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.id.chars().count().cmp(&b.id.chars().count()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        results.truncate(limit);
+
+        results
+    }
+
+    /// Suggests existing note names for completing a wiki-link.
+    ///
+    /// Only real notes are suggested, since completing to a phantom node would
+    /// just repeat a broken link. Notes whose name starts with the prefix
+    /// (case-insensitively) come first, shortest name first; the remaining
+    /// notes that fuzzily match it (see `helpers::fuzzy_score`) follow by
+    /// descending score.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Text typed after `[[` so far
+    /// * `limit` - Maximum number of suggestions
+    ///
+    /// # Returns
+    ///
+    /// Note IDs in suggestion order, ties broken alphabetically.
+    pub fn complete_link(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let prefix_lower = prefix.to_lowercase();
+
+        let mut matches: Vec<(bool, i64, &str)> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| !Self::is_phantom(node))
+            .filter_map(|node| {
+                let score = helpers::fuzzy_score(prefix, &node.id)?;
+                let is_prefix = node.id.to_lowercase().starts_with(&prefix_lower);
+
+                Some((is_prefix, if is_prefix { 0 } else { score }, node.id.as_str()))
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| b.1.cmp(&a.1))
+                .then_with(|| a.2.chars().count().cmp(&b.2.chars().count()))
+                .then_with(|| a.2.cmp(b.2))
+        });
+
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, id)| id.to_string())
+            .collect()
+    }
+
+    /// Builds the hierarchical tree of all hashtags.
+    ///
+    /// Intermediate segments that are never used as a tag on their own (e.g.
+    /// `project` when only `project/frontend` exists) still get a node, with a
+    /// `note_count` of 0.
+    ///
+    /// # Returns
+    ///
+    /// The root `TagNode`, whose children are the top-level tags and whose
+    /// `total_count` is the number of notes carrying any tag.
+    pub fn get_tag_tree(&self) -> TagNode {
+        let mut root = TagNode::default();
+
+        for (tag, notes) in &self.tag_index.notes {
+            let mut node = &mut root;
+
+            for segment in tag.split('/') {
+                let index = match node.children.iter().position(|child| child.name == segment) {
+                    Some(index) => index,
+                    None => {
+                        let path = if node.path.is_empty() {
+                            segment.to_string()
+                        } else {
+                            format!("{}/{}", node.path, segment)
+                        };
+
+                        node.children.push(TagNode {
+                            name: segment.to_string(),
+                            path,
+                            ..TagNode::default()
+                        });
+                        node.children.len() - 1
+                    }
+                };
+
+                node = &mut node.children[index];
+            }
+
+            node.note_count = notes.len();
+        }
+
+        Self::finish_tag_node(&mut root, &self.tag_index);
+
+        root
+    }
+
+    /// Sorts a tag subtree and fills in its `total_count`s.
+    ///
+    /// Returns the distinct notes tagged anywhere in the subtree.
+    fn finish_tag_node<'a>(
+        node: &mut TagNode,
+        tag_index: &'a TagIndex,
+    ) -> BTreeSet<&'a str> {
+        let mut notes: BTreeSet<&str> = tag_index
+            .notes
+            .get(&node.path)
+            .map(|notes| notes.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+
+        node.children.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for child in &mut node.children {
+            notes.extend(Self::finish_tag_node(child, tag_index));
+        }
+
+        node.total_count = notes.len();
+
+        notes
+    }
+
+    /// Returns the real notes that link to a node, excluding the node itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of the linked node
+    ///
+    /// # Returns
+    ///
+    /// Each referencing note once, sorted by ID. Empty if nothing links to the node.
+    pub fn get_backlink_sources(&self, node_id: &str) -> Vec<&Node> {
+        let sources: HashSet<&str> = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.to == node_id && edge.from != node_id)
+            .map(|edge| edge.from.as_str())
+            .collect();
+
+        let mut nodes: Vec<&Node> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|node| !Self::is_phantom(node) && sources.contains(node.id.as_str()))
+            .collect();
+
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+        nodes
+    }
+
+    /// Renders a markdown report of every node's backlinks ("linked mentions").
+    ///
+    /// Existing notes are listed under `## Notes` and phantom nodes under
+    /// `## Missing Notes`, each as a `###` heading followed by the notes linking to
+    /// it as wiki-links. Nodes are sorted by ID; notes without backlinks are
+    /// listed with a placeholder line so the report covers the whole vault.
+    pub fn backlinks_report(&self) -> String {
+        let mut nodes: Vec<&Node> = self.graph.nodes.iter().collect();
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let (missing, notes): (Vec<&Node>, Vec<&Node>) =
+            nodes.into_iter().partition(|node| Self::is_phantom(node));
+
+        let mut report = String::from("# Backlinks Report\n");
+
+        for (heading, section) in [("Notes", notes), ("Missing Notes", missing)] {
+            report.push_str(&format!("\n## {}\n", heading));
+
+            if section.is_empty() {
+                report.push_str("\n_None_\n");
+            }
+
+            for node in section {
+                report.push_str(&format!("\n### {}\n\n", node.id));
+
+                let sources = self.get_backlink_sources(&node.id);
+
+                if sources.is_empty() {
+                    report.push_str("_No backlinks_\n");
+                }
+
+                for source in sources {
+                    report.push_str(&format!("- [[{}]]\n", source.id));
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Computes the changes from this cache state to a newer one.
+    ///
+    /// Nodes are matched by ID; a node whose label, value, group, path, tags or
+    /// color differ is reported as updated. All lists in the result are sorted
+    /// (nodes by ID, edges by source then target) so deltas are deterministic.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The newer cache state
+    ///
+    /// # Returns
+    ///
+    /// A `GraphDelta` that is empty if both states contain the same graph.
+    pub fn diff(&self, other: &GraphCache) -> GraphDelta {
+        let old_nodes: HashMap<&str, &Node> =
+            self.graph.nodes.iter().map(|node| (node.id.as_str(), node)).collect();
+        let new_nodes: HashMap<&str, &Node> =
+            other.graph.nodes.iter().map(|node| (node.id.as_str(), node)).collect();
+
+        let mut delta = GraphDelta::default();
+
+        for (id, node) in &new_nodes {
+            match old_nodes.get(id) {
+                None => delta.added_nodes.push((*node).clone()),
+                Some(old) if old != node => delta.updated_nodes.push((*node).clone()),
+                Some(_) => {}
+            }
+        }
+
+        delta.removed_nodes = old_nodes
+            .keys()
+            .filter(|id| !new_nodes.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect();
+
+        let mut edge_balance: HashMap<&Edge, isize> = HashMap::new();
+
+        for edge in &other.graph.edges {
+            *edge_balance.entry(edge).or_insert(0) += 1;
+        }
+
+        for edge in &self.graph.edges {
+            *edge_balance.entry(edge).or_insert(0) -= 1;
+        }
+
+        for (edge, balance) in edge_balance {
+            let target = if balance > 0 {
+                &mut delta.added_edges
+            } else {
+                &mut delta.removed_edges
+            };
+
+            for _ in 0..balance.unsigned_abs() {
+                target.push(edge.clone());
+            }
+        }
+
+        delta.added_nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        delta.updated_nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        delta.removed_nodes.sort();
+
+        let edge_order = |a: &Edge, b: &Edge| a.from.cmp(&b.from).then_with(|| a.to.cmp(&b.to));
+        delta.added_edges.sort_by(edge_order);
+        delta.removed_edges.sort_by(edge_order);
+
+        delta
+    }
+
+    /// Builds the sparse adjacency matrix of the cached graph.
+    ///
+    /// Each cell counts the links from the row node to the column node, so a
+    /// note referencing another one three times yields a count of 3. In
+    /// undirected mode each link also counts for the reverse cell (self-links
+    /// are counted once).
+    ///
+    /// # Arguments
+    ///
+    /// * `undirected` - Whether links count in both directions
+    pub fn get_adjacency_matrix(&self, undirected: bool) -> AdjacencyMatrix {
+        let mut node_ids: Vec<String> =
+            self.graph.nodes.iter().map(|node| node.id.clone()).collect();
+        node_ids.sort();
+
+        let index: HashMap<&str, usize> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), i))
+            .collect();
+
+        let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+
+        for edge in &self.graph.edges {
+            let row = index.get(edge.from.as_str());
+            let col = index.get(edge.to.as_str());
+
+            let (Some(&row), Some(&col)) = (row, col) else {
+                continue;
+            };
+
+            *counts.entry((row, col)).or_insert(0) += 1;
+
+            if undirected && row != col {
+                *counts.entry((col, row)).or_insert(0) += 1;
+            }
+        }
+
+        let mut entries: Vec<MatrixEntry> = counts
+            .into_iter()
+            .map(|((row, col), count)| MatrixEntry { row, col, count })
+            .collect();
+        entries.sort_by_key(|entry| (entry.row, entry.col));
+
+        AdjacencyMatrix {
+            node_ids,
+            entries,
+            directed: !undirected,
+        }
+    }
+
+    /// Counts how many times one note links to another.
+    ///
+    /// The graph keeps one edge per wiki-link occurrence rather than a
+    /// deduplicated set, so the count is the number of `from` -> `to` edges.
+    /// Links are counted in the given direction only.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - ID of the linking note
+    /// * `to` - ID of the linked note
+    ///
+    /// # Returns
+    ///
+    /// * `Some(usize)` - Number of links, 0 if `from` never references `to`
+    /// * `None` - No node with the ID `from` exists in the graph
+    pub fn count_links(&self, from: &str, to: &str) -> Option<usize> {
+        if !self.contains_node(from) {
+            return None;
+        }
+
+        Some(
+            self.graph
+                .edges
+                .iter()
+                .filter(|edge| edge.from == from && edge.to == to)
+                .count(),
+        )
+    }
+
+    /// Counts a node's incoming and outgoing links in one pass over the edges.
+    ///
+    /// Like `count_links`, every wiki-link occurrence counts, so a note linking
+    /// to the same target twice adds two.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of the node (real or phantom)
+    ///
+    /// # Returns
+    ///
+    /// * `Some(LinkCounts)` - The node's link counts; phantom nodes only have
+    ///   incoming links
+    /// * `None` - No node with this ID exists in the graph
+    pub fn get_link_counts(&self, node_id: &str) -> Option<LinkCounts> {
+        if !self.contains_node(node_id) {
+            return None;
+        }
+
+        let mut counts = LinkCounts {
+            incoming: 0,
+            outgoing: 0,
+        };
+
+        for edge in &self.graph.edges {
+            if edge.to == node_id {
+                counts.incoming += 1;
+            }
+
+            if edge.from == node_id {
+                counts.outgoing += 1;
+            }
+        }
+
+        Some(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{build_graph_with, phantom_node};
+    use crate::scanner::{roots_fingerprint, ScanFilter};
+    use crate::testing::{markdown_file, temp_vault};
+
+    fn cache_of(files: Vec<MarkdownFile>) -> GraphCache {
+        GraphCache::from_graph(build_graph_with(files, &BuildOptions::default()))
+    }
+
+    fn ids(nodes: &[Node]) -> Vec<&str> {
+        nodes.iter().map(|node| node.id.as_str()).collect()
+    }
+
+    /// Node and edge summary independent of order, to compare incremental updates
+    /// with a full build.
+    #[allow(clippy::type_complexity)]
+    fn summary(
+        graph: &GraphData,
+    ) -> (
+        Vec<(String, usize, Option<String>, Vec<String>)>,
+        Vec<(String, String)>,
+    ) {
+        let mut nodes: Vec<_> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.id.clone(), n.value, n.group.clone(), n.hashtags.clone()))
+            .collect();
+        nodes.sort();
+        let mut edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.clone(), e.to.clone()))
+            .collect();
+        edges.sort();
+        (nodes, edges)
+    }
+
+    #[test]
+    fn hubs_ordered_by_degree() {
+        let cache = cache_of(vec![
+            markdown_file("hub", "[[a]] [[b]] [[c]]"),
+            markdown_file("a", "[[hub]]"),
+            markdown_file("b", ""),
+            markdown_file("c", "[[x]]"),
+        ]);
+
+        let hubs = cache.get_hubs(3);
+
+        assert_eq!(
+            hubs[0],
+            NodeRank {
+                id: "hub".to_string(),
+                score: 4
+            }
+        );
+        assert_eq!(
+            hubs.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            vec!["hub", "a", "c"]
+        );
+    }
+
+    #[test]
+    fn sinks_exclude_orphans_and_phantoms() {
+        let cache = cache_of(vec![
+            markdown_file("n", "[[s]] [[p]]"),
+            markdown_file("s", ""),
+            markdown_file("o", ""),
+        ]);
+
+        assert_eq!(cache.get_sinks(), vec!["s"]);
+    }
+
+    #[test]
+    fn broken_links() {
+        let cache = cache_of(vec![
+            markdown_file("a", "[[x]] [[b]] [[x]] [[y]]"),
+            markdown_file("b", "[[z]]"),
+        ]);
+
+        let broken = cache.get_broken_links();
+
+        assert_eq!(broken.len(), 3);
+        assert_eq!(
+            broken[0],
+            BrokenLink {
+                source: "a".to_string(),
+                link: "x".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn notes_with_broken_links() {
+        let cache = cache_of(vec![
+            markdown_file("a", "[[ghost]] [[ghost]] [[other ghost]]"),
+            markdown_file("b", "[[a]]"),
+            markdown_file("c", "[[a]] [[ghost]]"),
+            markdown_file("d", ""),
+        ]);
+        assert_eq!(cache.get_notes_with_broken_links(), vec!["a", "c"]);
+
+        let cache = cache_of(vec![markdown_file("a", "[[b]]"), markdown_file("b", "")]);
+        assert!(cache.get_notes_with_broken_links().is_empty());
+    }
+
+    #[test]
+    fn edges_for_a_node() {
+        let cache = cache_of(vec![
+            markdown_file("a", "[[b]] [[p]]"),
+            markdown_file("b", "[[a]]"),
+        ]);
+
+        let edges = cache.get_edges_for("a").unwrap();
+        assert_eq!((edges.outgoing.len(), edges.incoming.len()), (2, 1));
+
+        let edges = cache.get_edges_for("p").unwrap();
+        assert_eq!((edges.outgoing.len(), edges.incoming.len()), (0, 1));
+
+        assert!(cache.get_edges_for("zz").is_none());
+    }
+
+    #[test]
+    fn tag_index_stays_consistent() {
+        let a = markdown_file("a", "#x #y [[b]] [[c]]");
+        let b = markdown_file("b", "#y [[a]]");
+        let options = BuildOptions::default();
+
+        let mut cache = cache_of(vec![a.clone()]);
+        assert_eq!(cache.get_all_tags(), vec!["x", "y"]);
+
+        cache.add_file(&b, &options);
+        assert_eq!(
+            summary(cache.graph()),
+            summary(cache_of(vec![a.clone(), b.clone()]).graph())
+        );
+        assert_eq!(cache.notes_with_tag("y"), vec!["a", "b"]);
+
+        cache.update_hashtags("a", vec!["z".to_string()]);
+        assert_eq!(cache.get_all_tags(), vec!["y", "z"]);
+        assert!(cache.notes_with_tag("x").is_empty());
+
+        assert!(cache.remove_file("b", &options));
+        assert!(cache.notes_with_tag("y").is_empty());
+        assert!(cache.remove_file("a", &options));
+        assert!(cache.graph().nodes.is_empty() && cache.graph().edges.is_empty());
+        assert!(cache.get_all_tags().is_empty());
+
+        let mut cache = cache_of(vec![a.clone(), b.clone()]);
+        cache.remove_file("a", &options);
+        assert_eq!(
+            summary(cache.graph()),
+            summary(cache_of(vec![b.clone()]).graph())
+        );
+
+        cache.add_file(&a, &options);
+        let a2 = markdown_file("a", "#q [[a]]");
+        cache.add_file(&a2, &options);
+        assert_eq!(
+            summary(cache.graph()),
+            summary(cache_of(vec![a2, b]).graph())
+        );
+        assert_eq!(cache.get_all_tags(), vec!["q", "y"]);
+    }
+
+    #[test]
+    fn notes_by_tag_with_children() {
+        let cache = cache_of(vec![
+            markdown_file("a", "#project"),
+            markdown_file("b", "#project/frontend #project"),
+            markdown_file("c", "#projects"),
+            markdown_file("d", "#project/frontend/ui"),
+        ]);
+
+        assert_eq!(cache.get_notes_by_tag("project", false), vec!["a", "b"]);
+        assert_eq!(cache.get_notes_by_tag("project", true), vec!["a", "b", "d"]);
+        assert_eq!(
+            cache.get_notes_by_tag("project/frontend", true),
+            vec!["b", "d"]
+        );
+        assert!(cache.get_notes_by_tag("nope", true).is_empty());
+    }
+
+    #[test]
+    fn tags_match_case_insensitively() {
+        let mut cache = cache_of(vec![
+            markdown_file("a", "#todo"),
+            markdown_file("b", "#Todo/Later"),
+            markdown_file("c", "#TODO"),
+            markdown_file("d", "#other"),
+        ]);
+
+        assert_eq!(cache.notes_with_tag("Todo"), vec!["a", "c"]);
+        assert_eq!(cache.get_notes_by_tag("todo", false), vec!["a", "c"]);
+        assert_eq!(cache.get_notes_by_tag("tOdO", true), vec!["a", "b", "c"]);
+        assert_eq!(
+            cache.get_all_tags(),
+            vec!["TODO", "Todo/Later", "other", "todo"]
+        );
+
+        cache.update_hashtags("c", vec![]);
+        assert_eq!(cache.notes_with_tag("todo"), vec!["a"]);
+        cache.update_hashtags("a", vec![]);
+        assert!(cache.notes_with_tag("todo").is_empty());
+        assert_eq!(cache.get_notes_by_tag("TODO", true), vec!["b"]);
+    }
+
+    #[test]
+    fn persisted_cache_round_trip_and_staleness() {
+        let dir = temp_vault("cache-persist");
+        let vault = dir.join("vault");
+        fs::create_dir_all(&vault).unwrap();
+        fs::write(vault.join("a.md"), "#t [[b]]").unwrap();
+        fs::write(vault.join("b.md"), "x").unwrap();
+        let roots = [vault.to_string_lossy().to_string()];
+        let file = dir.join("data").join("graph-cache.json");
+        let config = AppConfig::default();
+        let key = CacheKey::new(&roots, &config);
+        let fingerprint = || roots_fingerprint(&roots, &ScanFilter::default()).unwrap();
+
+        let cache = cache_of(vec![
+            markdown_file("a", "#t [[b]]"),
+            markdown_file("b", "x"),
+        ]);
+        cache.save(&file, &key, &fingerprint()).unwrap();
+
+        let loaded = GraphCache::load(&file, &key, &fingerprint())
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary(loaded.graph()), summary(cache.graph()));
+        assert_eq!(loaded.notes_with_tag("t"), vec!["a"]);
+
+        let other_config = AppConfig {
+            skip_hidden: true,
+            ..AppConfig::default()
+        };
+        let other_key = CacheKey::new(&roots, &other_config);
+        assert!(GraphCache::load(&file, &other_key, &fingerprint())
+            .unwrap()
+            .is_none());
+
+        fs::write(vault.join("c.md"), "y").unwrap();
+        assert!(GraphCache::load(&file, &key, &fingerprint())
+            .unwrap()
+            .is_none());
+
+        assert!(
+            GraphCache::load(&dir.join("missing.json"), &key, &fingerprint())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn diff_between_caches() {
+        let old = cache_of(vec![
+            markdown_file("a", "[[b]]"),
+            markdown_file("b", "#t"),
+            markdown_file("c", "x"),
+        ]);
+        let new = cache_of(vec![
+            markdown_file("a", "[[b]] [[b]] [[d]]"),
+            markdown_file("b", "#u"),
+        ]);
+
+        let delta = old.diff(&new);
+
+        assert_eq!(ids(&delta.added_nodes), vec!["d"]);
+        assert_eq!(delta.removed_nodes, vec!["c"]);
+        assert_eq!(ids(&delta.updated_nodes), vec!["b"]);
+        let added: Vec<_> = delta
+            .added_edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(added, vec![("a", "b"), ("a", "d")]);
+        assert!(delta.removed_edges.is_empty());
+        assert_eq!(old.diff(&old), GraphDelta::default());
+    }
+
+    #[test]
+    fn adjacency_matrix() {
+        let options = BuildOptions {
+            allow_self_links: true,
+            ..Default::default()
+        };
+        let cache = GraphCache::from_graph(build_graph_with(
+            vec![
+                markdown_file("b", "[[a]] [[a]] [[b]]"),
+                markdown_file("a", "[[c]]"),
+            ],
+            &options,
+        ));
+        let entries = |matrix: &AdjacencyMatrix| {
+            matrix
+                .entries
+                .iter()
+                .map(|e| (e.row, e.col, e.count))
+                .collect::<Vec<_>>()
+        };
+
+        let directed = cache.get_adjacency_matrix(false);
+        assert_eq!(directed.node_ids, vec!["a", "b", "c"]);
+        assert_eq!(entries(&directed), vec![(0, 2, 1), (1, 0, 2), (1, 1, 1)]);
+
+        let undirected = cache.get_adjacency_matrix(true);
+        assert_eq!(
+            entries(&undirected),
+            vec![(0, 1, 2), (0, 2, 1), (1, 0, 2), (1, 1, 1), (2, 0, 1)]
+        );
+        assert!(!undirected.directed);
+    }
+
+    #[test]
+    fn link_counts() {
+        let cache = cache_of(vec![
+            markdown_file("a", "[[b]] [[b]] [[c]] [[ghost]]"),
+            markdown_file("b", "[[a]]"),
+            markdown_file("c", ""),
+        ]);
+
+        assert_eq!(
+            cache.get_link_counts("a"),
+            Some(LinkCounts {
+                incoming: 1,
+                outgoing: 4
+            })
+        );
+        assert_eq!(
+            cache.get_link_counts("b"),
+            Some(LinkCounts {
+                incoming: 2,
+                outgoing: 1
+            })
+        );
+        assert_eq!(
+            cache.get_link_counts("ghost"),
+            Some(LinkCounts {
+                incoming: 1,
+                outgoing: 0
+            })
+        );
+        assert_eq!(cache.get_link_counts("nope"), None);
+
+        assert_eq!(cache.count_links("a", "b"), Some(2));
+        assert_eq!(cache.count_links("a", "c"), Some(1));
+        assert_eq!(cache.count_links("c", "a"), Some(0));
+        assert_eq!(cache.count_links("zz", "a"), None);
+    }
+
+    #[test]
+    fn incremental_updates_follow_build_options() {
+        let stubs = BuildOptions {
+            min_content_chars: Some(10),
+            ..Default::default()
+        };
+        let mut cache = GraphCache::from_graph(build_graph_with(
+            vec![markdown_file("big", "A substantial note linking [[stub]]")],
+            &stubs,
+        ));
+
+        cache.add_file(&markdown_file("stub", "now it has enough content"), &stubs);
+        assert!(cache.get_node("stub").unwrap().group.is_none());
+        cache.add_file(&markdown_file("stub", ""), &stubs);
+        assert_eq!(
+            cache.get_node("stub").unwrap().group.as_deref(),
+            Some("phantom")
+        );
+        cache.add_file(&markdown_file("lonely", "short"), &stubs);
+        assert!(cache.get_node("lonely").is_none());
+
+        let mut cache = cache_of(vec![markdown_file("note", "x")]);
+        cache.add_file(&markdown_file("b", "[[note.md]]"), &BuildOptions::default());
+        assert_eq!(cache.count_links("b", "note"), Some(1));
+        assert!(cache.get_node("note.md").is_none());
+
+        let self_links = BuildOptions {
+            allow_self_links: true,
+            ..Default::default()
+        };
+        let mut cache = cache_of(vec![markdown_file("b", "")]);
+        cache.add_file(&markdown_file("c", "[[c]] [[b]]"), &BuildOptions::default());
+        assert_eq!(cache.graph().edges.len(), 1);
+        assert_eq!(cache.get_node("c").unwrap().value, 0);
+        cache.add_file(&markdown_file("c", "[[c]] [[b]]"), &self_links);
+        assert_eq!(cache.graph().edges.len(), 2);
+        assert_eq!(cache.get_node("c").unwrap().value, 1);
+
+        let log = BuildOptions {
+            node_size_scale: NodeSizeScale::Log,
+            ..Default::default()
+        };
+        let mut cache =
+            GraphCache::from_graph(build_graph_with(vec![markdown_file("c", "")], &log));
+        cache.add_file(&markdown_file("d", "[[c]]"), &log);
+        let c = cache.get_node("c").unwrap();
+        assert_eq!((c.value, c.size), (1, 2f64.ln()));
+    }
+
+    #[test]
+    fn excluded_notes_toggle() {
+        let drop = BuildOptions {
+            drop_excluded_links: true,
+            ..Default::default()
+        };
+        let mut cache = GraphCache::from_graph(build_graph_with(
+            vec![markdown_file("a", "[[b]]"), markdown_file("b", "")],
+            &drop,
+        ));
+
+        cache.add_file(&markdown_file("b", "---\ngraph: false\n---\n"), &drop);
+        assert!(cache.get_node("b").is_none());
+        assert!(cache.graph().edges.is_empty());
+
+        cache.add_file(&markdown_file("b", "back"), &drop);
+        assert!(cache
+            .get_node("b")
+            .is_some_and(|node| !node.file_path.is_empty()));
+
+        let mut cache = cache_of(vec![markdown_file("a", "[[b]]"), markdown_file("b", "")]);
+        cache.add_file(
+            &markdown_file("b", "---\ngraph: FALSE\n---\n"),
+            &BuildOptions::default(),
+        );
+        assert_eq!(
+            cache.get_node("b").unwrap().group.as_deref(),
+            Some("phantom")
+        );
+    }
+
+    #[test]
+    fn aliases_update_incrementally() {
+        let options = BuildOptions::default();
+        let mut cache = cache_of(vec![
+            markdown_file("x", "---\naliases: [X1]\n---\n"),
+            markdown_file("a", "[[X1]]"),
+        ]);
+
+        cache.add_file(&markdown_file("b", "[[X1]] [[Y]]"), &options);
+        assert_eq!(cache.get_node("x").unwrap().value, 2);
+
+        cache.add_file(&markdown_file("y", "---\naliases: [Y2]\n---\n"), &options);
+        assert_eq!(cache.resolve_link_target("Y2"), "y");
+
+        cache.remove_file("y", &options);
+        assert_eq!(cache.resolve_link_target("Y2"), "Y2");
+    }
+
+    #[test]
+    fn relative_links_resolve_against_the_source() {
+        let file = |path: &str| MarkdownFile {
+            path: PathBuf::from(path),
+            content: String::new(),
+            name: Path::new(path)
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+        };
+        let cache = cache_of(vec![
+            file("/v/shared/note.md"),
+            file("/v/other/bare.md"),
+            file("/v/projects/plan.md"),
+        ]);
+        let links: Vec<String> = ["../shared/note", "bare", "../missing/x", "./plan"]
+            .iter()
+            .map(|link| link.to_string())
+            .collect();
+
+        let resolved = cache.resolve_links(Path::new("/v/projects/plan.md"), &links);
+
+        assert_eq!(resolved, vec!["note", "bare", "../missing/x", "plan"]);
+    }
+
+    #[test]
+    fn identical_content_is_a_no_op() {
+        let options = BuildOptions::default();
+        let a = markdown_file("a", "[[x]]");
+        let hashes = HashMap::from([("a".to_string(), content_hash(&a.content))]);
+        let mut cache = cache_of(vec![a.clone()]).with_content_hashes(hashes);
+
+        assert!(cache.handle_file_modified(&a, &options).is_empty());
+
+        let edited = markdown_file("a", "[[b]]");
+        let delta = cache.handle_file_modified(&edited, &options);
+        assert_eq!(ids(&delta.added_nodes), vec!["b"]);
+        assert_eq!(delta.removed_nodes, vec!["x"]);
+        assert!(cache.handle_file_modified(&edited, &options).is_empty());
+
+        cache.update_hashtags("a", vec!["t".to_string()]);
+        assert_eq!(
+            cache
+                .handle_file_modified(&edited, &options)
+                .updated_nodes
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn note_deltas_match_a_full_diff() {
+        let notes = || {
+            vec![
+                markdown_file("a", "[[b]] [[ghost]] [[shared]]"),
+                markdown_file("b", "[[shared]] [[c]]"),
+                markdown_file("c", "---\naliases: [see]\n---\n[[a]]"),
+            ]
+        };
+        let options = BuildOptions {
+            drop_excluded_links: true,
+            ..Default::default()
+        };
+
+        for edited in [
+            markdown_file("a", "[[c]] [[new]] [[shared]] [[a]]"),
+            markdown_file("a", "[[see]] [[b]] [[b]]"),
+            markdown_file("b", "---\ngraph: false\n---\n[[c]]"),
+            markdown_file("d", "[[ghost]] [[a]]"),
+        ] {
+            let mut cache = cache_of(notes());
+            let previous = cache.clone();
+            let delta = cache.handle_file_modified(&edited, &options);
+            assert_eq!(delta, previous.diff(&cache), "{}", edited.content);
+        }
+
+        for removed in ["a", "b", "c"] {
+            let mut cache = cache_of(notes());
+            let previous = cache.clone();
+            let delta = cache.handle_file_removed(removed, &options);
+            assert_eq!(delta, previous.diff(&cache), "{}", removed);
+        }
+    }
+
+    #[test]
+    fn removed_file_becomes_a_phantom_while_linked() {
+        let options = BuildOptions::default();
+        let mut cache = cache_of(vec![
+            markdown_file("a", "[[b]]"),
+            markdown_file("b", "[[c]]"),
+        ]);
+
+        let delta = cache.handle_file_removed("b", &options);
+        assert_eq!(delta.removed_nodes, vec!["c"]);
+        assert_eq!(
+            cache.get_node("b").unwrap().group.as_deref(),
+            Some("phantom")
+        );
+
+        assert!(cache
+            .handle_file_removed("b", &options)
+            .is_empty());
+        assert!(cache
+            .handle_file_removed("zz", &options)
+            .is_empty());
+    }
+
+    #[test]
+    fn complete_link_ranking() {
+        let cache = cache_of(vec![
+            markdown_file("project", "[[ghost]]"),
+            markdown_file("Projects archive", ""),
+            markdown_file("my project", ""),
+            markdown_file("pr", ""),
+            markdown_file("graph", ""),
+        ]);
+
+        assert_eq!(
+            cache.complete_link("pro", 10),
+            vec!["project", "Projects archive", "my project"]
+        );
+        assert_eq!(cache.complete_link("PR", 2), vec!["pr", "project"]);
+        assert_eq!(cache.complete_link("grh", 10), vec!["graph"]);
+        assert!(cache.complete_link("gho", 10).is_empty());
+        assert!(cache.complete_link("zzz", 10).is_empty());
+    }
+
+    #[test]
+    fn search_ranks_fuzzy_matches() {
+        let cache = cache_of(vec![
+            markdown_file("graph", ""),
+            markdown_file("geography", ""),
+            markdown_file("grh", ""),
+            markdown_file("other", ""),
+        ]);
+
+        let results = cache.search_notes("grh", 10);
+        let found: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+
+        assert_eq!(found, vec!["grh", "graph", "geography"]);
+        assert_eq!(cache.search_notes("grh", 1).len(), 1);
+    }
+
+    #[test]
+    fn pagerank_matches_hand_computed_ranks() {
+        let cache = cache_of(vec![
+            markdown_file("a", "[[b]] [[c]] [[b]]"),
+            markdown_file("b", "[[c]]"),
+            markdown_file("c", "[[a]]"),
+            markdown_file("d", ""),
+        ]);
+
+        let ranks = cache.pagerank(100, 0.85);
+        let rank = |id: &str| ranks.iter().find(|s| s.id == id).unwrap().score;
+
+        // The dangling note d spreads its rank evenly, so every note gets
+        // base = 0.15/4 + 0.85*d/4 with d = base. Then a = base + 0.85c,
+        // b = base + 0.425a and c = base + 0.425a + 0.85b.
+        let d = 0.0375 / (1.0 - 0.2125);
+        let a = (d + 0.85 * d + 0.85 * 0.85 * d) / (1.0 - 0.85 * 0.425 - 0.85 * 0.85 * 0.425);
+        let b = d + 0.425 * a;
+        let c = d + 0.425 * a + 0.85 * b;
+        for (id, expected) in [("a", a), ("b", b), ("c", c), ("d", d)] {
+            assert!(
+                (rank(id) - expected).abs() < 1e-9,
+                "{} {} {}",
+                id,
+                rank(id),
+                expected
+            );
+        }
+        assert!((ranks.iter().map(|s| s.score).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(ranks[0].id, "c");
+
+        assert!(cache
+            .pagerank(10, 0.0)
+            .iter()
+            .all(|s| (s.score - 0.25).abs() < 1e-12));
+        assert!(GraphCache::new().pagerank(5, 0.85).is_empty());
+    }
+
+    #[test]
+    fn tag_tree_counts() {
+        let cache = cache_of(vec![
+            markdown_file("n1", "#a/b #a"),
+            markdown_file("n2", "#a/c"),
+            markdown_file("n3", "#a #a/b"),
+            markdown_file("n4", "#x/y/z"),
+        ]);
+
+        let root = cache.get_tag_tree();
+
+        assert_eq!(root.total_count, 4);
+        let names: Vec<&str> = root.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "x"]);
+        let a = &root.children[0];
+        assert_eq!((a.note_count, a.total_count), (2, 3));
+        assert_eq!(a.children[0].path, "a/b");
+        assert_eq!(
+            (a.children[0].note_count, a.children[0].total_count),
+            (2, 2)
+        );
+        assert_eq!(
+            (a.children[1].note_count, a.children[1].total_count),
+            (1, 1)
+        );
+        let x = &root.children[1];
+        assert_eq!((x.note_count, x.total_count), (0, 1));
+        assert_eq!(x.children[0].children[0].path, "x/y/z");
+    }
+
+    #[test]
+    fn tag_clusters_include_untagged() {
+        let cache = cache_of(vec![
+            markdown_file("a", "#rust #web"),
+            markdown_file("b", "#web"),
+            markdown_file("c", "#Web #db [[ghost]]"),
+            markdown_file("d", "#db #rust"),
+            markdown_file("e", "plain"),
+            markdown_file("f", "#solo"),
+        ]);
+
+        let clusters = cache.get_tag_clusters();
+
+        assert_eq!(clusters["Web"], vec!["a", "b", "c"]);
+        assert_eq!(clusters["db"], vec!["d"]);
+        assert_eq!(clusters["solo"], vec!["f"]);
+        assert_eq!(clusters[UNTAGGED_CLUSTER], vec!["e"]);
+        assert_eq!(clusters.len(), 4);
+        assert_eq!(clusters.values().map(Vec::len).sum::<usize>(), 6);
+    }
+
+    #[test]
+    fn case_mismatch_links() {
+        let cache = cache_of(vec![
+            markdown_file("a", "[[note]] [[note]] [[Note]] [[ghost]]"),
+            markdown_file("Note", "[[A]]"),
+            markdown_file("b", "[[NOTE]]"),
+        ]);
+
+        let mismatches = cache.get_case_mismatch_links();
+        let found: Vec<(&str, &str, &str)> = mismatches
+            .iter()
+            .map(|m| (m.source.as_str(), m.link.as_str(), m.note.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("Note", "A", "a"),
+                ("a", "note", "Note"),
+                ("b", "NOTE", "Note")
+            ]
+        );
+    }
+
+    #[test]
+    fn phantom_clusters_all_phantom_vs_mixed() {
+        let mut graph = build_graph_with(
+            vec![
+                markdown_file("outline", "[[x]] [[y]]"),
+                markdown_file("b", ""),
+                markdown_file("c", "[[lone]]"),
+            ],
+            &BuildOptions::default(),
+        );
+        for id in ["p1", "p2", "p3", "q"] {
+            graph.nodes.push(phantom_node(id, 0, &BuildOptions::default()));
+        }
+        for (from, to) in [("p2", "p1"), ("p3", "p2"), ("q", "b")] {
+            graph.edges.push(Edge {
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+        let cache = GraphCache::from_graph(graph);
+
+        // p1-p3 link each other; q's link to the real note b doesn't join it
+        // to b, and x, y and lone are only linked from real notes.
+        assert_eq!(
+            cache.get_phantom_clusters(),
+            vec![
+                vec!["lone".to_string()],
+                vec!["p1".to_string(), "p2".to_string(), "p3".to_string()],
+                vec!["q".to_string()],
+                vec!["x".to_string()],
+                vec!["y".to_string()],
+            ]
+        );
+
+        assert!(cache_of(vec![markdown_file("a", "[[b]]"), markdown_file("b", "")])
+            .get_phantom_clusters()
+            .is_empty());
+    }
+}
@@ -20,12 +20,28 @@
 //! operations without freezing the UI. However, long-running operations should
 //! still consider emitting progress events.
 
-use crate::config::{AppConfig, AppState};
-use crate::graph::{scan_and_build_graph, GraphData};
+use crate::cache::{
+    AdjacencyMatrix, BrokenLink, CaseMismatchLink, EdgeSet, GraphDelta, LinkCounts, NodeRank,
+    NodeScore, OutgoingLink, SearchResult, TagNode,
+};
+use crate::config::{AppConfig, AppState, ConfigField, ConfigSource};
+use crate::events;
+use crate::graph::{self, D3Graph, EdgeTagFilter, GraphData, TagMatch};
+use crate::helpers;
+use crate::parser::{
+    self, FrontmatterValue, Heading, LinkSyntax, ParsedContentDto, ParsedPositions,
+};
+use crate::scanner::{self, scan_root, strip_bom, ExtCount, MarkdownFile};
 use crate::templates;
-use std::path::PathBuf;
-use std::process::Command;
-use tauri::State;
+use crate::undo::{FileChange, Operation};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, State};
 
 /// Scans a directory for markdown files and builds a graph.
 ///
@@ -33,13 +49,35 @@ use tauri::State;
 /// markdown files and constructing a graph of wiki-link connections. It's typically
 /// called when the application starts or when the user changes the root directory.
 ///
+/// The resulting graph also replaces the contents of the in-memory graph cache,
+/// which backs the analytical commands (e.g. `get_hubs`). Once the cache is
+/// replaced, a single `rescan-complete` event with the new node and edge counts
+/// is emitted. The new cache is also persisted so the next launch can skip the
+/// initial scan (see `AppState::rescan`).
+///
+/// Only files accepted by the configured `include_patterns` and `ignore_patterns`
+/// are scanned. Node colors are resolved from the configured `group_colors`.
+/// The configured `root_dirs` are scanned along with `path` and merged into the
+/// same graph; notes in `path` win name collisions.
+///
+/// Scanning a different vault than the configured `root_dir` stops the periodic
+/// rescan (see `AppState::start_periodic_rescan`), which would otherwise replace
+/// the graph with the configured vault again, and the file watcher of the
+/// configured roots (see `AppState::start_vault_watch`).
+///
+/// With `max_nodes` configured, a larger graph is returned limited to its
+/// highest-value nodes and flagged as `truncated` (see `graph::truncate_graph`).
+/// The cache and the `rescan-complete` event always describe the full graph.
+///
 /// # Arguments
 ///
 /// * `path` - File system path to the directory containing markdown files
+/// * `app` - Application handle used to emit the `rescan-complete` event
+/// * `state` - Tauri managed state holding the configuration and graph cache
 ///
 /// # Returns
 ///
-/// * `Ok(GraphData)` - Complete graph with all nodes and edges
+/// * `Ok(GraphData)` - Complete graph with all nodes and edges, unless truncated
 /// * `Err(String)` - Error message describing what went wrong
 ///
 /// # Errors
@@ -48,6 +86,7 @@ use tauri::State;
 /// - The specified path doesn't exist or isn't accessible
 /// - File system permissions prevent reading directories or files
 /// - Any markdown file contains invalid UTF-8 encoding
+/// - A configured include or ignore pattern is not a valid glob
 ///
 /// # Performance
 ///
@@ -61,10 +100,124 @@ use tauri::State;
 /// import { invoke } from '@tauri-apps/api/core';
 ///
 /// const graphData = await invoke('scan_folder', { path: '/path/to/notes' });
+/// if (graphData.truncated) console.warn('Showing only the most linked notes');
+/// ```
+#[tauri::command]
+pub fn scan_folder(path: String, app: AppHandle, state: State<AppState>) -> Result<GraphData, String> {
+    let config = state.get_config();
+    let roots = config.scan_roots(&path);
+
+    if roots != config.configured_roots() {
+        state.stop_periodic_rescan();
+        state.stop_vault_watch();
+    }
+
+    let graph = state.rescan(&roots, &app)?;
+
+    Ok(limit_graph(&config, graph))
+}
+
+/// Returns the graph currently held in the cache.
+///
+/// At startup the cache is restored from disk when the vault is unchanged, or
+/// rebuilt by a full scan otherwise, so the frontend can display the graph
+/// without calling `scan_folder` first. This runs in the background, so the
+/// graph is empty until the `graph-ready` event arrives. It also stays empty if
+/// no `root_dir` is configured and nothing has been scanned yet.
+///
+/// Like `scan_folder`, the graph is limited to `max_nodes` nodes if configured.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(GraphData)` - The cached graph (possibly empty or truncated)
+/// * `Err(String)` - Error message if the graph could not be retrieved
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+/// import { listen } from '@tauri-apps/api/event';
+///
+/// await listen('graph-ready', async () => render(await invoke('get_cached_graph')));
+/// const cached = await invoke('get_cached_graph');
+/// if (cached.nodes.length > 0) render(cached);
+/// ```
+#[tauri::command]
+pub fn get_cached_graph(state: State<AppState>) -> Result<GraphData, String> {
+    let graph = state.lock_cache().graph().clone();
+
+    Ok(limit_graph(&state.get_config(), graph))
+}
+
+/// Applies the configured `max_nodes` limit to a graph sent to the frontend.
+fn limit_graph(config: &AppConfig, graph: GraphData) -> GraphData {
+    match config.max_nodes {
+        Some(max_nodes) => graph::truncate_graph(graph, max_nodes),
+        None => graph,
+    }
+}
+
+/// Remembers the current graph so later changes can be diffed against it.
+///
+/// Takes a snapshot of the graph cache; a subsequent `diff_since_snapshot`
+/// reports everything that changed since (e.g. after a rescan). Taking a new
+/// snapshot replaces the previous one.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(())` - The snapshot was taken
+/// * `Err(String)` - Error message if the snapshot could not be taken
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('snapshot_graph');
+/// ```
+#[tauri::command]
+pub fn snapshot_graph(state: State<AppState>) -> Result<(), String> {
+    state.take_snapshot();
+    println!("[Snapshot] Graph snapshot taken");
+    Ok(())
+}
+
+/// Reports what changed in the graph since the last `snapshot_graph` call.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache and snapshot
+///
+/// # Returns
+///
+/// * `Ok(GraphDelta)` - Nodes and edges added, removed or updated since the snapshot
+/// * `Err(String)` - Error message if no snapshot has been taken yet
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('snapshot_graph');
+/// await invoke('scan_folder', { path });
+/// const delta = await invoke('diff_since_snapshot');
+/// console.log(`${delta.added_nodes.length} new notes`);
 /// ```
 #[tauri::command]
-pub fn scan_folder(path: String) -> Result<GraphData, String> {
-    scan_and_build_graph(&path)
+pub fn diff_since_snapshot(state: State<AppState>) -> Result<GraphDelta, String> {
+    let snapshot = state
+        .get_snapshot()
+        .ok_or_else(|| "No graph snapshot taken".to_string())?;
+
+    Ok(snapshot.diff(&state.lock_cache()))
 }
 
 /// Retrieves the current application configuration.
@@ -99,15 +252,46 @@ pub fn get_config(state: State<AppState>) -> AppConfig {
     state.get_config()
 }
 
+/// Reports where the current configuration came from.
+///
+/// Returns the configuration file resolved at startup, which search location
+/// produced it (`cli`, `exe_dir`, `cwd` or `default`) and whether it was parsed
+/// successfully. This complements `get_config` when debugging user reports such
+/// as "my config.json is ignored".
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state containing the recorded configuration source
+///
+/// # Returns
+///
+/// * `Ok(ConfigSource)` - Path, origin and load status of the configuration file
+/// * `Err(String)` - Error message if the source could not be determined
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const source = await invoke('get_config_source');
+/// console.log(source.origin, source.path, source.loaded);
+/// ```
+#[tauri::command]
+pub fn get_config_source(state: State<AppState>) -> Result<ConfigSource, String> {
+    Ok(state.config_source.clone())
+}
+
 /// Opens a markdown file in the nvim editor by node ID.
 ///
 /// Launches an external nvim process to edit the specified markdown file. This
 /// command is typically invoked when the user double-clicks a node in the graph
 /// visualization.
 ///
-/// The function constructs the file path from the node ID and root directory.
-/// If the file doesn't exist and a phantom node template is configured, the file
-/// will be automatically created from the template before opening it in nvim.
+/// An existing note is opened at the path recorded in the graph cache, so moved
+/// notes are found; other node IDs map to `{root_dir}/{node_id}.md` (see
+/// `note_write_path`). If the file doesn't exist and a phantom node template is
+/// configured, the file will be automatically created from the template before
+/// opening it in nvim.
 ///
 /// # Arguments
 ///
@@ -129,13 +313,16 @@ pub fn get_config(state: State<AppState>) -> AppConfig {
 ///
 /// # File Path Construction
 ///
-/// The file path is constructed as: `{root_dir}/{node_id}.md`
+/// Existing notes are opened at the path recorded in the graph cache, so notes
+/// in subdirectories or moved by `organize_by_tag` are found. Other notes are
+/// created as `{root_dir}/{node_id}.md` (see `note_write_path`).
 ///
 /// # Phantom Node Creation
 ///
 /// When a file doesn't exist:
 /// 1. Checks if `template_phantom_node` is configured
-/// 2. Creates the file from the template with variable substitution
+/// 2. Creates the file from the template with variable substitution and adds
+///    it to the graph cache
 /// 3. Opens the newly created file in nvim
 ///
 /// # Platform-Specific Behavior
@@ -147,11 +334,17 @@ pub fn get_config(state: State<AppState>) -> AppConfig {
 /// ## Unix/Linux/macOS
 /// Directly spawns `nvim <file>` as a child process.
 ///
+/// On every platform, a configured `terminal_command` is put in front of
+/// `nvim <file>`, so nvim gets a terminal window even when the app was started
+/// from a GUI (see `editor_command`).
+///
 /// # Process Management
 ///
-/// The spawned nvim process runs independently of the Tauri application. The
-/// command returns immediately after spawning without waiting for nvim to close.
-/// This is a fire-and-forget operation.
+/// The command returns immediately after spawning without waiting for nvim to
+/// close; a background thread reaps the process once it exits. With
+/// `detach_editor` set, the editor does not inherit the app's standard streams
+/// and, on Unix, runs in its own process group, so it is not killed together
+/// with the app.
 ///
 /// # Frontend Usage
 ///
@@ -171,12 +364,7 @@ pub fn open_file(node_id: String, state: State<AppState>) -> Result<(), String>
 
     let config = state.get_config();
 
-    let root_dir = config
-        .root_dir
-        .ok_or_else(|| "Root directory not configured".to_string())?;
-
-    let mut file_path = PathBuf::from(&root_dir);
-    file_path.push(format!("{}.md", node_id));
+    let file_path = note_write_path(&config, &state, &node_id)?;
 
     let file_path_str = file_path
         .to_str()
@@ -187,9 +375,16 @@ pub fn open_file(node_id: String, state: State<AppState>) -> Result<(), String>
     if !file_path.exists() {
         println!("[OpenFile] File does not exist, attempting to create from template");
 
-        if let Some(template_path) = config.template_phantom_node {
+        if let Some(template_path) = &config.template_phantom_node {
             println!("[OpenFile] Creating file from template: {}", template_path);
-            templates::create_from_template(&template_path, file_path_str)?;
+            templates::create_from_template(
+                template_path,
+                file_path_str,
+                &config.template_variables,
+            )?;
+            state
+                .lock_cache()
+                .add_file(&scanner::read_markdown_file(&file_path)?, &config.build_options());
             println!("[OpenFile] File created successfully: {}", file_path_str);
         } else {
             return Err(format!(
@@ -199,26 +394,92 @@ pub fn open_file(node_id: String, state: State<AppState>) -> Result<(), String>
         }
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        Command::new("cmd")
-            .args(&["/C", "start", "nvim", file_path_str])
-            .spawn()
-            .map_err(|e| format!("Error launching nvim: {}", e))?;
-    }
+    let (program, args) = editor_command(
+        std::env::consts::OS,
+        file_path_str,
+        config.terminal_command.as_deref(),
+    );
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        Command::new("nvim")
-            .arg(file_path_str)
-            .spawn()
-            .map_err(|e| format!("Error launching nvim: {}", e))?;
+    println!("[OpenFile] Running: {} {:?}", program, args);
+
+    let mut command = Command::new(&program);
+    command.args(&args);
+
+    if config.detach_editor {
+        detach(&mut command);
     }
 
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Error launching {}: {}", program, e))?;
+
+    std::thread::spawn(move || child.wait());
+
     println!("[OpenFile] File opened in nvim");
     Ok(())
 }
 
+/// Assembles the command opening a file in nvim on an OS.
+///
+/// Kept separate from `open_file` so the command lines can be checked without
+/// launching anything.
+///
+/// # Arguments
+///
+/// * `os` - Operating system name as in `std::env::consts::OS` (e.g. `"windows"`)
+/// * `file_path` - File to open
+/// * `terminal_command` - Optional terminal emulator command (e.g.
+///   `x-terminal-emulator -e`), split at whitespace and put in front of `nvim <file>`.
+///   Blank commands are ignored.
+///
+/// # Returns
+///
+/// The program to run and its arguments.
+pub fn editor_command(
+    os: &str,
+    file_path: &str,
+    terminal_command: Option<&str>,
+) -> (String, Vec<String>) {
+    let mut words: Vec<String> = terminal_command
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+
+    words.push("nvim".to_string());
+    words.push(file_path.to_string());
+
+    if os == "windows" {
+        let mut args = vec!["/C".to_string(), "start".to_string()];
+        args.extend(words);
+
+        return ("cmd".to_string(), args);
+    }
+
+    let program = words.remove(0);
+
+    (program, words)
+}
+
+/// Detaches a command from the app process.
+///
+/// The child gets no standard streams from the app and, on Unix, is placed in
+/// its own process group so signals sent to the app's group (e.g. when its
+/// terminal closes) don't reach it.
+fn detach(command: &mut Command) {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        command.process_group(0);
+    }
+}
+
 /// Creates a markdown file from a phantom node using a template.
 ///
 /// When a phantom node (a referenced but non-existent file) is selected, this
@@ -228,11 +489,13 @@ pub fn open_file(node_id: String, state: State<AppState>) -> Result<(), String>
 /// # Arguments
 ///
 /// * `node_name` - Name of the node/file to create (without .md extension)
+/// * `dry_run` - If true, only renders the template and returns the content that
+///   would be written; nothing is created and the target file isn't checked
 /// * `state` - Tauri managed state containing the application configuration
 ///
 /// # Returns
 ///
-/// * `Ok(String)` - Full path to the created file
+/// * `Ok(String)` - Full path to the created file, or the rendered content on a dry run
 /// * `Err(String)` - Error message describing what went wrong
 ///
 /// # Errors
@@ -241,7 +504,7 @@ pub fn open_file(node_id: String, state: State<AppState>) -> Result<(), String>
 /// - No `root_dir` is configured (nowhere to create the file)
 /// - No `template_phantom_node` is configured (no template to use)
 /// - The template file doesn't exist or can't be read
-/// - The target file already exists (won't overwrite)
+/// - The target file already exists (won't overwrite; not checked on a dry run)
 /// - File system permissions prevent file creation
 ///
 /// # Template Variables
@@ -249,6 +512,10 @@ pub fn open_file(node_id: String, state: State<AppState>) -> Result<(), String>
 /// The template file can contain these placeholders:
 /// - `{{date}}` - Replaced with current date in YYYY-MM-DD format
 /// - `{{week}}` - Replaced with current ISO week number (1-53)
+/// - `{{uuid}}` - Replaced with a random v4 UUID
+/// - `{{title}}` - Replaced with the node name
+/// - `{{slug}}` - Replaced with the node name as a lowercase, dash-separated slug
+/// - `{{name}}` - Any custom variable configured in `template_variables`
 ///
 /// # Example Template
 ///
@@ -269,7 +536,8 @@ pub fn open_file(node_id: String, state: State<AppState>) -> Result<(), String>
 ///
 /// try {
 ///   const filePath = await invoke('create_phantom_node', {
-///     nodeName: 'My New Note'
+///     nodeName: 'My New Note',
+///     dryRun: false
 ///   });
 ///   console.log('Created file:', filePath);
 /// } catch (error) {
@@ -277,29 +545,4408 @@ pub fn open_file(node_id: String, state: State<AppState>) -> Result<(), String>
 /// }
 /// ```
 #[tauri::command]
-pub fn create_phantom_node(node_name: String, state: State<AppState>) -> Result<String, String> {
+pub fn create_phantom_node(
+    node_name: String,
+    dry_run: bool,
+    state: State<AppState>,
+) -> Result<String, String> {
     println!("[CreatePhantomNode] Creating node: {}", node_name);
 
     let config = state.get_config();
 
-    let root_dir = config
-        .root_dir
-        .ok_or_else(|| "Root directory not configured".to_string())?;
+    let file_path = note_path(&config, &node_name)?;
 
     let template_path = config
         .template_phantom_node
         .ok_or_else(|| "Template for phantom nodes not configured".to_string())?;
 
-    let mut file_path = PathBuf::from(&root_dir);
-    file_path.push(format!("{}.md", node_name));
-
     let file_path_str = file_path
         .to_str()
         .ok_or_else(|| "Invalid file path".to_string())?;
 
-    templates::create_from_template(&template_path, file_path_str)?;
+    if dry_run {
+        println!("[CreatePhantomNode] Dry run, rendering template only");
+        return templates::create_from_template_preview(
+            &template_path,
+            file_path_str,
+            &config.template_variables,
+        );
+    }
+
+    templates::create_from_template(&template_path, file_path_str, &config.template_variables)?;
+
+    state.record_operation(Operation {
+        description: format!("Create note {}", node_name),
+        changes: vec![FileChange::Created { path: file_path.clone() }],
+    });
 
     println!("[CreatePhantomNode] Created file: {}", file_path_str);
 
     Ok(file_path_str.to_string())
 }
+
+/// Returns the most-connected (hub) notes of the cached graph.
+///
+/// Ranks all real notes by their total degree (incoming + outgoing wiki-links)
+/// and returns the top `limit` entries. Phantom nodes are never included. This
+/// command powers the "key notes" panel of the frontend.
+///
+/// # Arguments
+///
+/// * `limit` - Maximum number of notes to return
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<NodeRank>)` - Notes ordered by descending degree, ties broken by name
+/// * `Err(String)` - Error message if the ranking could not be computed
+///
+/// # Cache Dependency
+///
+/// The ranking is computed from the graph cache, which is populated by
+/// `scan_folder`. Before the first scan the result is empty.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const hubs = await invoke('get_hubs', { limit: 10 });
+/// hubs.forEach(hub => console.log(hub.id, hub.score));
+/// ```
+#[tauri::command]
+pub fn get_hubs(limit: usize, state: State<AppState>) -> Result<Vec<NodeRank>, String> {
+    Ok(state.lock_cache().get_hubs(limit))
+}
+
+/// Returns the sink notes of the cached graph.
+///
+/// Sink notes receive wiki-links from other notes but contain no outgoing
+/// wiki-links themselves. Orphans (notes with no links in either direction) and
+/// phantom nodes are not reported.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - Sorted node IDs of all sink notes
+/// * `Err(String)` - Error message if the query could not be answered
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const sinks = await invoke('get_sinks');
+/// ```
+#[tauri::command]
+pub fn get_sinks(state: State<AppState>) -> Result<Vec<String>, String> {
+    Ok(state.lock_cache().get_sinks())
+}
+
+/// Reports all wiki-links that don't resolve to an existing note.
+///
+/// Produces a link-health report from the graph cache: one entry per source note
+/// and missing link target. The same resolution rules as graph construction are
+/// applied, so every reported link corresponds to a phantom node in the graph.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<BrokenLink>)` - Broken links sorted by source note and link text
+/// * `Err(String)` - Error message if the report could not be produced
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const broken = await invoke('check_links');
+/// broken.forEach(b => console.log(`${b.source} -> [[${b.link}]]`));
+/// ```
+#[tauri::command]
+pub fn check_links(state: State<AppState>) -> Result<Vec<BrokenLink>, String> {
+    Ok(state.lock_cache().get_broken_links())
+}
+
+/// Renames a hashtag in every note of the cached graph.
+///
+/// Rewrites `#old` to `#new` in the content of each note on disk and updates the
+/// hashtags stored in the graph cache, so tag queries reflect the rename without
+/// a full rescan. Hierarchical children are renamed along with their parent
+/// (`#old/sub` becomes `#new/sub`), while tags that merely start with the same
+/// characters (`#oldish`) are left untouched. Tags in the frontmatter `tags:` key
+/// are renamed too, while `#` inside wiki-links and URLs is not a tag and stays
+/// (see `parser::rename_hashtag`).
+///
+/// # Arguments
+///
+/// * `old` - Tag to rename, with or without the leading `#`; matched
+///   case-insensitively, so renaming `todo` also rewrites `#Todo`
+/// * `new` - New tag name, with or without the leading `#`
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(usize)` - Number of files that were changed
+/// * `Err(String)` - Error message if a tag name is invalid or a file couldn't be rewritten
+///
+/// # Errors
+///
+/// Returns an error if either tag name is not a valid tag (word characters with
+/// optional `/` hierarchy separators), or if a note can't be read or written.
+/// Notes processed before the failing one keep their renamed content. The
+/// rename can be reverted with `undo_last`.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const changed = await invoke('rename_tag', { old: 'todo', new: 'tasks' });
+/// console.log(`Renamed tag in ${changed} notes`);
+/// ```
+#[tauri::command]
+pub fn rename_tag(old: String, new: String, state: State<AppState>) -> Result<usize, String> {
+    let old = old.trim_start_matches('#');
+    let new = new.trim_start_matches('#');
+
+    for tag in [old, new] {
+        if !parser::is_valid_tag(tag) {
+            return Err(format!("Invalid tag name: {}", tag));
+        }
+    }
+
+    let notes: Vec<(String, String)> = state
+        .lock_cache()
+        .graph()
+        .nodes
+        .iter()
+        .filter(|node| !node.file_path.is_empty())
+        .map(|node| (node.id.clone(), node.file_path.clone()))
+        .collect();
+
+    let mut changes = Vec::new();
+    let syntax = state.get_config().link_syntax();
+    let result = rename_tag_in_notes(notes, old, new, &syntax, &state, &mut changes);
+
+    state.record_operation(Operation {
+        description: format!("Rename tag #{} to #{}", old, new),
+        changes,
+    });
+
+    let changed = result?;
+
+    println!("[Tags] Renamed #{} to #{} in {} files", old, new, changed);
+
+    Ok(changed)
+}
+
+/// Rewrites a hashtag in the given notes, recording each rewritten file in `changes`.
+///
+/// Files rewritten before an error are still recorded, so they can be undone.
+fn rename_tag_in_notes(
+    notes: Vec<(String, String)>,
+    old: &str,
+    new: &str,
+    syntax: &LinkSyntax,
+    state: &AppState,
+    changes: &mut Vec<FileChange>,
+) -> Result<usize, String> {
+    for (node_id, file_path) in notes {
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| format!("Error reading file {:?}: {}", file_path, e))?;
+
+        let Some(renamed) = parser::rename_hashtag(&content, old, new, syntax) else {
+            continue;
+        };
+
+        fs::write(&file_path, &renamed)
+            .map_err(|e| format!("Error writing file {:?}: {}", file_path, e))?;
+
+        changes.push(FileChange::Modified {
+            path: PathBuf::from(&file_path),
+            previous: content.into_bytes(),
+        });
+
+        let hashtags = parser::parse_markdown(strip_bom(&renamed)).hashtags;
+        state.lock_cache().update_hashtags(&node_id, hashtags);
+    }
+
+    Ok(changes.len())
+}
+
+/// Returns the notes carrying a hashtag.
+///
+/// Answered from the tag index of the graph cache, so the query doesn't depend
+/// on the number of notes in the vault.
+///
+/// # Arguments
+///
+/// * `tag` - Tag to look up, with or without the leading `#`; matched
+///   case-insensitively, so `Todo` finds notes tagged `todo`
+/// * `include_children` - If `true`, notes with hierarchical child tags match too
+///   (querying `project` also returns notes tagged `project/frontend`)
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - Sorted IDs of the matching notes (empty if none match)
+/// * `Err(String)` - Error message if the query could not be answered
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const notes = await invoke('get_notes_by_tag', { tag: 'project', includeChildren: true });
+/// ```
+#[tauri::command]
+pub fn get_notes_by_tag(
+    tag: String,
+    include_children: bool,
+    state: State<AppState>,
+) -> Result<Vec<String>, String> {
+    let tag = tag.trim_start_matches('#');
+
+    Ok(state.lock_cache().get_notes_by_tag(tag, include_children))
+}
+
+/// Returns every hashtag used in the vault, e.g. for tag autocompletion.
+///
+/// Answered from the tag index of the graph cache (see `GraphCache::get_all_tags`).
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - Tag names without the `#`, sorted alphabetically; tags
+///   differing only in case are listed separately
+/// * `Err(String)` - Error message if the tags could not be listed
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const tags = await invoke('get_all_tags');
+/// ```
+#[tauri::command]
+pub fn get_all_tags(state: State<AppState>) -> Result<Vec<String>, String> {
+    Ok(state.lock_cache().get_all_tags())
+}
+
+/// Reads a note's content for the preview panel.
+///
+/// Locates the note's file through the graph cache (see `note_file_path`) and
+/// returns its content with the first `previewer.offset` lines removed, so
+/// fixed-size headers can be hidden from previews. With `previewer.skip_frontmatter` enabled, a leading
+/// frontmatter block is removed first, and the offset applies to the body.
+///
+/// # Arguments
+///
+/// * `node_id` - The ID/name of the note (without .md extension)
+/// * `state` - Tauri managed state containing the application configuration
+///
+/// # Returns
+///
+/// * `Ok(String)` - Note content after skipping the configured offset
+/// * `Err(String)` - Error message if the note could not be read
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The note isn't in the graph cache or is a phantom node
+/// - The note's file lies outside every configured root directory
+/// - The file can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const content = await invoke('read_note', { nodeId: 'MyNote' });
+/// ```
+#[tauri::command]
+pub fn read_note(node_id: String, state: State<AppState>) -> Result<String, String> {
+    read_preview(&state.get_config(), &state, &node_id)
+}
+
+/// Maximum number of backlink snippets returned by `read_note_with_context`.
+const MAX_BACKLINK_SNIPPETS: usize = 20;
+
+/// Maximum length of a backlink snippet in characters; longer lines are truncated.
+const MAX_SNIPPET_CHARS: usize = 200;
+
+/// A line from another note that links to the previewed note.
+///
+/// # Fields
+///
+/// * `source` - Node ID of the referencing note
+/// * `line` - Trimmed line containing the link, truncated to `MAX_SNIPPET_CHARS`
+#[derive(Debug, Clone, Serialize)]
+pub struct BacklinkSnippet {
+    pub source: String,
+    pub line: String,
+}
+
+/// A note's preview content together with its "referenced by" context.
+///
+/// # Fields
+///
+/// * `content` - Note content, as returned by `read_note`
+/// * `backlinks` - Lines from other notes linking to this note
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteWithContext {
+    pub content: String,
+    pub backlinks: Vec<BacklinkSnippet>,
+}
+
+/// Reads a note for preview together with snippets of the notes linking to it.
+///
+/// The content is identical to `read_note`. Referencing notes are looked up in
+/// the graph cache and read from disk; every line containing a wiki-link to the
+/// note becomes one snippet. At most `MAX_BACKLINK_SNIPPETS` snippets are
+/// returned (ordered by source note, then line), each truncated to
+/// `MAX_SNIPPET_CHARS` characters. Self-links are not reported.
+///
+/// # Arguments
+///
+/// * `node_id` - The ID/name of the note (without .md extension)
+/// * `state` - Tauri managed state containing the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(NoteWithContext)` - Note content and backlink snippets
+/// * `Err(String)` - Error message if the note could not be read
+///
+/// # Errors
+///
+/// Fails under the same conditions as `read_note`. Referencing notes that can't
+/// be read (e.g. deleted since the last scan) are skipped.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { content, backlinks } = await invoke('read_note_with_context', { nodeId: 'MyNote' });
+/// backlinks.forEach(b => console.log(`${b.source}: ${b.line}`));
+/// ```
+#[tauri::command]
+pub fn read_note_with_context(
+    node_id: String,
+    state: State<AppState>,
+) -> Result<NoteWithContext, String> {
+    let config = state.get_config();
+    let content = read_preview(&config, &state, &node_id)?;
+    let syntax = config.link_syntax();
+
+    let sources: Vec<(String, String)> = state
+        .lock_cache()
+        .get_backlink_sources(&node_id)
+        .into_iter()
+        .map(|node| (node.id.clone(), node.file_path.clone()))
+        .collect();
+
+    let mut backlinks = Vec::new();
+
+    for (source, file_path) in sources {
+        let Ok(source_content) = fs::read_to_string(&file_path) else {
+            println!("[ReadNote] Skipping unreadable backlink source: {}", file_path);
+            continue;
+        };
+
+        for line in parser::lines_linking_to(strip_bom(&source_content), &node_id, &syntax) {
+            if backlinks.len() == MAX_BACKLINK_SNIPPETS {
+                return Ok(NoteWithContext { content, backlinks });
+            }
+
+            backlinks.push(BacklinkSnippet {
+                source: source.clone(),
+                line: truncate_chars(line, MAX_SNIPPET_CHARS),
+            });
+        }
+    }
+
+    Ok(NoteWithContext { content, backlinks })
+}
+
+/// A note together with its last modification time.
+///
+/// # Fields
+///
+/// * `id` - Node ID of the note
+/// * `modified` - Last modification time in milliseconds since the Unix epoch
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentNote {
+    pub id: String,
+    pub modified: u64,
+}
+
+/// Lists the most recently modified notes.
+///
+/// Modification times are read lazily from the file system for every real note
+/// in the graph cache, so edits made since the last scan are taken into
+/// account. Notes whose metadata can't be read (e.g. deleted since the last
+/// scan) are skipped.
+///
+/// # Arguments
+///
+/// * `limit` - Maximum number of notes to return
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<RecentNote>)` - Up to `limit` notes, newest first (ties ordered by ID)
+/// * `Err(String)` - Error message if the list could not be produced
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const recent = await invoke('get_recent_notes', { limit: 10 });
+/// recent.forEach(n => console.log(n.id, new Date(n.modified)));
+/// ```
+#[tauri::command]
+pub fn get_recent_notes(limit: usize, state: State<AppState>) -> Result<Vec<RecentNote>, String> {
+    let mut recent = note_modified_times(&state);
+
+    recent.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.id.cmp(&b.id)));
+    recent.truncate(limit);
+
+    Ok(recent)
+}
+
+/// Reads the modification time of every real note in the graph cache.
+///
+/// Notes whose metadata can't be read (e.g. deleted since the last scan) are
+/// skipped. The result is in graph order.
+fn note_modified_times(state: &AppState) -> Vec<RecentNote> {
+    let notes: Vec<(String, String)> = state
+        .lock_cache()
+        .graph()
+        .nodes
+        .iter()
+        .filter(|node| !node.file_path.is_empty())
+        .map(|node| (node.id.clone(), node.file_path.clone()))
+        .collect();
+
+    notes
+        .into_iter()
+        .filter_map(|(id, file_path)| {
+            let modified = fs::metadata(&file_path).and_then(|m| m.modified()).ok()?;
+            let modified = modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as u64;
+
+            Some(RecentNote { id, modified })
+        })
+        .collect()
+}
+
+/// Appends a line of text to a note, creating the note if it doesn't exist.
+///
+/// Intended for journaling workflows (e.g. a daily log) where short entries are
+/// appended to the end of an existing note. The text goes through the same
+/// variable substitution as templates, so entries like `- {{date}}: done` get
+/// a timestamp.
+///
+/// # Arguments
+///
+/// * `node_id` - The ID/name of the note (without .md extension)
+/// * `text` - Entry to append; written on a new line
+/// * `state` - Tauri managed state containing the application configuration
+///
+/// # Returns
+///
+/// * `Ok(())` - The entry was appended
+/// * `Err(String)` - Error message if the operation failed
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The note is missing and no `root_dir` is configured
+/// - The note is missing and creating it from the template fails
+/// - The note cannot be opened for appending or written to
+///
+/// # Missing Notes
+///
+/// Existing notes are found through the graph cache (see `note_write_path`). If
+/// the note doesn't exist, it is first created as `{root_dir}/{node_id}.md` from
+/// `template_phantom_node` when a template is configured, or as an empty file
+/// otherwise. The graph cache is updated with the note's new content.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('append_to_note', { nodeId: 'Journal', text: '- {{date}}: shipped it' });
+/// ```
+#[tauri::command]
+pub fn append_to_note(node_id: String, text: String, state: State<AppState>) -> Result<(), String> {
+    println!("[AppendToNote] Appending to node: {}", node_id);
+
+    let config = state.get_config();
+
+    let file_path = note_write_path(&config, &state, &node_id)?;
+
+    let file_path_str = file_path
+        .to_str()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+
+    let change = match fs::read(&file_path) {
+        Ok(previous) => FileChange::Modified { path: file_path.clone(), previous },
+        Err(_) => FileChange::Created { path: file_path.clone() },
+    };
+
+    if !file_path.exists() {
+        if let Some(template_path) = &config.template_phantom_node {
+            println!("[AppendToNote] File does not exist, creating from template: {}", template_path);
+            templates::create_from_template(template_path, file_path_str, &config.template_variables)?;
+        }
+    }
+
+    let entry = helpers::replace_variables_with(&text, Some(&node_id), &config.template_variables);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&file_path)
+        .map_err(|e| format!("Failed to open file '{}': {}", file_path_str, e))?;
+
+    writeln!(file)
+        .and_then(|_| write!(file, "{}", entry))
+        .map_err(|e| format!("Failed to append to file '{}': {}", file_path_str, e))?;
+
+    state
+        .lock_cache()
+        .add_file(&scanner::read_markdown_file(&file_path)?, &config.build_options());
+
+    state.record_operation(Operation {
+        description: format!("Append to note {}", node_id),
+        changes: vec![change],
+    });
+
+    println!("[AppendToNote] Entry appended to: {}", file_path_str);
+    Ok(())
+}
+
+/// Reads a note for preview, skipping the frontmatter (if `previewer.skip_frontmatter`
+/// is set) and then the configured `previewer.offset` lines.
+///
+/// The note is located with `note_file_path`.
+///
+/// # Errors
+///
+/// Returns an error if the note isn't a cached real note, its path can't be
+/// resolved inside `root_dir` or the file can't be read.
+fn read_preview(config: &AppConfig, state: &AppState, node_id: &str) -> Result<String, String> {
+    read_preview_at(config, &note_file_path(config, state, node_id)?)
+}
+
+/// Reads the file at `path` for preview, like `read_preview`.
+///
+/// # Errors
+///
+/// Returns an error if the path can't be resolved inside a configured root
+/// (see `ensure_inside_root`) or the file can't be read.
+fn read_preview_at(config: &AppConfig, path: &Path) -> Result<String, String> {
+    let file_path = ensure_inside_root(config, path)?;
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Error reading file {:?}: {}", file_path, e))?;
+    let mut content = strip_bom(&content);
+
+    if config.previewer.skip_frontmatter {
+        content = parser::split_frontmatter(content).1;
+    }
+
+    Ok(skip_lines(content, config.previewer.offset.unwrap_or(0)).to_string())
+}
+
+/// Builds the path a new note is created at from its node ID.
+///
+/// The path is constructed as `{root}/{node_id}.md` in the primary root (see
+/// `AppConfig::configured_roots`). Only used for notes that don't exist yet;
+/// existing notes are located through the graph cache with `note_file_path`,
+/// since they may live in a subdirectory or another root.
+///
+/// A node ID may name a subfolder like `people/Alice`, but every component must
+/// be a plain name: `.`, `..` and absolute paths are rejected, so an uncached
+/// node ID like `../secret` can't write outside the root.
+///
+/// # Errors
+///
+/// Returns an error if the node ID is not a valid note path or no root
+/// directory is configured.
+fn note_path(config: &AppConfig, node_id: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(node_id);
+
+    if node_id.trim().is_empty()
+        || !relative.components().all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(format!("Invalid note name: {:?}", node_id));
+    }
+
+    let root_dir = config
+        .configured_roots()
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Root directory not configured".to_string())?;
+
+    let mut file_path = PathBuf::from(root_dir);
+    file_path.push(format!("{}.md", node_id));
+
+    Ok(file_path)
+}
+
+/// Verifies that a note path lies inside one of the configured root directories.
+///
+/// All paths are canonicalized, which resolves `..` components and symlinks,
+/// so node IDs like `../secret` can't be used to read arbitrary files. Every
+/// root of `AppConfig::configured_roots` counts, so notes from `root_dirs` pass.
+///
+/// # Returns
+///
+/// The canonical path of the note.
+///
+/// # Errors
+///
+/// Returns an error if no root is configured, the path doesn't exist or the
+/// note lies outside every root.
+fn ensure_inside_root(config: &AppConfig, path: &Path) -> Result<PathBuf, String> {
+    let roots = config.configured_roots();
+
+    if roots.is_empty() {
+        return Err("Root directory not configured".to_string());
+    }
+
+    let canonical = fs::canonicalize(path)
+        .map_err(|e| format!("Error resolving file {:?}: {}", path, e))?;
+
+    for root_dir in &roots {
+        let root = fs::canonicalize(root_dir)
+            .map_err(|e| format!("Error resolving root directory '{}': {}", root_dir, e))?;
+
+        if canonical.starts_with(&root) {
+            return Ok(canonical);
+        }
+    }
+
+    Err(format!("Path is outside the configured root directories: {:?}", path))
+}
+
+/// Returns the content that follows the first `count` lines.
+fn skip_lines(content: &str, count: usize) -> &str {
+    let mut rest = content;
+
+    for _ in 0..count {
+        match rest.find('\n') {
+            Some(index) => rest = &rest[index + 1..],
+            None => return "",
+        }
+    }
+
+    rest
+}
+
+/// Returns the forward links and backlinks of a single node.
+///
+/// Looks up all edges in the graph cache where the node is the source
+/// (`outgoing`) or the target (`incoming`). This is more targeted than fetching
+/// the whole graph for a backlinks/forward-links panel.
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the node (real or phantom)
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(EdgeSet)` - The node's outgoing and incoming edges
+/// * `Err(String)` - Error message if the node isn't in the graph
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { outgoing, incoming } = await invoke('get_edges_for', { nodeId: 'MyNote' });
+/// ```
+#[tauri::command]
+pub fn get_edges_for(node_id: String, state: State<AppState>) -> Result<EdgeSet, String> {
+    state
+        .lock_cache()
+        .get_edges_for(&node_id)
+        .ok_or_else(|| format!("Node not found: {}", node_id))
+}
+
+/// Returns the cached graph in D3 force-directed layout format.
+///
+/// The default graph format follows vis-network conventions (`edges` with
+/// `from`/`to`). Frontends embedding the graph with D3 expect `links` with
+/// `source`/`target` instead; this command performs that renaming while keeping
+/// each node's `label`, `value` and `group`.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(D3Graph)` - The cached graph in D3 format
+/// * `Err(String)` - Error message if the graph could not be converted
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { nodes, links } = await invoke('get_graph_d3');
+/// d3.forceSimulation(nodes).force('link', d3.forceLink(links).id(d => d.id));
+/// ```
+#[tauri::command]
+pub fn get_graph_d3(state: State<AppState>) -> Result<D3Graph, String> {
+    Ok(D3Graph::from(state.lock_cache().graph()))
+}
+
+/// Truncates text to at most `max_chars` characters, marking cut text with `…`.
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Returns the absolute path of a note's file.
+///
+/// Uses the `file_path` recorded for the node during the last scan instead of
+/// reconstructing it from `root_dir`, so notes in subdirectories resolve
+/// correctly.
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the note
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(String)` - Path of the note's file
+/// * `Err(String)` - Error message if the node is unknown or a phantom
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const path = await invoke('get_note_path', { nodeId: 'MyNote' });
+/// ```
+#[tauri::command]
+pub fn get_note_path(node_id: String, state: State<AppState>) -> Result<String, String> {
+    let cache = state.lock_cache();
+
+    let node = cache
+        .get_node(&node_id)
+        .ok_or_else(|| format!("Node not found: {}", node_id))?;
+
+    if node.file_path.is_empty() {
+        return Err(format!("Node has no file (phantom node): {}", node_id));
+    }
+
+    Ok(node.file_path.clone())
+}
+
+/// Opens the system file manager with a note's file highlighted.
+///
+/// The note is resolved through the graph cache like `get_note_path`. The file
+/// manager is launched as a detached process; the command returns as soon as it
+/// was spawned.
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the note
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(())` - The file manager was launched
+/// * `Err(String)` - Error message if the note has no file or launching failed
+///
+/// # Platform-Specific Behavior
+///
+/// - Windows: `explorer /select,<file>` opens Explorer with the file selected
+/// - macOS: `open -R <file>` reveals the file in Finder
+/// - Linux and others: `xdg-open <dir>` opens the containing directory, since
+///   there is no portable way to select a file
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('reveal_in_file_manager', { nodeId: 'MyNote' });
+/// ```
+#[tauri::command]
+pub fn reveal_in_file_manager(node_id: String, state: State<AppState>) -> Result<(), String> {
+    let file_path = get_note_path(node_id, state)?;
+    let (program, args) = file_manager_command(std::env::consts::OS, Path::new(&file_path));
+
+    println!("[Reveal] Running: {} {:?}", program, args);
+
+    Command::new(&program)
+        .args(&args)
+        .spawn()
+        .map_err(|e| format!("Error launching {}: {}", program, e))?;
+
+    Ok(())
+}
+
+/// Assembles the command revealing a file in the file manager of an OS.
+///
+/// Kept separate from `reveal_in_file_manager` so the per-platform command
+/// lines can be checked without launching anything.
+///
+/// # Arguments
+///
+/// * `os` - Operating system name as in `std::env::consts::OS` (e.g. `"windows"`)
+/// * `file_path` - File to reveal
+///
+/// # Returns
+///
+/// The program to run and its arguments.
+pub fn file_manager_command(os: &str, file_path: &Path) -> (String, Vec<String>) {
+    let path = file_path.to_string_lossy().to_string();
+
+    match os {
+        "windows" => ("explorer".to_string(), vec![format!("/select,{}", path)]),
+        "macos" => ("open".to_string(), vec!["-R".to_string(), path]),
+        _ => {
+            let dir = file_path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+
+            ("xdg-open".to_string(), vec![dir.to_string_lossy().to_string()])
+        }
+    }
+}
+
+/// Returns the neighbors of a node in the cached graph.
+///
+/// By default links are followed in their direction only, so the neighbors are
+/// the notes a note links to. With `undirected: true` in the configuration,
+/// notes linking to the node are neighbors as well.
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the node (real or phantom)
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - Sorted IDs of the neighboring nodes
+/// * `Err(String)` - Error message if the node isn't in the graph
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const neighbors = await invoke('get_neighbors', { nodeId: 'MyNote' });
+/// ```
+#[tauri::command]
+pub fn get_neighbors(node_id: String, state: State<AppState>) -> Result<Vec<String>, String> {
+    let undirected = state.get_config().undirected;
+
+    state
+        .lock_cache()
+        .get_neighbors(&node_id, undirected)
+        .ok_or_else(|| format!("Node not found: {}", node_id))
+}
+
+/// Parses markdown content that isn't stored in a file.
+///
+/// Intended for live previews of unsaved editor buffers: the content is parsed
+/// exactly like a scanned note (using the configured wiki-link syntax), so the
+/// frontend can show the graph effects of an edit before it is saved.
+///
+/// # Arguments
+///
+/// * `content` - Markdown content to parse
+/// * `state` - Tauri managed state containing the application configuration
+///
+/// # Returns
+///
+/// * `Ok(ParsedContentDto)` - Wiki-links, hashtags, frontmatter and sections of the content
+/// * `Err(String)` - Error message if the content could not be parsed
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const parsed = await invoke('parse_content', { content: editor.getValue() });
+/// console.log(parsed.wiki_links, parsed.hashtags, parsed.frontmatter);
+/// ```
+#[tauri::command]
+pub fn parse_content(content: String, state: State<AppState>) -> Result<ParsedContentDto, String> {
+    let syntax = state.get_config().link_syntax();
+
+    Ok(parser::parse_markdown_with(strip_bom(&content), &syntax).into())
+}
+
+/// Returns the cached graph as a sparse adjacency matrix.
+///
+/// Links are directed by default; with `undirected: true` in the configuration
+/// the matrix is symmetric. See `AdjacencyMatrix` for the format.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(AdjacencyMatrix)` - Sorted node IDs and the non-zero link counts
+/// * `Err(String)` - Error message if the matrix could not be built
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { node_ids, entries } = await invoke('get_adjacency_matrix');
+/// entries.forEach(e => console.log(`${node_ids[e.row]} -> ${node_ids[e.col]}: ${e.count}`));
+/// ```
+#[tauri::command]
+pub fn get_adjacency_matrix(state: State<AppState>) -> Result<AdjacencyMatrix, String> {
+    let undirected = state.get_config().undirected;
+
+    Ok(state.lock_cache().get_adjacency_matrix(undirected))
+}
+
+/// Counts how many times one note links to another.
+///
+/// A note can reference the same target several times; every occurrence is
+/// stored as its own edge in the graph cache, so the count reflects the note's
+/// content at the last scan. Intended for edge-weight displays.
+///
+/// # Arguments
+///
+/// * `from` - ID of the linking note
+/// * `to` - ID of the linked note (may be a phantom node or not exist at all)
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(usize)` - Number of links from `from` to `to`, 0 if there are none
+/// * `Err(String)` - Error message if `from` is not in the graph
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const weight = await invoke('count_links', { from: 'Projects', to: 'Roadmap' });
+/// ```
+#[tauri::command]
+pub fn count_links(from: String, to: String, state: State<AppState>) -> Result<usize, String> {
+    state
+        .lock_cache()
+        .count_links(&from, &to)
+        .ok_or_else(|| format!("Node not found: {}", from))
+}
+
+/// Returns the hashtags of the cached graph as a tree.
+///
+/// Hierarchical tags such as `#project/frontend/ui` are split at `/` into
+/// nested nodes, each with the number of notes carrying exactly that tag and
+/// the number of distinct notes anywhere in its subtree (see `TagNode`).
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(TagNode)` - Unnamed root node whose children are the top-level tags
+/// * `Err(String)` - Error message if the tree could not be built
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const root = await invoke('get_tag_tree');
+/// root.children.forEach(tag => console.log(`#${tag.path} (${tag.total_count})`));
+/// ```
+#[tauri::command]
+pub fn get_tag_tree(state: State<AppState>) -> Result<TagNode, String> {
+    Ok(state.lock_cache().get_tag_tree())
+}
+
+/// Diagnostic summary of the application state.
+///
+/// # Fields
+///
+/// * `root_dir` - Configured root directory, if any
+/// * `root_dir_valid` - Whether `root_dir` and every entry of `root_dirs` is an
+///   existing directory (false if no root is configured)
+/// * `template` - Configured phantom node template, if any
+/// * `template_readable` - Whether the template can be loaded (false if none is configured)
+/// * `node_count` - Number of nodes in the graph cache
+/// * `edge_count` - Number of edges in the graph cache
+/// * `problems` - Human-readable description of every failed check; empty when healthy
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub root_dir: Option<String>,
+    pub root_dir_valid: bool,
+    pub template: Option<String>,
+    pub template_readable: bool,
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub problems: Vec<String>,
+}
+
+/// Checks the configuration and graph cache for support triage.
+///
+/// Consolidates the checks that otherwise only surface as errors from individual
+/// commands: whether a root directory is configured and exists, whether the
+/// phantom node template is configured and readable, and how large the cached
+/// graph is. The check itself never fails; problems are listed in the report.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(HealthReport)` - Results of all checks
+/// * `Err(String)` - Error message if the report could not be produced
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const report = await invoke('health_check');
+/// report.problems.forEach(problem => console.warn('[Health]', problem));
+/// ```
+#[tauri::command]
+pub fn health_check(state: State<AppState>) -> Result<HealthReport, String> {
+    let config = state.get_config();
+    let mut problems = Vec::new();
+
+    let roots = config.configured_roots();
+
+    let mut root_dir_valid = !roots.is_empty();
+
+    if roots.is_empty() {
+        problems.push("No root_dir is configured".to_string());
+    }
+
+    for root in &roots {
+        if let Err(e) = scan_root(root) {
+            problems.push(e);
+            root_dir_valid = false;
+        }
+    }
+
+    let template_readable = match config.template_phantom_node.as_deref() {
+        Some(template) => match templates::load_template(template) {
+            Ok(_) => true,
+            Err(e) => {
+                problems.push(e);
+                false
+            }
+        },
+        None => {
+            problems.push("No template_phantom_node is configured".to_string());
+            false
+        }
+    };
+
+    let (node_count, edge_count) = {
+        let cache = state.lock_cache();
+        (cache.graph().nodes.len(), cache.graph().edges.len())
+    };
+
+    Ok(HealthReport {
+        root_dir: config.root_dir,
+        root_dir_valid,
+        template: config.template_phantom_node,
+        template_readable,
+        node_count,
+        edge_count,
+        problems,
+    })
+}
+
+/// A note to create with `import_notes`.
+///
+/// # Fields
+///
+/// * `name` - Note name, used as the file name (`{root_dir}/{name}.md`) and node ID
+/// * `content` - Markdown content; template variables such as `{{date}}` are substituted
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoteInput {
+    pub name: String,
+    pub content: String,
+}
+
+/// Outcome of importing a single note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    /// The note file was written
+    Created,
+    /// A file with this name already exists and was left untouched
+    Skipped,
+    /// The note could not be written (see `NoteImportResult::error`)
+    Failed,
+}
+
+/// Result of importing a single note.
+///
+/// # Fields
+///
+/// * `name` - Name of the note, as given in the input
+/// * `status` - Whether the note was created, skipped or failed
+/// * `error` - Reason for skipping or failing, None for created notes
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteImportResult {
+    pub name: String,
+    pub status: ImportStatus,
+    pub error: Option<String>,
+}
+
+/// Summary of an `import_notes` batch.
+///
+/// # Fields
+///
+/// * `created` - Number of notes written
+/// * `skipped` - Number of notes whose file already existed
+/// * `failed` - Number of notes that could not be written
+/// * `results` - Per-note results, in input order
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub created: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub results: Vec<NoteImportResult>,
+}
+
+/// Creates many notes at once, e.g. when migrating from another tool.
+///
+/// Each note is written to `{root_dir}/{name}.md` after substituting template
+/// variables in its content (the note name serves as `{{title}}`, and the
+/// configured `template_variables` are available). Existing files are never
+/// overwritten; such notes are skipped. A failure only affects its own note, so
+/// the rest of the batch is still imported. Created notes are added to the graph
+/// cache without a full rescan.
+///
+/// # Arguments
+///
+/// * `notes` - Notes to create, each with a name and content
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(ImportReport)` - Counts of created, skipped and failed notes plus per-note results
+/// * `Err(String)` - Error message if no `root_dir` is configured
+///
+/// # Note Names
+///
+/// Names must be plain file names: empty names and names containing path
+/// separators or `..` fail, so an import can't write outside `root_dir`.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const report = await invoke('import_notes', {
+///   notes: [{ name: 'Inbox', content: '# Inbox\nImported {{date}}' }]
+/// });
+/// console.log(`${report.created} created, ${report.skipped} skipped, ${report.failed} failed`);
+/// ```
+#[tauri::command]
+pub fn import_notes(notes: Vec<NoteInput>, state: State<AppState>) -> Result<ImportReport, String> {
+    let config = state.get_config();
+
+    if config.configured_roots().is_empty() {
+        return Err("Root directory not configured".to_string());
+    }
+
+    let options = config.build_options();
+    let mut report = ImportReport::default();
+    let mut changes = Vec::new();
+
+    for note in notes {
+        let (status, error) = match import_note(&config, &note) {
+            Ok(Some(file)) => {
+                state.lock_cache().add_file(&file, &options);
+                changes.push(FileChange::Created { path: file.path });
+                report.created += 1;
+                (ImportStatus::Created, None)
+            }
+            Ok(None) => {
+                report.skipped += 1;
+                (ImportStatus::Skipped, Some("File already exists".to_string()))
+            }
+            Err(e) => {
+                report.failed += 1;
+                (ImportStatus::Failed, Some(e))
+            }
+        };
+
+        report.results.push(NoteImportResult {
+            name: note.name,
+            status,
+            error,
+        });
+    }
+
+    state.record_operation(Operation {
+        description: format!("Import {} notes", report.created),
+        changes,
+    });
+
+    println!(
+        "[Import] {} created, {} skipped, {} failed",
+        report.created, report.skipped, report.failed
+    );
+
+    Ok(report)
+}
+
+/// Writes a single imported note.
+///
+/// # Returns
+///
+/// * `Ok(Some(MarkdownFile))` - The note was created
+/// * `Ok(None)` - A file with this name already exists
+/// * `Err(String)` - The name is invalid or the file could not be written
+fn import_note(config: &AppConfig, note: &NoteInput) -> Result<Option<MarkdownFile>, String> {
+    let name = note.name.as_str();
+
+    if name.trim().is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(format!("Invalid note name: {:?}", name));
+    }
+
+    let file_path = note_path(config, name)?;
+    let content =
+        helpers::replace_variables_with(&note.content, Some(name), &config.template_variables);
+
+    let mut file = match OpenOptions::new().write(true).create_new(true).open(&file_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => return Ok(None),
+        Err(e) => return Err(format!("Failed to create file {:?}: {}", file_path, e)),
+    };
+
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write file {:?}: {}", file_path, e))?;
+
+    Ok(Some(MarkdownFile {
+        path: file_path,
+        content,
+        name: name.to_string(),
+    }))
+}
+
+/// Searches notes by name with fuzzy subsequence matching.
+///
+/// Works like fzf: the query characters must appear in order in a note's ID or
+/// label, but not necessarily next to each other, so `grh` finds `graph`.
+/// Results are ranked so that contiguous and start-of-word matches come first
+/// (see `helpers::fuzzy_score`).
+///
+/// # Arguments
+///
+/// * `query` - Search query (case-insensitive)
+/// * `limit` - Maximum number of results
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<SearchResult>)` - Matching notes, best match first
+/// * `Err(String)` - Error message if the search could not be performed
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const results = await invoke('search_notes', { query: 'grh', limit: 20 });
+/// results.forEach(r => console.log(r.id, r.score));
+/// ```
+#[tauri::command]
+pub fn search_notes(
+    query: String,
+    limit: usize,
+    state: State<AppState>,
+) -> Result<Vec<SearchResult>, String> {
+    Ok(state.lock_cache().search_notes(&query, limit))
+}
+
+/// Maximum number of notes compared by `find_similar_notes`.
+///
+/// Every pair of notes is compared, so the work grows quadratically; larger
+/// vaults are rejected instead of freezing the command for minutes.
+const MAX_SIMILARITY_NOTES: usize = 2000;
+
+/// Two notes with similar content.
+///
+/// # Fields
+///
+/// * `a` - Node ID of the first note (alphabetically)
+/// * `b` - Node ID of the second note
+/// * `similarity` - Jaccard similarity of the notes' word sets, from 0.0 to 1.0
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarPair {
+    pub a: String,
+    pub b: String,
+    pub similarity: f64,
+}
+
+/// Finds pairs of notes with near-duplicate content, as merge candidates.
+///
+/// Each note body (without frontmatter) is reduced to its set of distinct
+/// lowercase words, and every pair of notes is compared by the Jaccard
+/// similarity of these sets (see `helpers::jaccard_similarity`). Notes are read
+/// from disk, so the comparison reflects their current content.
+///
+/// # Arguments
+///
+/// * `threshold` - Minimum similarity (0.0 to 1.0) for a pair to be reported
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<SimilarPair>)` - Pairs at or above the threshold, most similar first
+/// * `Err(String)` - Error message if the threshold is out of range, the vault has
+///   more than `MAX_SIMILARITY_NOTES` notes, or a note can't be read
+///
+/// # Performance
+///
+/// The comparison is O(n²) in the number of notes, hence the size cap.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const pairs = await invoke('find_similar_notes', { threshold: 0.8 });
+/// pairs.forEach(p => console.log(`${p.a} ~ ${p.b}: ${p.similarity.toFixed(2)}`));
+/// ```
+#[tauri::command]
+pub fn find_similar_notes(
+    threshold: f64,
+    state: State<AppState>,
+) -> Result<Vec<SimilarPair>, String> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(format!("Threshold must be between 0 and 1, got {}", threshold));
+    }
+
+    let mut notes: Vec<(String, String)> = state
+        .lock_cache()
+        .graph()
+        .nodes
+        .iter()
+        .filter(|node| !node.file_path.is_empty())
+        .map(|node| (node.id.clone(), node.file_path.clone()))
+        .collect();
+
+    if notes.len() > MAX_SIMILARITY_NOTES {
+        return Err(format!(
+            "Too many notes to compare ({}, at most {} are supported)",
+            notes.len(),
+            MAX_SIMILARITY_NOTES
+        ));
+    }
+
+    notes.sort();
+
+    let mut word_sets = Vec::with_capacity(notes.len());
+
+    for (node_id, file_path) in notes {
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| format!("Error reading file {:?}: {}", file_path, e))?;
+        let (_, body) = parser::split_frontmatter(strip_bom(&content));
+
+        word_sets.push((node_id, parser::word_set(body)));
+    }
+
+    let mut pairs = Vec::new();
+
+    for (i, (a, a_words)) in word_sets.iter().enumerate() {
+        for (b, b_words) in &word_sets[i + 1..] {
+            let similarity = helpers::jaccard_similarity(a_words, b_words);
+
+            if similarity >= threshold && similarity > 0.0 {
+                pairs.push(SimilarPair {
+                    a: a.clone(),
+                    b: b.clone(),
+                    similarity,
+                });
+            }
+        }
+    }
+
+    pairs.sort_by(|x, y| y.similarity.total_cmp(&x.similarity));
+
+    println!("[Similarity] {} similar pairs at threshold {}", pairs.len(), threshold);
+
+    Ok(pairs)
+}
+
+/// Returns the cached graph with edges filtered by the hashtags of their endpoints.
+///
+/// Lets users focus on topical connections: with a `tag`, only links between
+/// two notes that both carry that tag are kept; without one, only links between
+/// notes sharing at least one tag are kept. All nodes are returned, so the
+/// frontend can still show unconnected notes. The cache itself is not changed.
+///
+/// # Arguments
+///
+/// * `tag` - Tag both endpoints must carry (with or without the leading `#`), or
+///   None to keep edges between notes with any common tag
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(GraphData)` - The cached graph with the filtered edges
+/// * `Err(String)` - Error message if the graph could not be filtered
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const projectGraph = await invoke('get_graph_filtered_by_tag', { tag: 'project' });
+/// const topicalGraph = await invoke('get_graph_filtered_by_tag', { tag: null });
+/// ```
+#[tauri::command]
+pub fn get_graph_filtered_by_tag(
+    tag: Option<String>,
+    state: State<AppState>,
+) -> Result<GraphData, String> {
+    let filter = match tag {
+        Some(tag) => EdgeTagFilter::Tag(tag.trim_start_matches('#').to_string()),
+        None => EdgeTagFilter::SharedTag,
+    };
+
+    Ok(graph::filter_edges_by_tag(state.lock_cache().graph(), &filter))
+}
+
+/// Reverts the most recent note modification.
+///
+/// Commands that write notes (`create_phantom_node`, `append_to_note`,
+/// `rename_tag`, `import_notes`, `organize_by_tag`, `rename_note`) record how to
+/// reverse their file changes in an in-memory log of the last
+/// `MAX_UNDO_OPERATIONS` operations; `rename_note` records the move of the note
+/// together with the notes whose links to it were rewritten. This command
+/// reverses the newest one: created files are deleted, modified files get their
+/// previous content back and moved files are moved back. The graph cache is then
+/// rebuilt from the configured roots and a `rescan-complete` event is emitted.
+///
+/// # Arguments
+///
+/// * `app` - Application handle used to emit the `rescan-complete` event
+/// * `state` - Tauri managed state holding the configuration, graph cache and undo log
+///
+/// # Returns
+///
+/// * `Ok(Some(String))` - Description of the undone operation
+/// * `Ok(None)` - There was nothing to undo
+/// * `Err(String)` - Error message if a file couldn't be restored or the rescan failed
+///
+/// # Errors
+///
+/// The operation is removed from the log even if reverting it fails, so a
+/// broken operation can't block older ones.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const undone = await invoke('undo_last');
+/// if (undone) console.log('Undid:', undone);
+/// ```
+#[tauri::command]
+pub fn undo_last(app: AppHandle, state: State<AppState>) -> Result<Option<String>, String> {
+    let Some(operation) = state.pop_operation() else {
+        return Ok(None);
+    };
+
+    operation.revert()?;
+
+    println!("[Undo] Reverted: {}", operation.description);
+
+    let roots = state.get_config().configured_roots();
+
+    if !roots.is_empty() {
+        state.rescan(&roots, &app)?;
+    }
+
+    Ok(Some(operation.description))
+}
+
+/// Returns the notes a note links to, flagging links to missing notes.
+///
+/// Powers the forward-links panel: every distinct link target is listed once,
+/// in the order the note first mentions it, with `exists: false` for targets
+/// that only exist as phantom nodes (broken links).
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the linking note
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<OutgoingLink>)` - The note's link targets
+/// * `Err(String)` - Error message if the node is not in the graph
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const links = await invoke('get_outgoing_links', { nodeId: 'MyNote' });
+/// const broken = links.filter(link => !link.exists);
+/// ```
+#[tauri::command]
+pub fn get_outgoing_links(
+    node_id: String,
+    state: State<AppState>,
+) -> Result<Vec<OutgoingLink>, String> {
+    state
+        .lock_cache()
+        .get_outgoing_links(&node_id)
+        .ok_or_else(|| format!("Node not found: {}", node_id))
+}
+
+/// Streams the cached graph to the frontend in chunks.
+///
+/// For very large vaults, returning the whole graph from a command means
+/// building and parsing one huge JSON object. This command instead emits a
+/// `graph-begin` event with the totals, `graph-chunk` events with at most
+/// `GRAPH_CHUNK_SIZE` nodes or edges each (nodes first), and a closing
+/// `graph-end` event (see `events::emit_graph_stream`).
+///
+/// # Arguments
+///
+/// * `app` - Application handle used to emit the events
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(usize)` - Number of `graph-chunk` events emitted
+/// * `Err(String)` - Error message if an event could not be emitted
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+/// import { listen } from '@tauri-apps/api/event';
+///
+/// const nodes = [], edges = [];
+/// const unlisten = await listen('graph-chunk', e => {
+///   nodes.push(...e.payload.nodes);
+///   edges.push(...e.payload.edges);
+/// });
+/// await listen('graph-end', () => { unlisten(); render(nodes, edges); });
+/// await invoke('stream_graph');
+/// ```
+#[tauri::command]
+pub fn stream_graph(app: AppHandle, state: State<AppState>) -> Result<usize, String> {
+    let graph = state.lock_cache().graph().clone();
+
+    events::emit_graph_stream(&app, &graph, events::GRAPH_CHUNK_SIZE)
+}
+
+/// Writes a markdown report of every note's backlinks to a file.
+///
+/// For maintenance: the report lists each note with the notes linking to it
+/// ("linked mentions"), and lists link targets without a file separately under
+/// a "Missing Notes" heading (see `GraphCache::backlinks_report`). The report is
+/// built from the graph cache. An existing file at `out_path` is overwritten.
+///
+/// # Arguments
+///
+/// * `out_path` - Path of the markdown file to write
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(())` - The report was written
+/// * `Err(String)` - Error message if the file couldn't be written
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('export_backlinks_report', { outPath: '/home/me/backlinks.md' });
+/// ```
+#[tauri::command]
+pub fn export_backlinks_report(out_path: String, state: State<AppState>) -> Result<(), String> {
+    let report = state.lock_cache().backlinks_report();
+
+    fs::write(&out_path, report)
+        .map_err(|e| format!("Failed to write report '{}': {}", out_path, e))?;
+
+    println!("[Report] Backlinks report written to: {}", out_path);
+
+    Ok(())
+}
+
+/// Describes the configuration fields so the frontend can render a settings form.
+///
+/// Each entry carries the field's JSON key, value type, whether it may be unset,
+/// a short description, its default and its current value (see
+/// `AppConfig::schema`). Nested fields are flattened with dotted names such as
+/// `previewer.offset`.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state containing the current configuration
+///
+/// # Returns
+///
+/// * `Ok(Vec<ConfigField>)` - One descriptor per configuration field, in form order
+/// * `Err(String)` - Error message if the schema could not be built
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const fields = await invoke('get_config_schema');
+/// for (const field of fields) {
+///   renderInput(field.name, field.field_type, field.value ?? field.default);
+/// }
+/// ```
+#[tauri::command]
+pub fn get_config_schema(state: State<AppState>) -> Result<Vec<ConfigField>, String> {
+    Ok(state.get_config().schema())
+}
+
+/// Validates, applies and saves a new configuration.
+///
+/// The configuration is checked with `AppConfig::validate` first; if it is
+/// invalid, nothing is changed. Otherwise root directories are normalized (see
+/// `normalize_root_dir`), the configuration replaces the one in the application
+/// state and is written to the configuration file (see `AppState::config_path`).
+/// When a setting that shapes the graph changed (the roots, scan patterns, link
+/// syntax, labels, colors and the other fields of `CacheKey`), the graph is
+/// rebuilt from the new roots and a `rescan-complete` event is emitted, as after
+/// `scan_folder`. The periodic
+/// rescan is restarted when the roots or `rescan_interval_secs` changed, the
+/// file watcher when the roots or `modify_throttle_ms` changed, and the template watch when
+/// `watch_template` or `template_phantom_node` changed.
+///
+/// Values given on the command line are part of the current configuration, so
+/// they are written to the file as well once a configuration is saved.
+///
+/// # Arguments
+///
+/// * `new_config` - The complete configuration to apply
+/// * `app` - Tauri application handle used to emit the rescan event
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(())` - The configuration was applied and saved
+/// * `Err(String)` - Error message if validation, saving or the rescan failed
+///
+/// # Errors
+///
+/// A validation error leaves the state and the file untouched. If saving the
+/// file fails, the new configuration is still active for this session, with
+/// the graph rebuilt and the background threads restarted as described above
+/// (see `AppState::apply_config`).
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const config = await invoke('get_config');
+/// config.skip_hidden = true;
+/// await invoke('set_config', { newConfig: config });
+/// ```
+#[tauri::command]
+pub fn set_config(
+    new_config: AppConfig,
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<(), String> {
+    state.apply_config(new_config, app)
+}
+
+/// Maximum number of suggestions returned by `complete_link`.
+const MAX_LINK_COMPLETIONS: usize = 20;
+
+/// Suggests note names for wiki-link autocompletion in an editor.
+///
+/// Returns existing notes whose name starts with the prefix, followed by notes
+/// that only match it fuzzily, e.g. `grh` for `graph` (see
+/// `GraphCache::complete_link`). At most `MAX_LINK_COMPLETIONS` names are
+/// returned. Phantom nodes are never suggested.
+///
+/// # Arguments
+///
+/// * `prefix` - Text typed after `[[` so far (case-insensitive)
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - Suggested note names, best first
+/// * `Err(String)` - Error message if suggestions could not be computed
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const names = await invoke('complete_link', { prefix: 'proj' });
+/// showCompletions(names.map(name => `[[${name}]]`));
+/// ```
+#[tauri::command]
+pub fn complete_link(prefix: String, state: State<AppState>) -> Result<Vec<String>, String> {
+    Ok(state.lock_cache().complete_link(&prefix, MAX_LINK_COMPLETIONS))
+}
+
+/// Ranks notes by PageRank importance.
+///
+/// Unlike `get_hubs`, which counts links, PageRank also weighs where links come
+/// from: a link from an important note is worth more than one from a note
+/// nobody links to. See `GraphCache::pagerank` for the algorithm; the scores of
+/// all nodes (phantom nodes included) sum to 1.
+///
+/// # Arguments
+///
+/// * `iterations` - Number of update rounds (e.g. 30)
+/// * `damping` - Probability of following a link, between 0.0 and 1.0 (commonly 0.85)
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<NodeScore>)` - All nodes ordered by descending score, ties broken by name
+/// * `Err(String)` - Error message if `damping` is outside 0.0 to 1.0
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const ranks = await invoke('get_pagerank', { iterations: 30, damping: 0.85 });
+/// ranks.slice(0, 10).forEach(r => console.log(r.id, r.score.toFixed(4)));
+/// ```
+#[tauri::command]
+pub fn get_pagerank(
+    iterations: usize,
+    damping: f64,
+    state: State<AppState>,
+) -> Result<Vec<NodeScore>, String> {
+    if !(0.0..=1.0).contains(&damping) {
+        return Err(format!("Damping must be between 0.0 and 1.0, got {}", damping));
+    }
+
+    Ok(state.lock_cache().pagerank(iterations, damping))
+}
+
+/// Reads a note by its file path for display in the preview panel.
+///
+/// Useful when the frontend already has a node's `file_path` and would
+/// otherwise have to map it back to a node ID for `read_note`. The path must
+/// lie inside `root_dir` or one of the `root_dirs` (symlinks and `..`
+/// components are resolved first), so arbitrary files can't be read. The
+/// content is returned like `read_note` returns it, without frontmatter if
+/// `previewer.skip_frontmatter` is set and without the first
+/// `previewer.offset` lines.
+///
+/// # Arguments
+///
+/// * `path` - Path of the note file, as in a node's `file_path`
+/// * `state` - Tauri managed state containing the configuration
+///
+/// # Returns
+///
+/// * `Ok(String)` - Note content for the preview
+/// * `Err(String)` - Error message if the path is outside the configured root
+///   directories, the file doesn't exist or can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const content = await invoke('read_note_by_path', { path: node.file_path });
+/// ```
+#[tauri::command]
+pub fn read_note_by_path(path: String, state: State<AppState>) -> Result<String, String> {
+    read_preview_at(&state.get_config(), Path::new(&path))
+}
+
+/// Counts the files of the vault by extension, markdown or not.
+///
+/// A diagnostic for "why is my note missing?": only `.md` files become notes,
+/// so listing every extension present in the configured roots (`root_dir` and
+/// `root_dirs`) reveals notes stored as `.markdown`, `.txt` and so on. Scan
+/// patterns and hidden-path settings are ignored; see `scanner::count_extensions`.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state containing the configuration
+///
+/// # Returns
+///
+/// * `Ok(Vec<ExtCount>)` - Extensions with their file counts, most common first;
+///   files without an extension are counted under an empty extension
+/// * `Err(String)` - Error message if no root directory is configured or a
+///   directory can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const extensions = await invoke('get_vault_extensions');
+/// extensions.forEach(e => console.log(`.${e.extension}: ${e.count}`));
+/// ```
+#[tauri::command]
+pub fn get_vault_extensions(state: State<AppState>) -> Result<Vec<ExtCount>, String> {
+    let roots = state.get_config().configured_roots();
+
+    if roots.is_empty() {
+        return Err("Root directory not configured".to_string());
+    }
+
+    scanner::count_extensions(&roots)
+}
+
+/// Brings the graph cache up to date and sends the changes as a delta.
+///
+/// For when the cache may have missed edits, e.g. notes created or deleted
+/// with another tool while the app was busy. The configured roots are
+/// rescanned, the result is diffed against the current cache, the cache is
+/// replaced and the changes are emitted as one `graph-delta` event (see
+/// `AppState::reconcile_and_emit`). Unlike `scan_folder`, which returns the whole graph,
+/// the frontend only receives what changed; no event is sent if nothing did.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle used to emit the delta
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(())` - The cache is up to date
+/// * `Err(String)` - Error message if no root directory is configured, the
+///   rescan failed or the event could not be emitted
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+/// import { listen } from '@tauri-apps/api/event';
+///
+/// await listen('graph-delta', e => applyDelta(e.payload));
+/// await invoke('reconcile');
+/// ```
+#[tauri::command]
+pub fn reconcile(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    state.reconcile_and_emit(&app)?;
+
+    Ok(())
+}
+
+/// Version and build information of the running application.
+///
+/// # Fields
+///
+/// * `name` - Package name
+/// * `version` - Package version, e.g. `0.1.0`
+/// * `os` - Target operating system, e.g. `linux`, `macos` or `windows`
+/// * `arch` - Target architecture, e.g. `x86_64` or `aarch64`
+/// * `debug` - Whether the binary was built with debug assertions (a dev build)
+#[derive(Debug, Clone, Serialize)]
+pub struct AppInfo {
+    pub name: String,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub debug: bool,
+}
+
+/// Returns version and build information for bug reports.
+///
+/// All values are fixed at compile time, so the report identifies exactly
+/// which build a user is running.
+///
+/// # Returns
+///
+/// The `AppInfo` of this build.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const info = await invoke('get_app_info');
+/// console.log(`${info.name} ${info.version} (${info.os}/${info.arch})`);
+/// ```
+#[tauri::command]
+pub fn get_app_info() -> AppInfo {
+    AppInfo {
+        name: env!("CARGO_PKG_NAME").to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        debug: cfg!(debug_assertions),
+    }
+}
+
+/// Returns the number of incoming and outgoing links of a node.
+///
+/// Gives the frontend both numbers for a node badge in a single call, read
+/// from the graph cache under one lock (see `GraphCache::get_link_counts`).
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the node
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(LinkCounts)` - `incoming` and `outgoing` link counts
+/// * `Err(String)` - Error message if the node doesn't exist
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { incoming, outgoing } = await invoke('get_link_counts', { nodeId: 'my-note' });
+/// badge.textContent = `${incoming}↓ ${outgoing}↑`;
+/// ```
+#[tauri::command]
+pub fn get_link_counts(node_id: String, state: State<AppState>) -> Result<LinkCounts, String> {
+    state
+        .lock_cache()
+        .get_link_counts(&node_id)
+        .ok_or_else(|| format!("Node not found: {}", node_id))
+}
+
+/// Lists the notes containing at least one broken link.
+///
+/// A to-do list for cleaning up source notes: each note appears once, however
+/// many missing notes it links to. Use `check_links` to see the links
+/// themselves.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - IDs of the notes linking to a phantom node, sorted alphabetically
+/// * `Err(String)` - Error message if the list could not be produced
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const notes = await invoke('get_notes_with_broken_links');
+/// notes.forEach(id => console.log(`${id} has broken links`));
+/// ```
+#[tauri::command]
+pub fn get_notes_with_broken_links(state: State<AppState>) -> Result<Vec<String>, String> {
+    Ok(state.lock_cache().get_notes_with_broken_links())
+}
+
+/// A note's links, tags and frontmatter as currently stored on disk.
+///
+/// # Fields
+///
+/// * `node_id` - ID of the inspected note
+/// * `file_path` - Path of the file that was read
+/// * `links` - Each wiki-link target once, in document order and resolved through
+///   aliases, flagged with whether it is an existing note in the graph
+/// * `hashtags` - Inline and frontmatter tags without the `#`
+/// * `frontmatter` - Frontmatter key/value pairs, or null without a frontmatter block
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteInspection {
+    pub node_id: String,
+    pub file_path: String,
+    pub links: Vec<OutgoingLink>,
+    pub hashtags: Vec<String>,
+    pub frontmatter: Option<BTreeMap<String, FrontmatterValue>>,
+}
+
+/// Locates an existing note's file.
+///
+/// The file is located through the path recorded for the node in the graph
+/// cache, which follows notes into subdirectories and is updated when notes are
+/// moved (e.g. by `organize_by_tag`). The path is checked to lie inside one of
+/// the configured root directories (see `ensure_inside_root`).
+///
+/// # Errors
+///
+/// Returns an error if the node isn't in the cache, is a phantom node or its
+/// file lies outside every configured root directory.
+fn note_file_path(config: &AppConfig, state: &AppState, node_id: &str) -> Result<PathBuf, String> {
+    let cached_path = state.lock_cache().get_node(node_id).map(|node| node.file_path.clone());
+
+    match cached_path {
+        Some(path) if path.is_empty() => {
+            Err(format!("Node has no file (phantom node): {}", node_id))
+        }
+        Some(path) => {
+            ensure_inside_root(config, Path::new(&path))?;
+            Ok(PathBuf::from(path))
+        }
+        None => Err(format!("Note not found: {}", node_id)),
+    }
+}
+
+/// Locates the file a note is written to, whether or not it exists yet.
+///
+/// Existing notes are located through the graph cache like in `note_file_path`;
+/// phantom and unknown notes get the path a new note is created at (see
+/// `note_path`).
+///
+/// # Errors
+///
+/// Returns an error if the note doesn't exist yet and no `root_dir` is configured.
+fn note_write_path(config: &AppConfig, state: &AppState, node_id: &str) -> Result<PathBuf, String> {
+    let cached_path = state
+        .lock_cache()
+        .get_node(node_id)
+        .map(|node| node.file_path.clone())
+        .filter(|path| !path.is_empty());
+
+    match cached_path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => note_path(config, node_id),
+    }
+}
+
+/// Reads a note's current content from disk.
+///
+/// See `note_file_path` for how the file is located.
+///
+/// # Returns
+///
+/// The path of the file and its content.
+///
+/// # Errors
+///
+/// Returns an error if the node is a phantom node or the file can't be read.
+fn read_note_file(
+    config: &AppConfig,
+    state: &AppState,
+    node_id: &str,
+) -> Result<(PathBuf, String), String> {
+    let file_path = note_file_path(config, state, node_id)?;
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Error reading file {:?}: {}", file_path, e))?;
+
+    Ok((file_path, content))
+}
+
+/// Reads and parses a single note for an inspector panel.
+///
+/// Unlike `get_outgoing_links`, the note is read and parsed fresh from disk, so
+/// the result reflects the file's current content even if the graph cache is
+/// momentarily stale. Links are resolved against the cache like in the graph,
+/// including path links relative to the note (see `GraphCache::resolve_links`),
+/// and a link exists if the cache holds a real note with the resolved ID.
+/// See `read_note_file` for how the file is located.
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the note to inspect
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(NoteInspection)` - The note's links, hashtags and frontmatter
+/// * `Err(String)` - Error message if the note is a phantom node or can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const note = await invoke('inspect_note', { nodeId: 'my-note' });
+/// note.links.forEach(link => console.log(link.target, link.exists ? '' : '(missing)'));
+/// ```
+#[tauri::command]
+pub fn inspect_note(node_id: String, state: State<AppState>) -> Result<NoteInspection, String> {
+    let config = state.get_config();
+    let (file_path, content) = read_note_file(&config, &state, &node_id)?;
+    let parsed = parser::parse_markdown_with(strip_bom(&content), &config.link_syntax());
+
+    let cache = state.lock_cache();
+    let mut seen = HashSet::new();
+    let links = cache
+        .resolve_links(&file_path, &parsed.wiki_links)
+        .into_iter()
+        .filter(|target| seen.insert(target.clone()))
+        .map(|target| OutgoingLink {
+            exists: cache.get_node(&target).is_some_and(|node| !node.file_path.is_empty()),
+            target,
+        })
+        .collect();
+
+    Ok(NoteInspection {
+        node_id,
+        file_path: file_path.to_string_lossy().to_string(),
+        links,
+        hashtags: parsed.hashtags,
+        frontmatter: parsed.frontmatter.map(|frontmatter| frontmatter.fields),
+    })
+}
+
+/// Groups the notes into clusters by their dominant hashtag.
+///
+/// For a clustered layout: each note is assigned to exactly one cluster, named
+/// after the tag it carries that the most notes in the vault share (ties go to
+/// the tag listed first in the note). Notes without tags end up in the
+/// `untagged` cluster. See `GraphCache::get_tag_clusters` for details.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, Vec<String>>)` - Cluster names mapped to sorted note IDs
+/// * `Err(String)` - Error message if the clusters could not be computed
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const clusters = await invoke('get_tag_clusters');
+/// Object.entries(clusters).forEach(([tag, notes]) => layoutCluster(tag, notes));
+/// ```
+#[tauri::command]
+pub fn get_tag_clusters(state: State<AppState>) -> Result<HashMap<String, Vec<String>>, String> {
+    Ok(state.lock_cache().get_tag_clusters())
+}
+
+/// Moves every note carrying a hashtag into a folder.
+///
+/// For bulk organization: each note tagged `tag` (matched case-insensitively,
+/// see `GraphCache::notes_with_tag`) is moved into `{root_dir}/{dest_rel_dir}`
+/// with `move_note`, creating the folder if needed. Node IDs are file stems, so
+/// links keep working; only the notes' paths change in the graph cache. Notes
+/// already in the folder are left alone. The moves can be reverted with
+/// `undo_last`.
+///
+/// # Arguments
+///
+/// * `tag` - Tag of the notes to move, with or without the leading `#`
+/// * `dest_rel_dir` - Destination folder relative to `root_dir`, e.g. `projects/web`
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(usize)` - Number of notes that were moved
+/// * `Err(String)` - Error message if the destination is invalid, or listing the
+///   notes that were skipped
+///
+/// # Errors
+///
+/// Returns an error without moving anything if no `root_dir` is configured or
+/// `dest_rel_dir` is absolute or contains `..`. A note whose file name already
+/// exists in the destination is skipped rather than overwritten, as is a note
+/// whose move fails; the other notes are still moved, and the error lists every
+/// skipped note.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const moved = await invoke('organize_by_tag', { tag: 'project', destRelDir: 'projects' });
+/// console.log(`Moved ${moved} notes`);
+/// ```
+#[tauri::command]
+pub fn organize_by_tag(
+    tag: String,
+    dest_rel_dir: String,
+    state: State<AppState>,
+) -> Result<usize, String> {
+    let tag = tag.trim_start_matches('#');
+    let config = state.get_config();
+    let dest_dir = vault_subdir(&config, &dest_rel_dir)?;
+
+    let notes: Vec<(String, PathBuf)> = {
+        let cache = state.lock_cache();
+
+        cache
+            .notes_with_tag(tag)
+            .into_iter()
+            .filter_map(|id| {
+                let node = cache.get_node(&id)?;
+                (!node.file_path.is_empty()).then(|| (id, PathBuf::from(&node.file_path)))
+            })
+            .collect()
+    };
+
+    let mut changes = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (node_id, path) in notes {
+        if path.parent() == Some(dest_dir.as_path()) {
+            continue;
+        }
+
+        match move_note(&path, &dest_dir) {
+            Ok(moved) => {
+                state
+                    .lock_cache()
+                    .set_file_path(&node_id, moved.to_string_lossy().to_string());
+                changes.push(FileChange::Moved { from: path, to: moved });
+            }
+            Err(e) => skipped.push(format!("{} ({})", node_id, e)),
+        }
+    }
+
+    let moved = changes.len();
+
+    state.record_operation(Operation {
+        description: format!("Move notes tagged #{} to {}", tag, dest_rel_dir),
+        changes,
+    });
+
+    println!("[Organize] Moved {} notes tagged #{} to {:?}", moved, tag, dest_dir);
+
+    if !skipped.is_empty() {
+        return Err(format!(
+            "Moved {} notes; skipped {}: {}",
+            moved,
+            skipped.len(),
+            skipped.join(", ")
+        ));
+    }
+
+    Ok(moved)
+}
+
+/// Resolves a folder inside `root_dir` from a relative path.
+///
+/// # Errors
+///
+/// Returns an error if no `root_dir` is configured or `rel_dir` is absolute or
+/// contains `..`, so the result can't point outside the vault.
+fn vault_subdir(config: &AppConfig, rel_dir: &str) -> Result<PathBuf, String> {
+    let root_dir = config
+        .root_dir
+        .as_ref()
+        .ok_or_else(|| "Root directory not configured".to_string())?;
+
+    let rel_path = Path::new(rel_dir);
+    let is_relative = rel_path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+
+    if !is_relative {
+        return Err(format!("Destination must be a folder inside the vault: {:?}", rel_dir));
+    }
+
+    Ok(Path::new(root_dir).join(rel_path))
+}
+
+/// Moves a note file into a folder, keeping its file name.
+///
+/// The folder is created if it doesn't exist. An existing file is never
+/// overwritten.
+///
+/// # Returns
+///
+/// The new path of the note.
+///
+/// # Errors
+///
+/// Returns an error if the destination already contains a file with this name,
+/// or the folder can't be created or the file can't be moved.
+fn move_note(path: &Path, dest_dir: &Path) -> Result<PathBuf, String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Invalid note path: {:?}", path))?;
+    let target = dest_dir.join(file_name);
+
+    if target.exists() {
+        return Err(format!("{:?} already exists", target));
+    }
+
+    fs::create_dir_all(dest_dir)
+        .map_err(|e| format!("Failed to create folder {:?}: {}", dest_dir, e))?;
+    fs::rename(path, &target)
+        .map_err(|e| format!("Failed to move {:?} to {:?}: {}", path, target, e))?;
+
+    Ok(target)
+}
+
+/// Returns the part of the graph whose notes match a set of tags.
+///
+/// For filtered views: keeps the notes carrying any (`mode: "any"`) or all
+/// (`mode: "all"`) of the tags, matched case-insensitively, and the links
+/// between them (see `graph::induced_subgraph`). Phantom nodes carry no tags
+/// and are always excluded, so broken links don't appear in the result.
+///
+/// # Arguments
+///
+/// * `tags` - Tags to match, with or without the leading `#`
+/// * `mode` - `any` or `all`
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(GraphData)` - The matching notes and the edges among them
+/// * `Err(String)` - Error message if no tag is given or `mode` is invalid
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const graph = await invoke('get_graph_for_tags', { tags: ['rust', 'web'], mode: 'all' });
+/// network.setData(graph);
+/// ```
+#[tauri::command]
+pub fn get_graph_for_tags(
+    tags: Vec<String>,
+    mode: String,
+    state: State<AppState>,
+) -> Result<GraphData, String> {
+    let mode = TagMatch::parse(&mode)?;
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    if tags.is_empty() {
+        return Err("No tags given".to_string());
+    }
+
+    Ok(graph::induced_subgraph(state.lock_cache().graph(), |node| {
+        !node.file_path.is_empty() && mode.matches(&tags, &node.hashtags)
+    }))
+}
+
+/// Reports broken links that match an existing note when case is ignored.
+///
+/// Links resolve case-sensitively, so `[[note]]` next to a file `Note.md` shows
+/// up as a phantom node. This lists such links with the note they most likely
+/// mean, so they can be fixed (see `GraphCache::get_case_mismatch_links`).
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<CaseMismatchLink>)` - Mismatched links sorted by source note
+/// * `Err(String)` - Error message if the report could not be produced
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const mismatches = await invoke('get_case_mismatch_links');
+/// mismatches.forEach(m => console.warn(`${m.source}: [[${m.link}]] should be [[${m.note}]]`));
+/// ```
+#[tauri::command]
+pub fn get_case_mismatch_links(state: State<AppState>) -> Result<Vec<CaseMismatchLink>, String> {
+    Ok(state.lock_cache().get_case_mismatch_links())
+}
+
+/// Returns the headings of a note for a document-structure panel.
+///
+/// The note is read fresh from disk (see `read_note_file`) and its ATX headings
+/// (`#` to `######`) are extracted in document order. Headings in the
+/// frontmatter or inside fenced code blocks are ignored (see
+/// `parser::extract_headings`).
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the note
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<Heading>)` - Each heading's level, text and 1-based line number
+/// * `Err(String)` - Error message if the note is a phantom node or can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const outline = await invoke('get_note_outline', { nodeId: 'my-note' });
+/// outline.forEach(h => console.log(`${'  '.repeat(h.level - 1)}${h.text} (line ${h.line})`));
+/// ```
+#[tauri::command]
+pub fn get_note_outline(node_id: String, state: State<AppState>) -> Result<Vec<Heading>, String> {
+    let (_, content) = read_note_file(&state.get_config(), &state, &node_id)?;
+
+    Ok(parser::extract_headings(strip_bom(&content)))
+}
+
+/// Returns where each wiki-link and hashtag of a note appears in its source.
+///
+/// Intended for click-to-source: the note is read fresh from disk (see
+/// `read_note_file`) and parsed with the configured wiki-link syntax (see
+/// `parser::parse_markdown_with_positions`). Each match carries its byte range
+/// and its 1-based line and column, so the editor can jump to it. Tags declared
+/// in the frontmatter `tags:` key have no position and are not included.
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the note
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(ParsedPositions)` - Wiki-links and hashtags in document order
+/// * `Err(String)` - Error message if the note is a phantom node or can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const positions = await invoke('get_link_positions', { nodeId: 'my-note' });
+/// const link = positions.wiki_links.find(l => l.text === 'other-note');
+/// editor.setCursor(link.line, link.column);
+/// ```
+#[tauri::command]
+pub fn get_link_positions(
+    node_id: String,
+    state: State<AppState>,
+) -> Result<ParsedPositions, String> {
+    let config = state.get_config();
+    let (_, content) = read_note_file(&config, &state, &node_id)?;
+
+    Ok(parser::parse_markdown_with_positions(strip_bom(&content), &config.link_syntax()))
+}
+
+/// Maximum number of headings returned by `find_heading`.
+const MAX_HEADING_HITS: usize = 50;
+
+/// A heading matching a `find_heading` query.
+///
+/// # Fields
+///
+/// * `node_id` - ID of the note containing the heading
+/// * `text` - Heading text
+/// * `level` - Heading level, 1 for `#` up to 6 for `######`
+/// * `line` - 1-based line number of the heading in the note
+/// * `score` - Fuzzy match score (see `helpers::fuzzy_score`); higher is better
+#[derive(Debug, Clone, Serialize)]
+pub struct HeadingHit {
+    pub node_id: String,
+    pub text: String,
+    pub level: usize,
+    pub line: usize,
+    pub score: i64,
+}
+
+/// Searches the headings of every note, for "go to heading" navigation.
+///
+/// Each note is read from disk and its outline extracted like in
+/// `get_note_outline`. Headings are fuzzily matched against the query (see
+/// `helpers::fuzzy_score`), so `inst` finds `Installation`. At most
+/// `MAX_HEADING_HITS` headings are returned.
+///
+/// # Arguments
+///
+/// * `query` - Search query; an empty query matches nothing
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<HeadingHit>)` - Matching headings ordered by descending score, ties
+///   broken by note ID and then line
+/// * `Err(String)` - Error message if a note can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const hits = await invoke('find_heading', { query: 'install' });
+/// hits.forEach(h => console.log(`${h.node_id}:${h.line} ${h.text}`));
+/// ```
+#[tauri::command]
+pub fn find_heading(query: String, state: State<AppState>) -> Result<Vec<HeadingHit>, String> {
+    let query = query.trim();
+
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let notes: Vec<(String, String)> = state
+        .lock_cache()
+        .graph()
+        .nodes
+        .iter()
+        .filter(|node| !node.file_path.is_empty())
+        .map(|node| (node.id.clone(), node.file_path.clone()))
+        .collect();
+
+    let mut hits = Vec::new();
+
+    for (node_id, file_path) in notes {
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| format!("Error reading file {:?}: {}", file_path, e))?;
+
+        for heading in parser::extract_headings(strip_bom(&content)) {
+            let Some(score) = helpers::fuzzy_score(query, &heading.text) else {
+                continue;
+            };
+
+            hits.push(HeadingHit {
+                node_id: node_id.clone(),
+                text: heading.text,
+                level: heading.level,
+                line: heading.line,
+                score,
+            });
+        }
+    }
+
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    hits.truncate(MAX_HEADING_HITS);
+
+    Ok(hits)
+}
+
+/// Lists the IDs of all notes, for quick-open palettes.
+///
+/// Cheaper than fetching the whole graph when the frontend only needs names to
+/// pick from. Phantom nodes are not included (see
+/// `GraphCache::get_all_file_names`).
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<String>)` - Note IDs sorted alphabetically
+/// * `Err(String)` - Error message if the list could not be produced
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const names = await invoke('list_notes');
+/// palette.setItems(names);
+/// ```
+#[tauri::command]
+pub fn list_notes(state: State<AppState>) -> Result<Vec<String>, String> {
+    Ok(state.lock_cache().get_all_file_names())
+}
+
+/// Lists the notes modified within a date range, for review workflows.
+///
+/// Like `get_recent_notes`, modification times are read from the file system
+/// for every real note in the graph cache. Both bounds are inclusive and accept
+/// either an ISO date (`2025-01-31`, in local time) or an RFC 3339 timestamp
+/// (`2025-01-31T18:00:00+01:00`). A date as `end` covers that whole day.
+///
+/// # Arguments
+///
+/// * `start` - First date or instant of the range
+/// * `end` - Last date or instant of the range
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<RecentNote>)` - Notes modified within the range, newest first
+///   (ties ordered by ID)
+/// * `Err(String)` - Error message if a bound is malformed or `start` is after `end`
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const week = await invoke('get_notes_in_range', { start: '2025-01-01', end: '2025-01-07' });
+/// week.forEach(n => console.log(n.id, new Date(n.modified)));
+/// ```
+#[tauri::command]
+pub fn get_notes_in_range(
+    start: String,
+    end: String,
+    state: State<AppState>,
+) -> Result<Vec<RecentNote>, String> {
+    let start_millis = helpers::parse_range_bound(&start, false)?;
+    let end_millis = helpers::parse_range_bound(&end, true)?;
+
+    if start_millis > end_millis {
+        return Err(format!("Range start {} is after its end {}", start, end));
+    }
+
+    let mut notes: Vec<RecentNote> = note_modified_times(&state)
+        .into_iter()
+        .filter(|note| (start_millis..=end_millis).contains(&note.modified))
+        .collect();
+
+    notes.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.id.cmp(&b.id)));
+
+    Ok(notes)
+}
+
+/// Returns the notes carrying a hashtag and the links among them.
+///
+/// For "tag map" views showing how the notes of one topic interconnect. Only
+/// edges whose both ends carry the tag are kept; links leaving the tag set and
+/// their targets are dropped (see `graph::induced_subgraph`). The tag is
+/// matched case-insensitively through the tag index, without child tags.
+///
+/// # Arguments
+///
+/// * `tag` - Tag to map, with or without the leading `#`
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(GraphData)` - The tagged notes and the edges between them
+/// * `Err(String)` - Error message if the tag is empty
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const topic = await invoke('get_tag_subgraph', { tag: 'rust' });
+/// network.setData(topic);
+/// ```
+#[tauri::command]
+pub fn get_tag_subgraph(tag: String, state: State<AppState>) -> Result<GraphData, String> {
+    let tag = tag.trim().trim_start_matches('#');
+
+    if tag.is_empty() {
+        return Err("Tag must not be empty".to_string());
+    }
+
+    let cache = state.lock_cache();
+    let notes: HashSet<String> = cache.notes_with_tag(tag).into_iter().collect();
+
+    Ok(graph::induced_subgraph(cache.graph(), |node| notes.contains(&node.id)))
+}
+
+/// Returns the frontmatter of a note as JSON, without reading its body.
+///
+/// For metadata-heavy views listing many notes: only the leading `---` block is
+/// read from disk (see `scanner::read_frontmatter_block`) and parsed with
+/// `parser::parse_frontmatter`. Scalars become JSON strings and lists become
+/// arrays. See `note_file_path` for how the file is located.
+///
+/// # Arguments
+///
+/// * `node_id` - The ID of the note
+/// * `state` - Tauri managed state containing the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(serde_json::Value)` - Object of the frontmatter keys; empty if the note
+///   has no frontmatter
+/// * `Err(String)` - Error message if the node is a phantom node or the file
+///   can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const meta = await invoke('get_frontmatter', { nodeId: 'project-plan' });
+/// console.log(meta.status, meta.tags);
+/// ```
+#[tauri::command]
+pub fn get_frontmatter(
+    node_id: String,
+    state: State<AppState>,
+) -> Result<serde_json::Value, String> {
+    let file_path = note_file_path(&state.get_config(), &state, &node_id)?;
+    let block = scanner::read_frontmatter_block(&file_path)?;
+    let fields = parser::parse_frontmatter(&block)
+        .map(|frontmatter| frontmatter.fields)
+        .unwrap_or_default();
+
+    serde_json::to_value(fields).map_err(|e| format!("Failed to serialize frontmatter: {}", e))
+}
+
+/// A note section matching a `find_notes_by_section` query.
+///
+/// # Fields
+///
+/// * `node_id` - ID of the note
+/// * `section` - Text of the matching H1 or H2 heading
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionHit {
+    pub node_id: String,
+    pub section: String,
+}
+
+/// Finds the notes having a section whose heading contains a query.
+///
+/// Sections are the H1 and H2 headings cached on each node when
+/// `heading_sections` is enabled, so no file is read. The query is matched
+/// case-insensitively as a substring. Unlike `find_heading`, this is a filter
+/// over the cached graph rather than a fuzzy search of every heading on disk.
+///
+/// # Arguments
+///
+/// * `query` - Text to look for in section headings
+/// * `state` - Tauri managed state containing the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<SectionHit>)` - Matching sections ordered by note ID, then document order
+/// * `Err(String)` - Error message if the query is empty or `heading_sections`
+///   is disabled
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const hits = await invoke('find_notes_by_section', { query: 'meeting' });
+/// hits.forEach(h => console.log(`${h.node_id}: ${h.section}`));
+/// ```
+#[tauri::command]
+pub fn find_notes_by_section(
+    query: String,
+    state: State<AppState>,
+) -> Result<Vec<SectionHit>, String> {
+    if !state.get_config().heading_sections {
+        return Err("Heading sections are disabled (set heading_sections)".to_string());
+    }
+
+    let query = query.trim().to_lowercase();
+
+    if query.is_empty() {
+        return Err("Query must not be empty".to_string());
+    }
+
+    let cache = state.lock_cache();
+    let mut nodes: Vec<_> = cache.graph().nodes.iter().collect();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(nodes
+        .into_iter()
+        .flat_map(|node| {
+            node.sections
+                .iter()
+                .filter(|section| section.to_lowercase().contains(&query))
+                .map(|section| SectionHit {
+                    node_id: node.id.clone(),
+                    section: section.clone(),
+                })
+        })
+        .collect())
+}
+
+/// Resolves a raw wiki-link to the ID of the node it points to.
+///
+/// For navigating from link text the frontend has at hand. The surrounding
+/// `[[`/`]]` are optional. The target is extracted like when building the graph
+/// (see `parser::link_target`): the alias, a heading or block reference and a
+/// markdown extension are removed. It is then resolved against the graph cache,
+/// following aliases (see `GraphCache::resolve_link_target`). Like in the graph,
+/// targets are matched case-sensitively. Path links such as `[[../shared/note]]`
+/// depend on the linking note's folder (see `graph::resolve_relative_link`), so
+/// they are not resolved here and come back unchanged.
+///
+/// # Arguments
+///
+/// * `raw` - Link text, e.g. `[[Alice.md#Contact|Al]]` or `Alice`
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(String)` - The ID of the note the link resolves to, or the phantom node
+///   ID the graph uses for it if no note matches
+/// * `Err(String)` - Error message if the link has no target (e.g. `[[#Setup]]`)
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const nodeId = await invoke('resolve_link', { raw: '[[Alice#Contact|Al]]' });
+/// network.focus(nodeId);
+/// ```
+#[tauri::command]
+pub fn resolve_link(raw: String, state: State<AppState>) -> Result<String, String> {
+    let trimmed = raw.trim();
+    let inner = trimmed
+        .strip_prefix("[[")
+        .and_then(|rest| rest.strip_suffix("]]"))
+        .unwrap_or(trimmed);
+    let target = parser::link_target(inner);
+
+    if target.is_empty() {
+        return Err(format!("Link has no target: {}", raw));
+    }
+
+    Ok(state.lock_cache().resolve_link_target(target))
+}
+
+/// Preview contents of several notes, as returned by `read_notes`.
+///
+/// # Fields
+///
+/// * `notes` - Note ID to preview content, as `read_note` returns it
+/// * `missing` - IDs that could not be read (e.g. phantom nodes or deleted files),
+///   in request order
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteBatch {
+    pub notes: HashMap<String, String>,
+    pub missing: Vec<String>,
+}
+
+/// Reads several notes for preview in one call.
+///
+/// Saves one IPC round-trip per note for panels previewing many notes, such as
+/// a backlinks list. Every note is read like `read_note`, so the frontmatter and
+/// `previewer.offset` are skipped as configured. A note that can't be read is
+/// listed in `missing` instead of failing the whole call. Duplicate IDs are
+/// read once.
+///
+/// # Arguments
+///
+/// * `node_ids` - IDs of the notes to read
+/// * `state` - Tauri managed state containing the application configuration
+///
+/// # Returns
+///
+/// * `Ok(NoteBatch)` - The contents of the readable notes and the IDs of the others
+/// * `Err(String)` - Error message if no `root_dir` is configured
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { notes, missing } = await invoke('read_notes', { nodeIds: ['a', 'b', 'ghost'] });
+/// Object.entries(notes).forEach(([id, content]) => preview.add(id, content));
+/// ```
+#[tauri::command]
+pub fn read_notes(node_ids: Vec<String>, state: State<AppState>) -> Result<NoteBatch, String> {
+    let config = state.get_config();
+
+    if config.configured_roots().is_empty() {
+        return Err("Root directory not configured".to_string());
+    }
+
+    let mut batch = NoteBatch {
+        notes: HashMap::new(),
+        missing: Vec::new(),
+    };
+
+    for node_id in node_ids {
+        if batch.notes.contains_key(&node_id) || batch.missing.contains(&node_id) {
+            continue;
+        }
+
+        match read_preview(&config, &state, &node_id) {
+            Ok(content) => {
+                batch.notes.insert(node_id, content);
+            }
+            Err(e) => {
+                println!("[ReadNotes] Skipping {}: {}", node_id, e);
+                batch.missing.push(node_id);
+            }
+        }
+    }
+
+    Ok(batch)
+}
+
+/// A line naming a note in plain text without linking to it.
+///
+/// # Fields
+///
+/// * `source` - Node ID of the note containing the mention
+/// * `line` - 1-based line number of the mention
+/// * `text` - Trimmed line, truncated to `MAX_SNIPPET_CHARS`
+#[derive(Debug, Clone, Serialize)]
+pub struct Mention {
+    pub source: String,
+    pub line: usize,
+    pub text: String,
+}
+
+/// Lists the places where other notes mention a note without linking to it.
+///
+/// Like Obsidian's "unlinked mentions": the note's name, its frontmatter
+/// `title` and its `aliases` are searched in every other note's text, read from
+/// disk, as whole words and ignoring case. Mentions inside a wiki-link, in the
+/// frontmatter or in fenced code blocks don't count (see
+/// `parser::unlinked_mentions`). Each line is reported once.
+///
+/// # Arguments
+///
+/// * `node_id` - The ID of the mentioned note
+/// * `state` - Tauri managed state containing the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<Mention>)` - Mentions ordered by source note, then line
+/// * `Err(String)` - Error message if the note is a phantom node or a file
+///   can't be read
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const mentions = await invoke('get_unlinked_mentions', { nodeId: 'Rust' });
+/// mentions.forEach(m => console.log(`${m.source}:${m.line} ${m.text}`));
+/// ```
+#[tauri::command]
+pub fn get_unlinked_mentions(
+    node_id: String,
+    state: State<AppState>,
+) -> Result<Vec<Mention>, String> {
+    let config = state.get_config();
+    let (_, content) = read_note_file(&config, &state, &node_id)?;
+    let frontmatter = parser::parse_frontmatter(strip_bom(&content));
+
+    let mut terms = vec![node_id.clone()];
+    terms.extend(frontmatter.as_ref().and_then(|fm| fm.get_str("title")).map(str::to_string));
+    terms.extend(graph::note_aliases(frontmatter.as_ref()));
+
+    let mut notes: Vec<(String, String)> = state
+        .lock_cache()
+        .graph()
+        .nodes
+        .iter()
+        .filter(|node| !node.file_path.is_empty() && node.id != node_id)
+        .map(|node| (node.id.clone(), node.file_path.clone()))
+        .collect();
+    notes.sort();
+
+    let syntax = config.link_syntax();
+    let mut mentions = Vec::new();
+
+    for (source, file_path) in notes {
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| format!("Error reading file {:?}: {}", file_path, e))?;
+
+        for (line, text) in parser::unlinked_mentions(strip_bom(&content), &terms, &syntax) {
+            mentions.push(Mention {
+                source: source.clone(),
+                line,
+                text: truncate_chars(text.trim(), MAX_SNIPPET_CHARS),
+            });
+        }
+    }
+
+    Ok(mentions)
+}
+
+/// Finds the connected components consisting only of missing notes.
+///
+/// Reports the connected components of the phantom-only subgraph (see
+/// `GraphCache::get_phantom_clusters`). Phantom nodes have no outgoing links,
+/// so only singletons can exist: every missing note is its own cluster.
+///
+/// # Arguments
+///
+/// * `state` - Tauri managed state holding the graph cache
+///
+/// # Returns
+///
+/// * `Ok(Vec<Vec<String>>)` - One list of sorted node IDs per phantom cluster,
+///   ordered by their first node ID
+/// * `Err(String)` - Error message if the query could not be answered
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const clusters = await invoke<string[][]>('get_phantom_clusters');
+/// clusters.forEach(c => console.log(`Unwritten cluster: ${c.join(', ')}`));
+/// ```
+#[tauri::command]
+pub fn get_phantom_clusters(state: State<AppState>) -> Result<Vec<Vec<String>>, String> {
+    Ok(state.lock_cache().get_phantom_clusters())
+}
+
+/// Renames a note and updates every wiki-link pointing to it.
+///
+/// Renames the note's file within its folder (keeping its extension), then
+/// rewrites the links to it in all notes, the renamed note included. Links are
+/// parsed rather than searched for as text (see `parser::rename_link_target`),
+/// so aliased (`[[old|Shown]]`) and heading-qualified (`[[old#Setup]]`) links
+/// keep their display text and heading, and notes that merely mention the old
+/// name or link to a similarly named note are left alone. The graph cache is
+/// updated without a full rescan.
+///
+/// # Arguments
+///
+/// * `node_id` - ID of the note to rename
+/// * `new_name` - New note name, without extension
+/// * `state` - Tauri managed state holding the configuration and graph cache
+///
+/// # Returns
+///
+/// * `Ok(usize)` - Number of notes whose links were rewritten
+/// * `Err(String)` - Error message if the rename failed
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `new_name` is empty or contains a path separator or link syntax (`#`, `|`, `[`, `]`)
+/// - The note is a phantom node or doesn't exist
+/// - A note named `new_name` already exists
+/// - The file can't be renamed, or a linking note can't be read or written
+///
+/// Notes rewritten before a failure keep their new content. The rename can be
+/// reverted with `undo_last`.
+///
+/// # Limitations
+///
+/// Links are matched by note name; path links (`[[folder/old]]`) and links
+/// through one of the note's aliases are not rewritten. Alias links keep
+/// resolving to the renamed note.
+///
+/// # Frontend Usage
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const updated = await invoke<number>('rename_note', { nodeId: 'draft', newName: 'Plan' });
+/// console.log(`Updated links in ${updated} notes`);
+/// ```
+#[tauri::command]
+pub fn rename_note(
+    node_id: String,
+    new_name: String,
+    state: State<AppState>,
+) -> Result<usize, String> {
+    let new_name = new_name.trim();
+
+    if new_name.is_empty()
+        || new_name == "."
+        || new_name == ".."
+        || new_name.contains(['/', '\\', '#', '|', '[', ']'])
+    {
+        return Err(format!("Invalid note name: {:?}", new_name));
+    }
+
+    let config = state.get_config();
+    let old_path = note_file_path(&config, &state, &node_id)?;
+
+    if state
+        .lock_cache()
+        .get_node(new_name)
+        .is_some_and(|node| !node.file_path.is_empty())
+    {
+        return Err(format!("A note named '{}' already exists", new_name));
+    }
+
+    let mut file_name = new_name.to_string();
+    if let Some(extension) = old_path.extension() {
+        file_name.push('.');
+        file_name.push_str(&extension.to_string_lossy());
+    }
+    let new_path = old_path.with_file_name(file_name);
+
+    if new_path.exists() {
+        return Err(format!("{:?} already exists", new_path));
+    }
+
+    let notes: Vec<PathBuf> = state
+        .lock_cache()
+        .graph()
+        .nodes
+        .iter()
+        .filter(|node| !node.file_path.is_empty())
+        .map(|node| {
+            if node.id == node_id {
+                new_path.clone()
+            } else {
+                PathBuf::from(&node.file_path)
+            }
+        })
+        .collect();
+
+    fs::rename(&old_path, &new_path)
+        .map_err(|e| format!("Failed to rename {:?} to {:?}: {}", old_path, new_path, e))?;
+
+    let options = config.build_options();
+    {
+        let mut cache = state.lock_cache();
+        cache.remove_file(&node_id, &options);
+        cache.add_file(&scanner::read_markdown_file(&new_path)?, &options);
+    }
+
+    let mut changes = vec![FileChange::Moved {
+        from: old_path,
+        to: new_path,
+    }];
+    let result = rename_links_in_notes(notes, &node_id, new_name, &state, &mut changes);
+
+    state.record_operation(Operation {
+        description: format!("Rename note {} to {}", node_id, new_name),
+        changes,
+    });
+
+    let changed = result?;
+
+    println!("[Rename] Renamed {} to {}, updated links in {} notes", node_id, new_name, changed);
+
+    Ok(changed)
+}
+
+/// Points the links to `old` in the given notes at `new`, recording each
+/// rewritten file in `changes`.
+///
+/// Files rewritten before an error are still recorded, so they can be undone.
+///
+/// # Returns
+///
+/// The number of rewritten notes.
+fn rename_links_in_notes(
+    notes: Vec<PathBuf>,
+    old: &str,
+    new: &str,
+    state: &AppState,
+    changes: &mut Vec<FileChange>,
+) -> Result<usize, String> {
+    let options = state.get_config().build_options();
+    let mut changed = 0;
+
+    for file_path in notes {
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| format!("Error reading file {:?}: {}", file_path, e))?;
+
+        let Some(renamed) = parser::rename_link_target(&content, old, new, &options.link_syntax)
+        else {
+            continue;
+        };
+
+        fs::write(&file_path, &renamed)
+            .map_err(|e| format!("Error writing file {:?}: {}", file_path, e))?;
+
+        changes.push(FileChange::Modified {
+            path: file_path.clone(),
+            previous: content.into_bytes(),
+        });
+        changed += 1;
+
+        state
+            .lock_cache()
+            .add_file(&scanner::read_markdown_file(&file_path)?, &options);
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::GraphCache;
+    use crate::graph::{build_graph_with, BuildOptions};
+    use crate::testing::{app_with, markdown_file, state_for, temp_vault, Recorder};
+    use std::time::{Duration, SystemTime};
+    use tauri::Manager;
+
+    fn scan(state: &AppState, dir: &Path) -> GraphData {
+        state
+            .rescan(&[dir.to_string_lossy().to_string()], &Recorder::default())
+            .unwrap()
+    }
+
+    fn cache_of(files: Vec<MarkdownFile>) -> GraphCache {
+        GraphCache::from_graph(build_graph_with(files, &BuildOptions::default()))
+    }
+
+    fn read(path: PathBuf) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn append_expands_variables_and_creates_notes() {
+        let dir = temp_vault("cmd-append");
+        fs::write(dir.join("j.md"), "# J").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+
+        append_to_note("j".into(), "- {{title}}".into(), st.clone()).unwrap();
+        assert_eq!(read(dir.join("j.md")), "# J\n- j");
+
+        append_to_note("new".into(), "x".into(), st.clone()).unwrap();
+        assert_eq!(read(dir.join("new.md")), "\nx");
+
+        fs::create_dir_all(dir.join("people")).unwrap();
+        append_to_note("people/Alice".into(), "x".into(), st.clone()).unwrap();
+        assert!(dir.join("people/Alice.md").exists());
+
+        for id in ["../escaped", "people/../../escaped", ".", "/tmp/escaped", ""] {
+            let err = append_to_note(id.into(), "x".into(), st.clone()).unwrap_err();
+            assert!(err.contains("Invalid note name"), "{}", err);
+            assert!(open_file(id.into(), st.clone()).is_err());
+        }
+        assert!(!dir.join("../escaped.md").exists());
+    }
+
+    #[test]
+    fn read_note_applies_offset_and_stays_in_the_vault() {
+        let dir = temp_vault("cmd-read").join("vault");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("n.md"), "a\nb\nc").unwrap();
+        fs::write(dir.parent().unwrap().join("secret.md"), "s").unwrap();
+        let state = state_for(&dir);
+        state.config.lock().unwrap().previewer.offset = Some(1);
+        let app = app_with(state);
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        assert_eq!(read_note("n".into(), st.clone()).unwrap(), "b\nc");
+        assert!(read_note("../secret".into(), st.clone()).is_err());
+    }
+
+    #[test]
+    fn read_note_keeps_frontmatter_after_bom() {
+        let dir = temp_vault("cmd-bom");
+        fs::write(
+            dir.join("a.md"),
+            "\u{feff}---\ntitle: Alpha\n---\n# Heading",
+        )
+        .unwrap();
+        let app = app_with(state_for(&dir));
+        scan(&app.state::<AppState>(), &dir);
+
+        assert!(read_note("a".into(), app.state())
+            .unwrap()
+            .starts_with("---"));
+    }
+
+    #[test]
+    fn preview_skips_frontmatter() {
+        let dir = temp_vault("cmd-skip-frontmatter");
+        fs::write(
+            dir.join("fm.md"),
+            "---\ntitle: X\ntags: [a]\n---\nBody 1\nBody 2",
+        )
+        .unwrap();
+        fs::write(dir.join("plain.md"), "Line 1\nLine 2").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        let read = |id: &str| read_note(id.into(), st.clone()).unwrap();
+
+        st.config.lock().unwrap().previewer.skip_frontmatter = true;
+        assert_eq!(read("fm"), "Body 1\nBody 2");
+        assert_eq!(read("plain"), "Line 1\nLine 2");
+
+        st.config.lock().unwrap().previewer.offset = Some(1);
+        assert_eq!(read("fm"), "Body 2");
+
+        st.config.lock().unwrap().previewer.skip_frontmatter = false;
+        assert!(read("fm").starts_with("title: X"));
+    }
+
+    #[test]
+    fn read_note_by_path_rejects_paths_outside_the_vault() {
+        let base = temp_vault("cmd-read-by-path");
+        let dir = base.join("vault");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/n.md"), "line1\nline2\n").unwrap();
+        fs::write(base.join("secret.md"), "secret").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        let path = dir.join("sub/n.md").to_string_lossy().to_string();
+
+        assert_eq!(
+            read_note_by_path(path.clone(), st.clone()).unwrap(),
+            "line1\nline2\n"
+        );
+        st.config.lock().unwrap().previewer.offset = Some(1);
+        assert_eq!(read_note_by_path(path, st.clone()).unwrap(), "line2\n");
+
+        let outside = dir.join("../secret.md").to_string_lossy().to_string();
+        assert!(read_note_by_path(outside, st.clone())
+            .unwrap_err()
+            .contains("outside"));
+        let outside = base.join("secret.md").to_string_lossy().to_string();
+        assert!(read_note_by_path(outside, st.clone()).is_err());
+    }
+
+    #[test]
+    fn read_notes_batch_reports_missing_ids() {
+        let dir = temp_vault("cmd-read-notes");
+        fs::write(dir.join("a.md"), "h1\nh2\nbody a").unwrap();
+        fs::write(dir.join("b.md"), "h1\nh2\nbody b").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        st.config.lock().unwrap().previewer.offset = Some(2);
+        let ids = ["a", "ghost", "b", "a", "../x", "ghost"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let batch = read_notes(ids, st.clone()).unwrap();
+
+        assert_eq!(batch.notes.len(), 2);
+        assert_eq!(batch.notes["a"], "body a");
+        assert_eq!(batch.notes["b"], "body b");
+        assert_eq!(batch.missing, vec!["ghost", "../x"]);
+
+        st.config.lock().unwrap().root_dir = None;
+        assert!(read_notes(vec!["a".into()], st.clone()).is_err());
+    }
+
+    #[test]
+    fn phantom_dry_run_writes_nothing() {
+        let dir = temp_vault("cmd-dry-run");
+        fs::write(dir.join("tpl.txt"), "# {{title}}").unwrap();
+        let state = state_for(&dir.join("notes"));
+        state.config.lock().unwrap().template_phantom_node =
+            Some(dir.join("tpl.txt").to_string_lossy().to_string());
+        let app = app_with(state);
+        let st = app.state::<AppState>();
+
+        assert_eq!(
+            create_phantom_node("N".into(), true, st.clone()).unwrap(),
+            "# N"
+        );
+        assert!(!dir.join("notes").exists());
+
+        create_phantom_node("N".into(), false, st.clone()).unwrap();
+        assert_eq!(read(dir.join("notes/N.md")), "# N");
+    }
+
+    #[test]
+    fn rename_tag_rewrites_notes_and_cache() {
+        let dir = temp_vault("cmd-rename-tag");
+        fs::write(dir.join("a.md"), "#old and #old/sub").unwrap();
+        fs::write(dir.join("b.md"), "#oldish").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        assert_eq!(
+            rename_tag("#old".into(), "new".into(), st.clone()).unwrap(),
+            1
+        );
+
+        assert_eq!(read(dir.join("a.md")), "#new and #new/sub");
+        assert_eq!(read(dir.join("b.md")), "#oldish");
+        assert_eq!(
+            st.lock_cache().get_node("a").unwrap().hashtags,
+            vec!["new", "new/sub"]
+        );
+        assert!(rename_tag("bad tag".into(), "x".into(), st.clone()).is_err());
+    }
+
+    #[test]
+    fn note_context_lists_backlink_lines() {
+        let dir = temp_vault("cmd-context");
+        fs::write(dir.join("a.md"), "body of a [[a]]").unwrap();
+        fs::write(
+            dir.join("b.md"),
+            "intro\n  see [[a]] here  \nother [[c]]\nagain [[a]] [[a]]",
+        )
+        .unwrap();
+        fs::write(dir.join("c.md"), format!("{} [[a]]", "x".repeat(300))).unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let note = read_note_with_context("a".into(), st.clone()).unwrap();
+
+        assert_eq!(note.content, "body of a [[a]]");
+        let lines: Vec<_> = note
+            .backlinks
+            .iter()
+            .map(|b| (b.source.as_str(), b.line.clone()))
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], ("b", "see [[a]] here".to_string()));
+        assert_eq!(lines[1], ("b", "again [[a]] [[a]]".to_string()));
+        assert_eq!(lines[2].0, "c");
+        assert_eq!(lines[2].1.chars().count(), 201);
+    }
+
+    #[test]
+    fn diff_since_snapshot_requires_a_snapshot() {
+        let old = cache_of(vec![
+            markdown_file("a", "[[b]]"),
+            markdown_file("b", "#t"),
+            markdown_file("c", "x"),
+        ]);
+        let new = cache_of(vec![
+            markdown_file("a", "[[b]] [[d]]"),
+            markdown_file("b", "#u"),
+        ]);
+        let expected = old.diff(&new);
+        let app = app_with(state_for(&temp_vault("cmd-snapshot")));
+        let st = app.state::<AppState>();
+
+        assert!(diff_since_snapshot(st.clone()).is_err());
+        st.replace_cache(old);
+        snapshot_graph(st.clone()).unwrap();
+        st.replace_cache(new);
+
+        assert_eq!(diff_since_snapshot(st.clone()).unwrap(), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn recent_notes_are_newest_first() {
+        let dir = temp_vault("cmd-recent");
+        for (name, age) in [("old", 300u64), ("new", 10), ("mid", 100)] {
+            let path = dir.join(format!("{}.md", name));
+            fs::write(&path, "x").unwrap();
+            let file = fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(age))
+                .unwrap();
+        }
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let recent = get_recent_notes(2, st.clone()).unwrap();
+
+        let ids: Vec<_> = recent.iter().map(|note| note.id.as_str()).collect();
+        assert_eq!(ids, vec!["new", "mid"]);
+        assert!(recent[0].modified > recent[1].modified);
+        assert_eq!(get_recent_notes(10, st.clone()).unwrap().len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn notes_in_range_are_inclusive() {
+        let dir = temp_vault("cmd-range");
+        let day = |s: &str| helpers::parse_range_bound(s, false).unwrap();
+        for (name, date) in [
+            ("a", "2025-01-01"),
+            ("b", "2025-01-05"),
+            ("c", "2025-01-10"),
+        ] {
+            let path = dir.join(format!("{}.md", name));
+            fs::write(&path, "").unwrap();
+            let time = UNIX_EPOCH + Duration::from_millis(day(date) + 3_600_000);
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        }
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        let ids = |start: &str, end: &str| -> Vec<String> {
+            get_notes_in_range(start.into(), end.into(), st.clone())
+                .unwrap()
+                .into_iter()
+                .map(|note| note.id)
+                .collect()
+        };
+
+        assert_eq!(ids("2025-01-01", "2025-01-05"), vec!["b", "a"]);
+        assert!(ids("2025-01-02", "2025-01-04").is_empty());
+        assert_eq!(ids("2025-01-05", "2025-01-05"), vec!["b"]);
+        assert_eq!(ids("2025-01-06", "2025-12-31"), vec!["c"]);
+        assert_eq!(ids("2024-12-31T00:00:00Z", "2025-02-01T00:00:00Z").len(), 3);
+        for (start, end) in [
+            ("2025-13-01", "2025-01-01"),
+            ("yesterday", "2025-01-01"),
+            ("2025-01-05", "2025-01-01"),
+        ] {
+            assert!(get_notes_in_range(start.into(), end.into(), st.clone()).is_err());
+        }
+    }
+
+    #[test]
+    fn note_path_distinguishes_phantoms() {
+        let dir = temp_vault("cmd-note-path");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/a.md"), "[[ghost]]").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        assert!(get_note_path("a".into(), st.clone())
+            .unwrap()
+            .ends_with("a.md"));
+        assert!(get_note_path("ghost".into(), st.clone())
+            .unwrap_err()
+            .contains("phantom"));
+        assert!(get_note_path("nope".into(), st.clone())
+            .unwrap_err()
+            .contains("not found"));
+    }
+
+    #[test]
+    fn file_manager_commands_per_os() {
+        let path = Path::new("/notes/sub/a.md");
+
+        assert_eq!(
+            file_manager_command("windows", path),
+            (
+                "explorer".into(),
+                vec!["/select,/notes/sub/a.md".to_string()]
+            )
+        );
+        assert_eq!(
+            file_manager_command("macos", path),
+            (
+                "open".into(),
+                vec!["-R".to_string(), "/notes/sub/a.md".into()]
+            )
+        );
+        assert_eq!(
+            file_manager_command("linux", path),
+            ("xdg-open".into(), vec!["/notes/sub".to_string()])
+        );
+        assert_eq!(
+            file_manager_command("linux", Path::new("a.md")).1,
+            vec![".".to_string()]
+        );
+    }
+
+    #[test]
+    fn editor_commands_per_os() {
+        let args = |v: &[&str]| v.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            editor_command("linux", "/n/a.md", None),
+            ("nvim".into(), args(&["/n/a.md"]))
+        );
+        assert_eq!(
+            editor_command("linux", "/n/a.md", Some("  ")),
+            ("nvim".into(), args(&["/n/a.md"]))
+        );
+        assert_eq!(
+            editor_command("linux", "/n/a b.md", Some("x-terminal-emulator -e")),
+            (
+                "x-terminal-emulator".into(),
+                args(&["-e", "nvim", "/n/a b.md"])
+            )
+        );
+        assert_eq!(
+            editor_command("macos", "/n/a.md", Some("kitty")),
+            ("kitty".into(), args(&["nvim", "/n/a.md"]))
+        );
+        assert_eq!(
+            editor_command("windows", "C:\\n\\a.md", None),
+            ("cmd".into(), args(&["/C", "start", "nvim", "C:\\n\\a.md"]))
+        );
+        assert_eq!(
+            editor_command("windows", "a.md", Some("wt")),
+            ("cmd".into(), args(&["/C", "start", "wt", "nvim", "a.md"]))
+        );
+    }
+
+    #[test]
+    fn neighbors_follow_the_undirected_setting() {
+        let dir = temp_vault("cmd-neighbors");
+        fs::write(dir.join("a.md"), "[[b]] [[a]]").unwrap();
+        fs::write(dir.join("b.md"), "[[c]]").unwrap();
+        fs::write(dir.join("c.md"), "").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        assert!(scan(&st, &dir).directed);
+        let neighbors = |id: &str| get_neighbors(id.into(), st.clone()).unwrap();
+
+        assert_eq!(neighbors("b"), vec!["c"]);
+        assert_eq!(neighbors("a"), vec!["b"]);
+
+        st.config.lock().unwrap().undirected = true;
+        assert_eq!(neighbors("b"), vec!["a", "c"]);
+        assert!(!scan(&st, &dir).directed);
+        assert!(get_neighbors("zz".into(), st.clone()).is_err());
+    }
+
+    #[test]
+    fn parse_content_matches_the_parser() {
+        let app = app_with(state_for(&temp_vault("cmd-parse-content")));
+        let text = "---\ntitle: T\ntags: [a]\n---\n# Heading\nSee [[x]] #b";
+
+        let dto = parse_content(text.into(), app.state()).unwrap();
+
+        let parsed = parser::parse_markdown(text);
+        assert_eq!(dto.wiki_links, parsed.wiki_links);
+        assert_eq!(dto.hashtags, parsed.hashtags);
+        assert_eq!(
+            dto.frontmatter.as_ref(),
+            parsed.frontmatter.as_ref().map(|f| &f.fields)
+        );
+        let json = serde_json::to_value(&dto).unwrap();
+        assert_eq!(json["frontmatter"]["title"], "T");
+        assert!(parse_content("plain".into(), app.state())
+            .unwrap()
+            .frontmatter
+            .is_none());
+    }
+
+    #[test]
+    fn count_links_counts_repeats() {
+        let dir = temp_vault("cmd-count-links");
+        fs::write(dir.join("a.md"), "[[b]] [[b]] and [[b]] [[c]]").unwrap();
+        fs::write(dir.join("b.md"), "[[c]]").unwrap();
+        fs::write(dir.join("c.md"), "").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        let count = |from: &str, to: &str| count_links(from.into(), to.into(), st.clone());
+
+        assert_eq!(count("a", "b").unwrap(), 3);
+        assert_eq!(count("a", "c").unwrap(), 1);
+        assert_eq!(count("c", "a").unwrap(), 0);
+        assert!(count("zz", "a").is_err());
+    }
+
+    #[test]
+    fn health_check_reports_each_problem() {
+        let dir = temp_vault("cmd-health");
+        fs::write(dir.join("a.md"), "[[b]]").unwrap();
+        let template = dir.join("tpl.txt");
+        fs::write(&template, "x").unwrap();
+        let config = AppConfig {
+            root_dir: Some(dir.to_string_lossy().to_string()),
+            template_phantom_node: Some(template.to_string_lossy().to_string()),
+            ..AppConfig::default()
+        };
+        let report = |config: AppConfig| {
+            let app = app_with(AppState::new(config, Default::default()));
+            scan(&app.state::<AppState>(), &dir);
+            health_check(app.state()).unwrap()
+        };
+
+        let healthy = report(config.clone());
+        assert!(healthy.problems.is_empty(), "{:?}", healthy.problems);
+        assert!(healthy.root_dir_valid && healthy.template_readable);
+        assert_eq!((healthy.node_count, healthy.edge_count), (2, 1));
+
+        let missing_root = report(AppConfig {
+            root_dir: Some(dir.join("missing").to_string_lossy().to_string()),
+            ..config.clone()
+        });
+        assert!(!missing_root.root_dir_valid && missing_root.template_readable);
+        assert_eq!(missing_root.problems.len(), 1);
+
+        let missing_template = report(AppConfig {
+            template_phantom_node: Some(dir.join("nope.md").to_string_lossy().to_string()),
+            ..config
+        });
+        assert!(missing_template.root_dir_valid && !missing_template.template_readable);
+        assert_eq!(missing_template.problems.len(), 1);
+
+        let app = app_with(AppState::new(AppConfig::default(), Default::default()));
+        let unconfigured = health_check(app.state()).unwrap();
+        assert!(!unconfigured.root_dir_valid && !unconfigured.template_readable);
+        assert_eq!(unconfigured.problems.len(), 2);
+    }
+
+    #[test]
+    fn import_skips_collisions_and_bad_names() {
+        let dir = temp_vault("cmd-import");
+        fs::write(dir.join("old.md"), "keep").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        let note = |name: &str, content: &str| NoteInput {
+            name: name.into(),
+            content: content.into(),
+        };
+        let notes = vec![
+            note("new", "# {{title}} [[old]]"),
+            note("old", "x"),
+            note("../evil", "x"),
+            note("two", ""),
+        ];
+
+        let report = import_notes(notes, st.clone()).unwrap();
+
+        assert_eq!((report.created, report.skipped, report.failed), (2, 1, 1));
+        let statuses: Vec<_> = report.results.iter().map(|result| result.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ImportStatus::Created,
+                ImportStatus::Skipped,
+                ImportStatus::Failed,
+                ImportStatus::Created
+            ]
+        );
+        assert_eq!(read(dir.join("old.md")), "keep");
+        assert_eq!(read(dir.join("new.md")), "# new [[old]]");
+        assert!(!dir.join("../evil.md").exists());
+        assert_eq!(st.lock_cache().count_links("new", "old"), Some(1));
+
+        st.pop_operation().unwrap().revert().unwrap();
+        assert!(!dir.join("new.md").exists() && !dir.join("two.md").exists());
+        assert_eq!(read(dir.join("old.md")), "keep");
+    }
+
+    #[test]
+    fn search_ranks_fuzzy_matches() {
+        let dir = temp_vault("cmd-search");
+        for name in ["graph", "geography", "grh", "other"] {
+            fs::write(dir.join(format!("{}.md", name)), "").unwrap();
+        }
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let results = search_notes("grh".into(), 10, st.clone()).unwrap();
+
+        let ids: Vec<_> = results.iter().map(|result| result.id.as_str()).collect();
+        assert_eq!(ids, vec!["grh", "graph", "geography"]);
+        assert_eq!(search_notes("grh".into(), 1, st.clone()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn similar_notes_ignore_frontmatter_and_empty_notes() {
+        let dir = temp_vault("cmd-similar");
+        fs::write(
+            dir.join("a.md"),
+            "The quick brown fox jumps over the lazy dog today",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.md"),
+            "---\ntitle: B\n---\nThe quick brown fox jumps over the lazy dog",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("c.md"),
+            "Completely unrelated shopping list: apples, milk",
+        )
+        .unwrap();
+        fs::write(dir.join("e.md"), "").unwrap();
+        fs::write(dir.join("f.md"), "").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let pairs = find_similar_notes(0.5, st.clone()).unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].a.as_str(), pairs[0].b.as_str()), ("a", "b"));
+        assert!(pairs[0].similarity > 0.85);
+
+        let all = find_similar_notes(0.0, st.clone()).unwrap();
+        let unrelated = all.iter().find(|pair| pair.a == "a" && pair.b == "c");
+        assert!(unrelated.is_none_or(|pair| pair.similarity < 0.2));
+        assert!(all.iter().all(|pair| !(pair.a == "e" && pair.b == "f")));
+        assert!(find_similar_notes(1.5, st.clone()).is_err());
+    }
+
+    #[test]
+    fn operations_revert_in_reverse_order() {
+        let dir = temp_vault("cmd-undo");
+        fs::write(dir.join("a.md"), "#todo first [[b]]").unwrap();
+        fs::write(dir.join("b.md"), "#todo/x second").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        assert!(st.pop_operation().is_none());
+
+        assert_eq!(
+            rename_tag("todo".into(), "tasks".into(), st.clone()).unwrap(),
+            2
+        );
+        append_to_note("a".into(), "more".into(), st.clone()).unwrap();
+        append_to_note("c".into(), "new".into(), st.clone()).unwrap();
+        let imported = NoteInput {
+            name: "imp".into(),
+            content: "x".into(),
+        };
+        import_notes(vec![imported], st.clone()).unwrap();
+
+        let undo = || st.pop_operation().unwrap().revert().unwrap();
+        undo();
+        assert!(!dir.join("imp.md").exists());
+        undo();
+        assert!(!dir.join("c.md").exists());
+        undo();
+        assert_eq!(read(dir.join("a.md")), "#tasks first [[b]]");
+        undo();
+        assert_eq!(read(dir.join("a.md")), "#todo first [[b]]");
+        assert_eq!(read(dir.join("b.md")), "#todo/x second");
+        assert!(st.pop_operation().is_none());
+    }
+
+    #[test]
+    fn outgoing_links_mark_phantoms() {
+        let dir = temp_vault("cmd-outgoing");
+        fs::write(dir.join("a.md"), "[[missing]] [[b]] [[missing]]").unwrap();
+        fs::write(dir.join("b.md"), "").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let links = get_outgoing_links("a".into(), st.clone()).unwrap();
+
+        let links: Vec<_> = links
+            .iter()
+            .map(|link| (link.target.as_str(), link.exists))
+            .collect();
+        assert_eq!(links, vec![("missing", false), ("b", true)]);
+        assert!(get_outgoing_links("b".into(), st.clone())
+            .unwrap()
+            .is_empty());
+        assert!(get_outgoing_links("zz".into(), st.clone()).is_err());
+    }
+
+    #[test]
+    fn backlinks_report_lists_missing_notes() {
+        let dir = temp_vault("cmd-backlinks-report");
+        fs::write(dir.join("a.md"), "[[b]] [[ghost]]").unwrap();
+        fs::write(dir.join("b.md"), "[[a]] [[b]]").unwrap();
+        fs::write(dir.join("c.md"), "[[b]] [[ghost]]").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        let out = dir.join("report.txt");
+
+        export_backlinks_report(out.to_string_lossy().to_string(), st.clone()).unwrap();
+
+        assert_eq!(
+            read(out),
+            "# Backlinks Report\n\n## Notes\n\n### a\n\n- [[b]]\n\n### b\n\n- [[a]]\n- [[c]]\n\n\
+             ### c\n\n_No backlinks_\n\n## Missing Notes\n\n### ghost\n\n- [[a]]\n- [[c]]\n"
+        );
+    }
+
+    #[test]
+    fn pagerank_rejects_bad_damping() {
+        let app = app_with(state_for(&temp_vault("cmd-pagerank")));
+
+        assert!(get_pagerank(5, 1.5, app.state()).is_err());
+        assert!(get_pagerank(5, 0.85, app.state()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn vault_extensions_cover_every_root() {
+        let dir = temp_vault("cmd-extensions");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        for file in ["a.md", "sub/b.md", "t.txt", "README"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let app = app_with(state_for(&dir));
+
+        let counts = get_vault_extensions(app.state()).unwrap();
+
+        let counts: Vec<_> = counts
+            .iter()
+            .map(|c| (c.extension.as_str(), c.count))
+            .collect();
+        assert_eq!(counts, vec![("md", 2), ("", 1), ("txt", 1)]);
+    }
+
+    #[test]
+    fn app_info_describes_the_build() {
+        let info = get_app_info();
+
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.os.is_empty() && !info.arch.is_empty());
+        assert_eq!(info.debug, cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn inspect_note_reads_disk_not_cache() {
+        let dir = temp_vault("cmd-inspect");
+        fs::write(dir.join("a.md"), "[[b]] #old").unwrap();
+        fs::write(dir.join("b.md"), "").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        fs::write(
+            dir.join("a.md"),
+            "---\nstatus: draft\n---\n[[b]] [[ghost]] [[b]] #new",
+        )
+        .unwrap();
+
+        let inspection = inspect_note("a".into(), st.clone()).unwrap();
+
+        let links: Vec<_> = inspection
+            .links
+            .iter()
+            .map(|link| (link.target.as_str(), link.exists))
+            .collect();
+        assert_eq!(links, vec![("b", true), ("ghost", false)]);
+        assert_eq!(inspection.hashtags, vec!["new"]);
+        assert_eq!(
+            serde_json::to_value(&inspection.frontmatter).unwrap()["status"],
+            "draft"
+        );
+        assert_eq!(st.lock_cache().get_node("a").unwrap().hashtags, vec!["old"]);
+
+        assert!(inspect_note("ghost".into(), st.clone()).is_err());
+    }
+
+    #[test]
+    fn inspect_note_resolves_relative_links() {
+        let dir = temp_vault("cmd-inspect-relative");
+        fs::create_dir_all(dir.join("projects")).unwrap();
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(dir.join("shared/note.md"), "").unwrap();
+        fs::write(
+            dir.join("projects/plan.md"),
+            "[[../shared/note]] [[../missing/x]]",
+        )
+        .unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let inspection = inspect_note("plan".into(), st.clone()).unwrap();
+
+        let links: Vec<_> = inspection
+            .links
+            .iter()
+            .map(|link| (link.target.as_str(), link.exists))
+            .collect();
+        assert_eq!(links, vec![("note", true), ("../missing/x", false)]);
+    }
+
+    #[test]
+    fn organize_by_tag_moves_notes_and_reports_collisions() {
+        let dir = temp_vault("cmd-organize");
+        fs::write(dir.join("a.md"), "#proj [[b]]").unwrap();
+        fs::write(dir.join("b.md"), "#Proj").unwrap();
+        fs::write(dir.join("c.md"), "#other").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        assert_eq!(
+            organize_by_tag("#proj".into(), "projects/web".into(), st.clone()).unwrap(),
+            2
+        );
+        let dest = dir.join("projects/web");
+        assert!(dest.join("a.md").exists() && dest.join("b.md").exists());
+        assert!(!dir.join("a.md").exists() && dir.join("c.md").exists());
+        let moved = st.lock_cache().get_node("a").unwrap().file_path.clone();
+        assert_eq!(moved, dest.join("a.md").to_string_lossy());
+        assert_eq!(st.lock_cache().graph().edges.len(), 1);
+
+        fs::create_dir_all(dir.join("misc")).unwrap();
+        fs::create_dir_all(dir.join("archive")).unwrap();
+        fs::write(dir.join("misc/x.md"), "#done").unwrap();
+        fs::write(dir.join("y.md"), "#done").unwrap();
+        fs::write(dir.join("archive/y.md"), "old").unwrap();
+        scan(&st, &dir);
+        let error = organize_by_tag("done".into(), "archive".into(), st.clone()).unwrap_err();
+        assert!(
+            error.contains("Moved 1 notes") && error.contains("y ("),
+            "{}",
+            error
+        );
+        assert!(dir.join("archive/x.md").exists() && dir.join("y.md").exists());
+        assert_eq!(read(dir.join("archive/y.md")), "old");
+        assert!(organize_by_tag("done".into(), "../out".into(), st.clone()).is_err());
+
+        st.pop_operation().unwrap().revert().unwrap();
+        assert!(dir.join("misc/x.md").exists() && !dir.join("archive/x.md").exists());
+    }
+
+    #[test]
+    fn graph_for_tags_any_and_all() {
+        let app = app_with(state_for(&temp_vault("cmd-graph-tags")));
+        let st = app.state::<AppState>();
+        st.replace_cache(cache_of(vec![
+            markdown_file("a", "#rust #web [[b]] [[c]] [[ghost]]"),
+            markdown_file("b", "#web [[a]]"),
+            markdown_file("c", "#Rust [[d]]"),
+            markdown_file("d", "#db"),
+        ]));
+        let query = |tags: &[&str], mode: &str| {
+            let tags = tags.iter().map(|t| t.to_string()).collect();
+            get_graph_for_tags(tags, mode.into(), st.clone())
+        };
+
+        let any = query(&["rust", "#web"], "any").unwrap();
+        let mut ids: Vec<_> = any.nodes.iter().map(|node| node.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(any.edges.len(), 3);
+
+        let all = query(&["rust", "web"], "all").unwrap();
+        assert_eq!(
+            all.nodes
+                .iter()
+                .map(|node| node.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a"]
+        );
+        assert!(all.edges.is_empty());
+        assert!(query(&["rust"], "some").is_err());
+        assert!(query(&[], "any").is_err());
+    }
+
+    #[test]
+    fn tag_subgraph_matches_exact_tags() {
+        let app = app_with(state_for(&temp_vault("cmd-tag-subgraph")));
+        let st = app.state::<AppState>();
+        st.replace_cache(cache_of(vec![
+            markdown_file("a", "#Rust [[b]] [[c]] [[x]]"),
+            markdown_file("b", "#rust [[a]]"),
+            markdown_file("c", "#web [[a]]"),
+            markdown_file("d", "#rust/async [[a]]"),
+        ]));
+
+        let sub = get_tag_subgraph("#rust".into(), st.clone()).unwrap();
+
+        let mut ids: Vec<_> = sub.nodes.iter().map(|node| node.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b"]);
+        let mut edges: Vec<_> = sub
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+        edges.sort();
+        assert_eq!(edges, vec![("a", "b"), ("b", "a")]);
+        assert!(get_tag_subgraph(" # ".into(), st.clone()).is_err());
+    }
+
+    #[test]
+    fn outline_and_heading_search() {
+        let dir = temp_vault("cmd-headings");
+        fs::write(dir.join("a.md"), "# Intro\ntext\n\n## Setup\n").unwrap();
+        fs::write(
+            dir.join("b.md"),
+            "---\ntitle: B\n---\nx\n```\n# Installation\n```\n### Installation guide\n",
+        )
+        .unwrap();
+        fs::write(dir.join("n.md"), "# One\n```\n# not\n```\n## Two").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let outline = get_note_outline("n".into(), st.clone()).unwrap();
+        assert_eq!(
+            outline.iter().map(|h| h.line).collect::<Vec<_>>(),
+            vec![1, 5]
+        );
+
+        let hits = find_heading("install".into(), st.clone()).unwrap();
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(
+            (hit.node_id.as_str(), hit.text.as_str(), hit.line, hit.level),
+            ("b", "Installation guide", 8, 3)
+        );
+        let hits = find_heading("setup".into(), st.clone()).unwrap();
+        assert_eq!((hits[0].node_id.as_str(), hits[0].line), ("a", 4));
+        assert!(find_heading("  ".into(), st.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_notes_skips_phantoms() {
+        let app = app_with(state_for(&temp_vault("cmd-list-notes")));
+        let st = app.state::<AppState>();
+        st.replace_cache(cache_of(vec![
+            markdown_file("zeta", "[[ghost]]"),
+            markdown_file("Alpha", ""),
+            markdown_file("beta", "[[zeta]]"),
+        ]));
+
+        assert_eq!(
+            list_notes(st.clone()).unwrap(),
+            vec!["Alpha", "beta", "zeta"]
+        );
+        assert_eq!(get_all_tags(st.clone()).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn frontmatter_is_read_from_disk() {
+        let dir = temp_vault("cmd-frontmatter");
+        fs::write(
+            dir.join("a.md"),
+            "\u{feff}---\ntitle: A\ntags: [x, y]\n---\nbody [[b]]\n",
+        )
+        .unwrap();
+        fs::write(dir.join("b.md"), "no frontmatter\n---\nk: v\n---\n").unwrap();
+        fs::write(dir.join("c.md"), "---\nk: v\nnever closed\n").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        let frontmatter = |id: &str| get_frontmatter(id.into(), st.clone());
+
+        let expected = serde_json::json!({"title": "A", "tags": ["x", "y"]});
+        assert_eq!(frontmatter("a").unwrap(), expected);
+        assert_eq!(frontmatter("b").unwrap(), serde_json::json!({}));
+        assert_eq!(frontmatter("c").unwrap(), serde_json::json!({}));
+        assert!(frontmatter("ghost").is_err());
+    }
+
+    #[test]
+    fn section_search_requires_heading_sections() {
+        let dir = temp_vault("cmd-sections");
+        fs::write(
+            dir.join("a.md"),
+            "intro\n## Setup\n# Main Title\n# Second\n",
+        )
+        .unwrap();
+        fs::write(dir.join("b.md"), "# Weekly meeting\n[[ghost]]").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        assert!(find_notes_by_section("main".into(), st.clone()).is_err());
+
+        st.config.lock().unwrap().heading_sections = true;
+        scan(&st, &dir);
+        let hits = find_notes_by_section("MEET".into(), st.clone()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            (hits[0].node_id.as_str(), hits[0].section.as_str()),
+            ("b", "Weekly meeting")
+        );
+        let hits = find_notes_by_section("e".into(), st.clone()).unwrap();
+        let ids: Vec<_> = hits.iter().map(|hit| hit.node_id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "a", "a", "b"]);
+    }
+
+    #[test]
+    fn resolve_link_strips_aliases_and_headings() {
+        let app = app_with(state_for(&temp_vault("cmd-resolve-link")));
+        let st = app.state::<AppState>();
+        st.replace_cache(cache_of(vec![markdown_file(
+            "Alice",
+            "---\naliases: [Al, Ally]\n---\n",
+        )]));
+        let resolve = |raw: &str| resolve_link(raw.into(), st.clone());
+
+        assert_eq!(resolve("[[Alice|Al]]").unwrap(), "Alice");
+        assert_eq!(resolve("Al").unwrap(), "Alice");
+        assert_eq!(resolve("[[Ally#Intro]]").unwrap(), "Alice");
+        assert_eq!(resolve(" [[Alice.md]] ").unwrap(), "Alice");
+        assert_eq!(resolve("[[alice]]").unwrap(), "alice");
+        assert_eq!(resolve("[[Nobody.markdown|N]]").unwrap(), "Nobody");
+        assert!(resolve("[[#Setup]]").is_err());
+        assert!(resolve("[[ |x]]").is_err());
+    }
+
+    #[test]
+    fn unlinked_mentions_skip_links_and_code() {
+        let dir = temp_vault("cmd-mentions");
+        fs::write(
+            dir.join("Rust.md"),
+            "---\ntitle: Rust Language\naliases: [ferris]\n---\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("a.md"),
+            "---\ntopic: rust\n---\nI like [[Rust]] a lot\nrust is fast\n\
+             [[Rust|rust]] again, and Rust too\nRustacean\n```\nrust in code\n```\n\
+             the rust language, Ferris!\n",
+        )
+        .unwrap();
+        fs::write(dir.join("b.md"), "nothing here\n[[Rust]]\n[[ghost]]").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let mentions = get_unlinked_mentions("Rust".into(), st.clone()).unwrap();
+
+        let found: Vec<_> = mentions
+            .iter()
+            .map(|m| (m.source.as_str(), m.line))
+            .collect();
+        assert_eq!(found, vec![("a", 5), ("a", 6), ("a", 11)]);
+        assert_eq!(mentions[0].text, "rust is fast");
+        assert!(get_unlinked_mentions("ghost".into(), st.clone()).is_err());
+    }
+
+    #[test]
+    fn link_positions_come_from_disk() {
+        let dir = temp_vault("cmd-link-positions");
+        fs::write(dir.join("a.md"), "x [[b]]\n#tag").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        let positions = get_link_positions("a".into(), st.clone()).unwrap();
+        let expected = parser::parse_markdown_with_positions("x [[b]]\n#tag", &Default::default());
+
+        assert_eq!(
+            serde_json::to_value(&positions).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+        assert!(get_link_positions("b".into(), st.clone()).is_err());
+    }
+
+    #[test]
+    fn rename_note_rewrites_plain_aliased_and_heading_links() {
+        let dir = temp_vault("cmd-rename-note");
+        fs::write(dir.join("old.md"), "self [[old#Top]]").unwrap();
+        fs::write(dir.join("a.md"), "see [[old]] and [[old|the plan]]").unwrap();
+        fs::write(
+            dir.join("b.md"),
+            "[[old#Setup]] | [[older]] [[old#Setup|setup]]",
+        )
+        .unwrap();
+        fs::write(dir.join("c.md"), "mentions old only").unwrap();
+        fs::write(dir.join("older.md"), "x").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        assert!(rename_note("old".into(), "a/b".into(), st.clone()).is_err());
+        assert!(rename_note("old".into(), "older".into(), st.clone()).is_err());
+        assert!(rename_note("ghost".into(), "x".into(), st.clone()).is_err());
+        assert_eq!(
+            rename_note("old".into(), "plan".into(), st.clone()).unwrap(),
+            3
+        );
+
+        assert!(!dir.join("old.md").exists());
+        assert_eq!(read(dir.join("plan.md")), "self [[plan#Top]]");
+        assert_eq!(read(dir.join("a.md")), "see [[plan]] and [[plan|the plan]]");
+        assert_eq!(
+            read(dir.join("b.md")),
+            "[[plan#Setup]] | [[older]] [[plan#Setup|setup]]"
+        );
+        assert_eq!(read(dir.join("c.md")), "mentions old only");
+
+        let normalize = |graph: &GraphData| {
+            let mut nodes: Vec<_> = graph
+                .nodes
+                .iter()
+                .map(|node| (node.id.clone(), node.value, node.group.clone()))
+                .collect();
+            let mut edges: Vec<_> = graph
+                .edges
+                .iter()
+                .map(|edge| (edge.from.clone(), edge.to.clone()))
+                .collect();
+            nodes.sort();
+            edges.sort();
+            (nodes, edges)
+        };
+        let incremental = normalize(st.lock_cache().graph());
+        let full = normalize(&scan(&st, &dir));
+        assert_eq!(incremental, full);
+        assert!(!full.0.iter().any(|(id, _, _)| id == "old"));
+
+        st.pop_operation().unwrap().revert().unwrap();
+        assert_eq!(read(dir.join("old.md")), "self [[old#Top]]");
+        assert_eq!(read(dir.join("a.md")), "see [[old]] and [[old|the plan]]");
+        assert_eq!(
+            read(dir.join("b.md")),
+            "[[old#Setup]] | [[older]] [[old#Setup|setup]]"
+        );
+        assert!(!dir.join("plan.md").exists());
+    }
+
+    #[test]
+    fn cached_graph_respects_max_nodes() {
+        let dir = temp_vault("cmd-max-nodes");
+        for (name, content) in [
+            ("a", "[[hub]] [[b]]"),
+            ("b", "[[hub]]"),
+            ("hub", ""),
+            ("z", ""),
+        ] {
+            fs::write(dir.join(format!("{}.md", name)), content).unwrap();
+        }
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+        assert!(!get_cached_graph(st.clone()).unwrap().truncated);
+
+        st.config.lock().unwrap().max_nodes = Some(2);
+        let graph = get_cached_graph(st.clone()).unwrap();
+
+        assert!(graph.truncated);
+        let mut ids: Vec<_> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["b", "hub"]);
+        assert_eq!(st.lock_cache().graph().nodes.len(), 4);
+    }
+
+    #[test]
+    fn tags_and_tag_lookups() {
+        let dir = temp_vault("cmd-tags");
+        fs::write(dir.join("n1.md"), "#a/b #a").unwrap();
+        fs::write(dir.join("n2.md"), "#a/c #b").unwrap();
+        let app = app_with(state_for(&dir));
+        let st = app.state::<AppState>();
+        scan(&st, &dir);
+
+        assert_eq!(
+            get_all_tags(st.clone()).unwrap(),
+            vec!["a", "a/b", "a/c", "b"]
+        );
+        assert_eq!(
+            get_notes_by_tag("#a".into(), false, st.clone()).unwrap(),
+            vec!["n1"]
+        );
+        assert_eq!(
+            get_notes_by_tag("a".into(), true, st.clone()).unwrap(),
+            vec!["n1", "n2"]
+        );
+
+        let tree = get_tag_tree(st.clone()).unwrap();
+        let names: Vec<_> = tree
+            .children
+            .iter()
+            .map(|child| child.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(tree.children[0].total_count, 2);
+    }
+
+    #[test]
+    fn complete_link_and_phantom_clusters() {
+        let app = app_with(state_for(&temp_vault("cmd-complete-link")));
+        let st = app.state::<AppState>();
+        st.replace_cache(cache_of(vec![
+            markdown_file("project", "[[ghost]] [[spirit]]"),
+            markdown_file("my project", ""),
+        ]));
+
+        assert_eq!(
+            complete_link("pro".into(), st.clone()).unwrap(),
+            vec!["project", "my project"]
+        );
+        assert_eq!(
+            get_phantom_clusters(st.clone()).unwrap(),
+            vec![vec!["ghost"], vec!["spirit"]]
+        );
+    }
+}
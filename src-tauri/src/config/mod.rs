@@ -15,14 +15,41 @@
 //!
 //! # Thread Safety
 //!
-//! The `AppState` struct wraps configuration in an `Arc<Mutex<>>` to provide safe
-//! concurrent access from multiple Tauri command handlers.
+//! The `AppState` struct wraps configuration and the graph cache in `Arc<Mutex<>>`
+//! to provide safe concurrent access from multiple Tauri command handlers.
 
+use crate::cache::{CacheKey, GraphCache, GraphDelta};
+use crate::events::{self, EventEmitter};
+use crate::graph::{
+    apply_group_colors, build_graph_with, BuildOptions, GraphData, LabelFormat, NodeSizeScale,
+};
+use crate::parser::LinkSyntax;
+use crate::scanner::{
+    content_hash, read_markdown_file, roots_fingerprint, scan_directories, ScanFilter,
+};
+use crate::undo::{Operation, OperationLog};
 use clap::Parser;
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
 use serde::{Deserialize, Serialize};
+use serde_json::error::Category;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Interval at which `AppState::start_template_watch` checks the template file.
+pub const TEMPLATE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Quiet period after the last file system event on a path before
+/// `AppState::start_vault_watch` applies the change.
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Throttle window used when `modify_throttle_ms` is not configured.
+pub const DEFAULT_MODIFY_THROTTLE_MS: u64 = 2000;
 
 /// Application configuration structure.
 ///
@@ -33,27 +60,360 @@ use std::sync::{Arc, Mutex};
 ///
 /// * `root_dir` - Optional path to the root directory containing markdown files to scan.
 ///   If None, the application may prompt the user or use a default location.
+/// * `root_dirs` - Additional root directories merged into every scan, so notes in
+///   several vaults form one graph. When two notes share a name, the one found first
+///   (in `root_dir`, then `root_dirs` order) wins.
 /// * `template_phantom_node` - Optional path to the template file used for creating phantom nodes.
 ///   When a phantom node is converted to a real file, this template is used as the base content.
+/// * `include_patterns` - Glob patterns (relative to the root directory) of files to scan.
+///   When empty, every markdown file is scanned.
+/// * `ignore_patterns` - Glob patterns (relative to the root directory) of files to skip.
+///   Ignore patterns take precedence over include patterns.
+/// * `template_variables` - Custom template variables (name without braces to value).
+///   Values may contain other placeholders such as `{{date}}`.
+/// * `previewer` - Settings for the note previewer (see `PreviewerConfig`)
+/// * `group_colors` - CSS colors per node group (e.g. `{"phantom": "#555"}`), sent to the
+///   frontend as each node's `color`
+/// * `wiki_link_pattern` - Optional regex for internal links (e.g. `\(\(([^)]+)\)\)` for
+///   `((target))`), whose first capture group is the link target. Defaults to `[[target]]`.
+/// * `skip_symlinks` - Whether symlinked notes and directories are ignored while scanning.
+///   By default they are resolved to their targets and deduplicated.
+/// * `skip_hidden` - Whether hidden files and files in hidden directories (any path
+///   component starting with `.`, e.g. `.trash/note.md`) are ignored while scanning
+/// * `label_max_length` - Maximum number of characters shown in node labels; longer labels
+///   are truncated with `…`. None shows full labels.
+/// * `strip_date_prefix` - Remove a leading `YYYY-MM-DD ` date from node labels
+/// * `namespaces` - Namespace prefixes (e.g. `people`) removed from node labels, so
+///   `[[people/Alice]]` is labeled `Alice`. Node IDs keep the prefix.
+/// * `namespace_groups` - Put notes in one of the `namespaces` into a group named after
+///   it: real notes by their folder relative to their root (unless their frontmatter
+///   sets a group), phantom nodes by their link target (`[[people/Alice]]`).
+/// * `min_content_chars` - Minimum number of characters (excluding frontmatter) a file
+///   needs to become a note. Shorter stub files are left out of the graph and only
+///   appear as phantom nodes if linked. None keeps every file.
+/// * `allow_self_links` - Keep links from a note to itself. By default they are
+///   dropped, so a note can't inflate its own backlink count.
+/// * `scan_on_startup` - Populate the graph cache when the app starts (default true).
+///   When false the app starts with an empty graph, so the window appears sooner for
+///   huge vaults, and the frontend scans on demand with `scan_folder`.
+/// * `node_size_scale` - Scale (`linear`, `log` or `sqrt`) deriving each node's `size`
+///   from its incoming link count. None sizes linearly; `value` stays the raw count.
+/// * `rescan_interval_secs` - Interval of a periodic full rescan that catches changes made
+///   while nothing was updating the graph (see `AppState::start_periodic_rescan`). None
+///   disables it; must not be 0.
+/// * `drop_excluded_links` - Drop links to notes that opt out of the graph with `graph: false`
+///   or `exclude_from_graph: true` frontmatter, instead of showing them as phantom nodes
+/// * `skip_blockquote_links` - Ignore wiki-links on blockquote lines (starting with `>`), so
+///   quoted or cited text doesn't add edges
+/// * `file_metadata` - Include each note's file size (`size_bytes`) and modification time
+///   (`modified`) on its node. Off by default to save a `stat` per note.
+/// * `heading_sections` - Expose each note's H1 and H2 headings as `sections` on its node,
+///   for navigation and `find_notes_by_section`
+/// * `watch_template` - Watch `template_phantom_node` and emit a `template-updated` event
+///   when it changes (see `AppState::start_template_watch`)
+/// * `scan_threads` - Number of threads reading note files during a scan. None uses the
+///   available parallelism of the machine; must not be 0.
+/// * `max_nodes` - Maximum number of nodes `scan_folder` and `get_cached_graph` return.
+///   Larger graphs are cut down to their highest-value nodes and flagged as `truncated`
+///   (see `graph::truncate_graph`); the cache keeps the full graph. None returns every
+///   node; must not be 0.
+/// * `modify_throttle_ms` - Minimum interval in milliseconds between two reparses of the
+///   same note by the vault watch; further changes within the window are applied once
+///   it ends (see `ModifyThrottle`). None uses `DEFAULT_MODIFY_THROTTLE_MS`.
+/// * `terminal_command` - Terminal emulator command the editor is launched in, e.g.
+///   `x-terminal-emulator -e`. Split at whitespace; `nvim <file>` is appended. None
+///   launches nvim directly.
+/// * `detach_editor` - Start the editor detached from the app (own process group, no
+///   inherited stdio), so it keeps running when the app exits
+/// * `undirected` - Treat links as bidirectional in neighbor queries and ask the frontend
+///   to render edges without arrows. Stored edges keep their direction.
+///
+/// # Unknown Keys
+///
+/// Unknown keys are rejected when deserializing, so a typo such as `root_directory`
+/// produces an error instead of being silently ignored.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct AppConfig {
     pub root_dir: Option<String>,
+    #[serde(default)]
+    pub root_dirs: Vec<String>,
     pub template_phantom_node: Option<String>,
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    #[serde(default)]
+    pub template_variables: HashMap<String, String>,
+    #[serde(default)]
+    pub previewer: PreviewerConfig,
+    #[serde(default)]
+    pub group_colors: HashMap<String, String>,
+    #[serde(default)]
+    pub wiki_link_pattern: Option<String>,
+    #[serde(default)]
+    pub skip_symlinks: bool,
+    #[serde(default)]
+    pub skip_hidden: bool,
+    #[serde(default)]
+    pub undirected: bool,
+    #[serde(default)]
+    pub label_max_length: Option<usize>,
+    #[serde(default)]
+    pub strip_date_prefix: bool,
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    #[serde(default)]
+    pub namespace_groups: bool,
+    #[serde(default)]
+    pub min_content_chars: Option<usize>,
+    #[serde(default)]
+    pub allow_self_links: bool,
+    #[serde(default = "default_scan_on_startup")]
+    pub scan_on_startup: bool,
+    #[serde(default)]
+    pub node_size_scale: Option<NodeSizeScale>,
+    #[serde(default)]
+    pub rescan_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub drop_excluded_links: bool,
+    #[serde(default)]
+    pub skip_blockquote_links: bool,
+    #[serde(default)]
+    pub file_metadata: bool,
+    #[serde(default)]
+    pub heading_sections: bool,
+    #[serde(default)]
+    pub watch_template: bool,
+    #[serde(default)]
+    pub scan_threads: Option<usize>,
+    #[serde(default)]
+    pub max_nodes: Option<usize>,
+    #[serde(default)]
+    pub modify_throttle_ms: Option<u64>,
+    #[serde(default)]
+    pub terminal_command: Option<String>,
+    #[serde(default)]
+    pub detach_editor: bool,
+}
+
+/// The graph is built at startup unless configured otherwise.
+fn default_scan_on_startup() -> bool {
+    true
+}
+
+/// Note previewer configuration.
+///
+/// Controls how note content is returned by `read_note` for display in the
+/// frontend preview panel.
+///
+/// # Fields
+///
+/// * `offset` - Number of leading lines to skip when previewing a note (e.g. to
+///   hide a fixed-size header). None means no lines are skipped.
+/// * `skip_frontmatter` - Whether a leading `---` frontmatter block is hidden from
+///   previews, whatever its length. Applied before `offset`, which then counts
+///   lines of the body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PreviewerConfig {
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub skip_frontmatter: bool,
 }
 
 impl Default for AppConfig {
-    /// Creates a default configuration with all fields set to None.
+    /// Creates a default configuration with all optional fields set to None
+    /// and no scan patterns.
     ///
     /// This serves as the fallback when no configuration file is found and no
     /// CLI arguments are provided.
     fn default() -> Self {
         Self {
             root_dir: None,
+            root_dirs: Vec::new(),
             template_phantom_node: None,
+            include_patterns: Vec::new(),
+            ignore_patterns: Vec::new(),
+            template_variables: HashMap::new(),
+            previewer: PreviewerConfig::default(),
+            group_colors: HashMap::new(),
+            wiki_link_pattern: None,
+            skip_symlinks: false,
+            skip_hidden: false,
+            undirected: false,
+            label_max_length: None,
+            strip_date_prefix: false,
+            namespaces: Vec::new(),
+            namespace_groups: false,
+            min_content_chars: None,
+            allow_self_links: false,
+            scan_on_startup: true,
+            node_size_scale: None,
+            rescan_interval_secs: None,
+            drop_excluded_links: false,
+            skip_blockquote_links: false,
+            file_metadata: false,
+            heading_sections: false,
+            watch_template: false,
+            scan_threads: None,
+            max_nodes: None,
+            modify_throttle_ms: None,
+            terminal_command: None,
+            detach_editor: false,
         }
     }
 }
 
+/// Value type of a configuration field, as described by `AppConfig::schema`.
+///
+/// Serialized in snake_case (e.g. `"string_list"`), so the frontend can pick an
+/// input widget per field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigFieldType {
+    /// Free text, such as a path or a regex
+    String,
+    /// A non-negative integer
+    Integer,
+    /// A checkbox
+    Bool,
+    /// A list of strings
+    StringList,
+    /// A map of string keys to string values
+    StringMap,
+}
+
+/// Description of one `AppConfig` field for rendering a settings form.
+///
+/// # Fields
+///
+/// * `name` - JSON key of the field; nested fields use dots (e.g. `previewer.offset`)
+/// * `field_type` - Value type of the field (see `ConfigFieldType`)
+/// * `optional` - Whether the field may be `null` (unset)
+/// * `description` - One-line explanation shown next to the input
+/// * `default` - Value used when the field is not configured
+/// * `value` - Value in the current configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigField {
+    pub name: String,
+    pub field_type: ConfigFieldType,
+    pub optional: bool,
+    pub description: String,
+    pub default: serde_json::Value,
+    pub value: serde_json::Value,
+}
+
+/// Name, type, optionality and description of every `AppConfig` field, in
+/// the order they are shown in a settings form.
+///
+/// There is no derive for this, so a field added to `AppConfig` must be added
+/// here as well.
+const CONFIG_FIELDS: &[(&str, ConfigFieldType, bool, &str)] = &[
+    ("root_dir", ConfigFieldType::String, true, "Directory containing the notes"),
+    (
+        "root_dirs",
+        ConfigFieldType::StringList,
+        false,
+        "Additional directories merged into the graph",
+    ),
+    (
+        "template_phantom_node",
+        ConfigFieldType::String,
+        true,
+        "Template file for notes created from phantom nodes",
+    ),
+    ("include_patterns", ConfigFieldType::StringList, false, "Glob patterns of files to scan"),
+    ("ignore_patterns", ConfigFieldType::StringList, false, "Glob patterns of files to skip"),
+    ("template_variables", ConfigFieldType::StringMap, false, "Custom template variables"),
+    ("previewer.offset", ConfigFieldType::Integer, true, "Leading lines hidden in previews"),
+    (
+        "previewer.skip_frontmatter",
+        ConfigFieldType::Bool,
+        false,
+        "Hide frontmatter in previews",
+    ),
+    ("group_colors", ConfigFieldType::StringMap, false, "CSS colors per node group"),
+    ("wiki_link_pattern", ConfigFieldType::String, true, "Regex matching internal links"),
+    ("skip_symlinks", ConfigFieldType::Bool, false, "Ignore symlinked notes and directories"),
+    ("skip_hidden", ConfigFieldType::Bool, false, "Ignore hidden files and directories"),
+    ("undirected", ConfigFieldType::Bool, false, "Treat links as bidirectional"),
+    ("label_max_length", ConfigFieldType::Integer, true, "Maximum characters in node labels"),
+    ("strip_date_prefix", ConfigFieldType::Bool, false, "Remove leading dates from node labels"),
+    ("namespaces", ConfigFieldType::StringList, false, "Namespace prefixes hidden in node labels"),
+    ("namespace_groups", ConfigFieldType::Bool, false, "Group notes by namespace"),
+    (
+        "min_content_chars",
+        ConfigFieldType::Integer,
+        true,
+        "Minimum characters for a file to become a note",
+    ),
+    ("allow_self_links", ConfigFieldType::Bool, false, "Keep links from a note to itself"),
+    ("scan_on_startup", ConfigFieldType::Bool, false, "Build the graph when the app starts"),
+    (
+        "node_size_scale",
+        ConfigFieldType::String,
+        true,
+        "Node size scale: linear, log or sqrt",
+    ),
+    (
+        "rescan_interval_secs",
+        ConfigFieldType::Integer,
+        true,
+        "Seconds between periodic full rescans",
+    ),
+    (
+        "drop_excluded_links",
+        ConfigFieldType::Bool,
+        false,
+        "Drop links to notes excluded from the graph",
+    ),
+    (
+        "skip_blockquote_links",
+        ConfigFieldType::Bool,
+        false,
+        "Ignore wiki-links inside blockquotes",
+    ),
+    (
+        "file_metadata",
+        ConfigFieldType::Bool,
+        false,
+        "Include file size and modification time on nodes",
+    ),
+    (
+        "heading_sections",
+        ConfigFieldType::Bool,
+        false,
+        "Expose H1/H2 headings as note sections",
+    ),
+    (
+        "watch_template",
+        ConfigFieldType::Bool,
+        false,
+        "Emit template-updated when the template file changes",
+    ),
+    (
+        "scan_threads",
+        ConfigFieldType::Integer,
+        true,
+        "Threads used to read notes while scanning",
+    ),
+    (
+        "max_nodes",
+        ConfigFieldType::Integer,
+        true,
+        "Maximum number of nodes sent to the graph view",
+    ),
+    (
+        "modify_throttle_ms",
+        ConfigFieldType::Integer,
+        true,
+        "Minimum interval between two reparses of a note",
+    ),
+    ("terminal_command", ConfigFieldType::String, true, "Terminal the editor is launched in"),
+    ("detach_editor", ConfigFieldType::Bool, false, "Keep the editor running when the app exits"),
+];
+
 /// Command-line arguments structure.
 ///
 /// Defines the CLI interface using clap's derive macros. Arguments provided via
@@ -64,6 +424,7 @@ impl Default for AppConfig {
 /// * `--config <FILE>` - Path to a JSON configuration file to load
 /// * `--root-dir <DIR>` - Root directory containing markdown files to scan
 /// * `--template-phantom-node <FILE>` - Path to the template file for creating phantom nodes
+/// * `--previewer-offset <LINES>` - Number of leading lines to skip in note previews
 #[derive(Parser)]
 pub struct CliArgs {
     #[arg(long, value_name = "FILE")]
@@ -72,42 +433,203 @@ pub struct CliArgs {
     pub root_dir: Option<String>,
     #[arg(long, value_name = "FILE")]
     pub template_phantom_node: Option<String>,
+    #[arg(long, value_name = "LINES")]
+    pub previewer_offset: Option<usize>,
 }
 
 /// Thread-safe application state container.
 ///
-/// Manages application configuration with thread-safe access patterns using
-/// `Arc<Mutex<>>`. This allows multiple Tauri command handlers to safely read
-/// and update configuration concurrently.
+/// Manages application configuration and the in-memory graph cache with
+/// thread-safe access patterns using `Arc<Mutex<>>`. This allows multiple Tauri
+/// command handlers to safely read and update state concurrently.
 ///
 /// # Thread Safety
 ///
-/// The configuration is protected by a mutex, ensuring exclusive access during
-/// reads and writes. The `Arc` wrapper allows the state to be shared across
-/// threads without copying the entire configuration.
+/// The configuration and the cache are protected by separate mutexes, ensuring
+/// exclusive access during reads and writes. The `Arc` wrapper allows the state
+/// to be shared across threads without copying the entire configuration.
+///
+/// # Cache Persistence
+///
+/// When a `cache_file` is set (see `with_cache_file`), every rescan saves the
+/// new cache to that file and `restore_cache` can reuse it on the next launch.
+///
+/// # Undo
+///
+/// Commands that modify notes record their changes in `operations`, a bounded
+/// in-memory log reversed by the `undo_last` command.
+///
+/// # Background Timers
+///
+/// With `rescan_interval_secs` configured, `start_periodic_rescan` runs a timer
+/// thread whose handle is kept in `periodic_rescan`, shared by every clone.
+/// Likewise, with `watch_template` set, `start_template_watch` polls the
+/// template file on a thread kept in `template_watch`, and `start_vault_watch`
+/// runs the file watcher of the configured roots on a thread kept in
+/// `vault_watch`.
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub config: Arc<Mutex<AppConfig>>,
+    pub cache: Arc<Mutex<GraphCache>>,
+    pub config_source: ConfigSource,
+    pub cache_file: Option<PathBuf>,
+    pub snapshot: Arc<Mutex<Option<GraphCache>>>,
+    pub operations: Arc<Mutex<OperationLog>>,
+    pub periodic_rescan: Arc<Mutex<Option<TimerThread>>>,
+    pub template_watch: Arc<Mutex<Option<TimerThread>>>,
+    pub vault_watch: Arc<Mutex<Option<TimerThread>>>,
+}
+
+/// Handle of a running timer thread (periodic rescan, template or vault watch).
+///
+/// Dropping `stop` or sending on it ends the thread after its current tick.
+///
+/// # Fields
+///
+/// * `stop` - Channel the thread waits on between ticks
+/// * `handle` - Join handle of the thread
+#[derive(Debug)]
+pub struct TimerThread {
+    stop: Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+/// Stops a timer thread and waits for it to finish.
+///
+/// Waits for a tick that is in progress, so callers must not hold the
+/// configuration or cache lock.
+///
+/// # Returns
+///
+/// `true` if a timer was running.
+fn stop_timer(slot: &Mutex<Option<TimerThread>>) -> bool {
+    let Some(timer) = slot.lock().unwrap().take() else {
+        return false;
+    };
+
+    let _ = timer.stop.send(());
+
+    if timer.handle.join().is_err() {
+        eprintln!("[Timer] Timer thread panicked");
+    }
+
+    true
+}
+
+/// Per-file throttle of the vault watch.
+///
+/// Coalesces rapid changes to the same note, such as repeated autosaves that
+/// are further apart than `WATCH_DEBOUNCE`: a path is processed at most once
+/// per window. A change arriving within the window after the path was last
+/// processed is deferred until the window ends, and any number of deferred
+/// changes is processed once. Whether a processed change is a no-op is still
+/// decided by the content hash (see `GraphCache::handle_file_modified`).
+pub struct ModifyThrottle {
+    window: Duration,
+    processed: HashMap<PathBuf, Instant>,
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl ModifyThrottle {
+    /// Creates a throttle with the given window; a zero window throttles nothing.
+    pub fn new(window: Duration) -> Self {
+        ModifyThrottle {
+            window,
+            processed: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Offers a changed path at time `now`.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the path should be processed now, `false` if it was processed
+    /// less than the window ago and has been deferred (see `take_due`).
+    pub fn offer(&mut self, path: &Path, now: Instant) -> bool {
+        self.processed.retain(|_, at| now.duration_since(*at) < self.window);
+
+        match self.processed.get(path) {
+            Some(at) => {
+                self.pending.insert(path.to_path_buf(), *at + self.window);
+                false
+            }
+            None => {
+                self.processed.insert(path.to_path_buf(), now);
+                true
+            }
+        }
+    }
+
+    /// Removes and returns the deferred paths whose window has ended by `now`.
+    pub fn take_due(&mut self, now: Instant) -> Vec<PathBuf> {
+        let due: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, until)| **until <= now)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in &due {
+            self.pending.remove(path);
+            self.processed.insert(path.clone(), now);
+        }
+
+        due
+    }
+
+    /// Returns when the earliest deferred path is due, if any path is deferred.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.pending.values().min().copied()
+    }
+}
+
+/// Returns the modification time and size of a file, or None if it doesn't exist.
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+
+    Some((metadata.modified().ok()?, metadata.len()))
 }
 
 impl AppState {
     /// Creates a new AppState with the provided configuration.
     ///
-    /// Wraps the configuration in `Arc<Mutex<>>` for thread-safe access.
+    /// Wraps the configuration in `Arc<Mutex<>>` for thread-safe access and
+    /// starts with an empty graph cache.
     ///
     /// # Arguments
     ///
     /// * `config` - The initial application configuration
+    /// * `config_source` - Description of the configuration file used at startup
     ///
     /// # Returns
     ///
     /// A new `AppState` instance ready for use in Tauri's managed state system.
-    pub fn new(config: AppConfig) -> Self {
+    pub fn new(config: AppConfig, config_source: ConfigSource) -> Self {
         Self {
             config: Arc::new(Mutex::new(config)),
+            cache: Arc::new(Mutex::new(GraphCache::new())),
+            config_source,
+            cache_file: None,
+            snapshot: Arc::new(Mutex::new(None)),
+            operations: Arc::new(Mutex::new(OperationLog::default())),
+            periodic_rescan: Arc::new(Mutex::new(None)),
+            template_watch: Arc::new(Mutex::new(None)),
+            vault_watch: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Sets the file the graph cache is persisted to.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache_file` - Location of the persisted cache, usually
+    ///   `{app_data_dir}/graph-cache.json`; None disables persistence
+    pub fn with_cache_file(mut self, cache_file: Option<PathBuf>) -> Self {
+        self.cache_file = cache_file;
+        self
+    }
+
     /// Retrieves a clone of the current configuration.
     ///
     /// Acquires the mutex lock, clones the configuration, and returns it.
@@ -139,183 +661,2116 @@ impl AppState {
     pub fn update_config(&self, config: AppConfig) {
         *self.config.lock().unwrap() = config;
     }
-}
 
-impl AppConfig {
-    /// Loads configuration from a JSON file.
+    /// Acquires exclusive access to the graph cache.
     ///
-    /// Reads the file at the specified path and deserializes it into an `AppConfig`
-    /// structure. All fields in the JSON file are optional - missing fields will
-    /// be set to None.
+    /// The returned guard keeps the cache locked until it is dropped, so callers
+    /// should keep its scope short and avoid file I/O while holding it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (another thread panicked while holding the lock).
+    pub fn lock_cache(&self) -> MutexGuard<'_, GraphCache> {
+        self.cache.lock().unwrap()
+    }
+
+    /// Returns the path configuration changes are saved to.
+    ///
+    /// This is the file loaded at startup, or `config.json` in the current
+    /// working directory when no file was found, which `locate_config` picks up
+    /// on the next launch.
+    pub fn config_path(&self) -> PathBuf {
+        self.config_source
+            .path
+            .as_ref()
+            .map_or_else(|| PathBuf::from("config.json"), PathBuf::from)
+    }
+
+    /// Replaces the graph cache with a freshly built one.
     ///
     /// # Arguments
     ///
-    /// * `path` - Path to the JSON configuration file
+    /// * `cache` - The new cache contents, typically built from a full scan
     ///
-    /// # Returns
+    /// # Panics
     ///
-    /// * `Ok(AppConfig)` - Successfully loaded and parsed configuration
-    /// * `Err(String)` - Error message if file reading or JSON parsing fails
+    /// Panics if the mutex is poisoned (another thread panicked while holding the lock).
+    pub fn replace_cache(&self, cache: GraphCache) {
+        *self.cache.lock().unwrap() = cache;
+    }
+
+    /// Records a reversible operation for `undo_last`.
     ///
-    /// # Errors
+    /// Operations without file changes (e.g. an import that skipped every file)
+    /// have nothing to undo and are neither recorded nor logged.
     ///
-    /// Returns an error if:
-    /// - The file cannot be read (doesn't exist, permission denied, etc.)
-    /// - The file contains invalid JSON syntax
-    /// - The JSON structure doesn't match the expected schema
-    pub fn from_file(path: &PathBuf) -> Result<Self, String> {
-        let content = fs::read_to_string(path)
-            .map_err(|e| format!("Error reading configuration file: {}", e))?;
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (another thread panicked while holding the lock).
+    pub fn record_operation(&self, operation: Operation) {
+        if operation.changes.is_empty() {
+            return;
+        }
 
-        let config: AppConfig = serde_json::from_str(&content)
-            .map_err(|e| format!("Error parsing JSON configuration: {}", e))?;
+        println!("[Undo] Recorded: {}", operation.description);
+        self.operations.lock().unwrap().record(operation);
+    }
 
-        Ok(config)
+    /// Removes and returns the most recently recorded operation, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned (another thread panicked while holding the lock).
+    pub fn pop_operation(&self) -> Option<Operation> {
+        self.operations.lock().unwrap().pop()
     }
 
-    /// Creates configuration from CLI arguments.
+    /// Stores a copy of the current graph cache as the snapshot for later diffs.
     ///
-    /// Extracts configuration values from parsed command-line arguments.
-    /// Only the `root_dir` argument is currently mapped; the `config` argument
-    /// is used for file loading and not stored in the configuration itself.
+    /// Replaces any previous snapshot.
     ///
-    /// # Arguments
+    /// # Panics
     ///
-    /// * `args` - Parsed CLI arguments structure
+    /// Panics if a mutex is poisoned (another thread panicked while holding the lock).
+    pub fn take_snapshot(&self) {
+        let cache = self.lock_cache().clone();
+        *self.snapshot.lock().unwrap() = Some(cache);
+    }
+
+    /// Returns a copy of the last snapshot taken with `take_snapshot`, if any.
     ///
-    /// # Returns
+    /// # Panics
     ///
-    /// A new `AppConfig` with values populated from CLI arguments.
-    pub fn from_cli(args: &CliArgs) -> Self {
-        Self {
-            root_dir: args.root_dir.clone(),
-            template_phantom_node: args.template_phantom_node.clone(),
-        }
+    /// Panics if the mutex is poisoned (another thread panicked while holding the lock).
+    pub fn get_snapshot(&self) -> Option<GraphCache> {
+        self.snapshot.lock().unwrap().clone()
     }
 
-    /// Merges two configurations with priority handling.
+    /// Scans root directories, rebuilds the graph cache from them and persists the result.
     ///
-    /// Combines a base configuration with an override configuration, where the
-    /// override takes precedence. For each field, if the override contains a
-    /// value (Some), it is used; otherwise, the base value is used.
+    /// The roots are merged into one graph (see `scan_directories`). Applies the
+    /// configured scan patterns, link syntax, label format, group colors and link
+    /// direction. Failing to persist the cache is logged but not treated as an
+    /// error, since the in-memory cache is already up to date.
     ///
-    /// This implements the configuration hierarchy: CLI arguments override
-    /// file-based configuration, which overrides defaults.
+    /// Every rebuild of the cache goes through this method, so it is also where
+    /// the `rescan-complete` event (see `events::emit_rescan_complete`) is
+    /// emitted once the new cache is in place.
     ///
     /// # Arguments
     ///
-    /// * `base` - The lower-priority configuration (e.g., from file or defaults)
-    /// * `override_config` - The higher-priority configuration (e.g., from CLI)
+    /// * `roots` - Directories to scan, in priority order (see `AppConfig::scan_roots`)
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `rescan-complete` event
     ///
     /// # Returns
     ///
-    /// A new `AppConfig` with merged values according to the priority rules.
-    pub fn merge(base: Self, override_config: Self) -> Self {
-        Self {
-            root_dir: override_config.root_dir.or(base.root_dir),
-            template_phantom_node: override_config.template_phantom_node.or(base.template_phantom_node),
+    /// * `Ok(GraphData)` - The newly built graph, now stored in the cache
+    /// * `Err(String)` - Error message if the patterns are invalid, scanning fails
+    ///   or the event could not be emitted
+    pub fn rescan<E: EventEmitter>(
+        &self,
+        roots: &[String],
+        emitter: &E,
+    ) -> Result<GraphData, String> {
+        let config = self.get_config();
+        let filter = config.scan_filter()?;
+
+        let fingerprint = roots_fingerprint(roots, &filter)?;
+        let files = scan_directories(roots, &filter, config.scan_threads())?;
+        let content_hashes: HashMap<String, u64> = files
+            .iter()
+            .map(|file| (file.name.clone(), content_hash(&file.content)))
+            .collect();
+
+        let options = BuildOptions {
+            roots: roots.iter().map(PathBuf::from).collect(),
+            ..config.build_options()
+        };
+        let mut graph = build_graph_with(files, &options);
+        apply_group_colors(&mut graph, &config.group_colors);
+        graph.directed = !config.undirected;
+
+        let cache = GraphCache::from_graph(graph.clone()).with_content_hashes(content_hashes);
+
+        if let Some(cache_file) = &self.cache_file {
+            let key = CacheKey::new(roots, &config);
+
+            match cache.save(cache_file, &key, &fingerprint) {
+                Ok(()) => println!("[Cache] Saved graph cache to {:?}", cache_file),
+                Err(e) => eprintln!("[Cache] {}", e),
+            }
         }
+
+        self.replace_cache(cache);
+        events::emit_rescan_complete(emitter, &graph)?;
+
+        Ok(graph)
     }
-}
 
-/// Loads and merges configuration from all available sources.
-///
-/// This is the main entry point for configuration loading. It orchestrates the
-/// complete configuration loading and merging process following this priority order:
-/// 1. Parse CLI arguments
-/// 2. Load JSON configuration (from --config path or default locations)
-/// 3. Merge CLI arguments over JSON configuration
-/// 4. Return the final merged configuration
-///
-/// The function also logs the configuration loading process and final values to
-/// stdout for debugging purposes.
-///
-/// # Returns
-///
-/// * `Ok(AppConfig)` - Successfully loaded and merged configuration
-/// * `Err(String)` - Error message if a specified configuration file fails to load
-///
-/// # Errors
-///
-/// Returns an error if:
-/// - The `--config` specified file fails to load or parse
-/// - Note: Missing default config.json is NOT an error - it falls back to defaults
-///
-/// # Configuration Search Locations
-///
-/// When no `--config` is specified, searches for config.json in:
-/// 1. Directory containing the executable
-/// 2. Current working directory
-///
-/// If no configuration file is found, uses default values.
-pub fn load_config() -> Result<AppConfig, String> {
-    let args = CliArgs::parse();
+    /// Populates the graph cache on a background thread.
+    ///
+    /// Runs `restore_cache` on a new thread, so a slow scan of a large vault
+    /// doesn't block the window from appearing, and emits a `graph-ready` event
+    /// when done (see `events::emit_graph_ready`). The state shares its cache with
+    /// every clone, so the result is visible to all command handlers; until then
+    /// they see an empty graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `graph-ready` event
+    ///
+    /// # Returns
+    ///
+    /// The handle of the spawned thread.
+    pub fn restore_cache_in_background<E>(&self, emitter: E) -> JoinHandle<()>
+    where
+        E: EventEmitter + Send + 'static,
+    {
+        let state = self.clone();
 
-    let json_config = if let Some(config_path) = &args.config {
-        println!("[Config] Loading configuration from: {:?}", config_path);
-        AppConfig::from_file(config_path)?
-    } else {
-        match try_load_default_config() {
-            Some(config) => {
-                println!("[Config] Loaded configuration from ./config.json");
-                config
+        thread::spawn(move || {
+            let result = state.restore_cache(&emitter);
+
+            match &result {
+                Ok(true) => println!("[Cache] Using persisted graph cache"),
+                Ok(false) => println!("[Cache] Graph cache populated"),
+                Err(e) => eprintln!("[Error] Failed to build graph cache: {}", e),
             }
-            None => {
-                println!("[Config] config.json not found, using defaults");
-                AppConfig::default()
+
+            let graph = state.lock_cache().graph().clone();
+
+            if let Err(e) = events::emit_graph_ready(&emitter, &graph, &result) {
+                eprintln!("[Error] Failed to emit graph-ready event: {}", e);
             }
+        })
+    }
+
+    /// Rescans root directories and returns how the graph changed.
+    ///
+    /// Rebuilds the cache like `rescan` and diffs the previous cache against the
+    /// new one (see `GraphCache::diff`), so changes made while nothing was
+    /// updating the cache can be sent to the frontend incrementally.
+    ///
+    /// # Arguments
+    ///
+    /// * `roots` - Directories to scan, in priority order (see `AppConfig::scan_roots`)
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `rescan-complete` event
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(GraphDelta)` - Changes from the previous cache to the rebuilt one
+    /// * `Err(String)` - Error message if the rescan failed
+    pub fn reconcile<E: EventEmitter>(
+        &self,
+        roots: &[String],
+        emitter: &E,
+    ) -> Result<GraphDelta, String> {
+        let previous = self.lock_cache().clone();
+
+        self.rescan(roots, emitter)?;
+
+        Ok(previous.diff(&self.lock_cache()))
+    }
+
+    /// Reconciles the configured roots and emits the changes.
+    ///
+    /// Runs `reconcile` on `root_dir` and `root_dirs`, which emits
+    /// `rescan-complete`, and then emits a `graph-delta` event (see
+    /// `events::emit_graph_delta`) unless nothing changed. Used by the
+    /// `reconcile` command and by every tick of the periodic rescan.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `graph-delta` event
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(GraphDelta)` - Changes from the previous cache to the rebuilt one
+    /// * `Err(String)` - Error message if no root is configured, the rescan failed
+    ///   or the event could not be emitted
+    pub fn reconcile_and_emit<E: EventEmitter>(&self, emitter: &E) -> Result<GraphDelta, String> {
+        let roots = self.get_config().configured_roots();
+
+        if roots.is_empty() {
+            return Err("Root directory not configured".to_string());
         }
-    };
 
-    let cli_config = AppConfig::from_cli(&args);
+        let delta = self.reconcile(&roots, emitter)?;
 
-    let final_config = AppConfig::merge(json_config, cli_config);
+        println!(
+            "[Reconcile] {} nodes added, {} removed, {} updated",
+            delta.added_nodes.len(),
+            delta.removed_nodes.len(),
+            delta.updated_nodes.len()
+        );
 
-    println!("[Config] Final configuration:");
-    println!("  root_dir: {:?}", final_config.root_dir);
-    println!("  template_phantom_node: {:?}", final_config.template_phantom_node);
+        if !delta.is_empty() {
+            events::emit_graph_delta(emitter, &delta)?;
+        }
 
-    Ok(final_config)
-}
+        Ok(delta)
+    }
 
-/// Attempts to automatically locate and load a default config.json file.
-///
-/// Searches for config.json in standard locations without requiring explicit
-/// CLI specification. This provides a convenient way to configure the application
-/// without command-line arguments.
-///
-/// # Search Order
-///
-/// 1. `<executable_directory>/config.json` - Checked first, useful for portable installations
-/// 2. `<current_working_directory>/config.json` - Checked second, useful for development
-///
-/// # Returns
-///
-/// * `Some(AppConfig)` - Successfully found and loaded a configuration file
-/// * `None` - No configuration file found in any of the search locations
-///
-/// # Error Handling
-///
-/// If a config.json file is found but fails to parse, the error is silently ignored
-/// and None is returned. This allows the application to fall back to defaults even
-/// when a malformed configuration file is present. The calling function will log
-/// that defaults are being used.
-fn try_load_default_config() -> Option<AppConfig> {
-    let exe_path = std::env::current_exe().ok()?;
-    let exe_dir = exe_path.parent()?;
+    /// Applies a modified note file to the graph cache and emits the changes.
+    ///
+    /// Reads the file and passes it to `GraphCache::handle_file_modified`, so a
+    /// note saved again with unchanged content is not reparsed. A `graph-delta`
+    /// event (see `events::emit_graph_delta`) is emitted unless nothing changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the modified markdown file
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `graph-delta` event
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(GraphDelta)` - Changes the modification made to the graph
+    /// * `Err(String)` - Error message if the file could not be read or the event
+    ///   could not be emitted
+    pub fn handle_file_modified<E: EventEmitter>(
+        &self,
+        path: &Path,
+        emitter: &E,
+    ) -> Result<GraphDelta, String> {
+        let config = self.get_config();
+        let file = read_markdown_file(path)?;
 
-    let config_path = exe_dir.join("config.json");
+        let delta = self
+            .lock_cache()
+            .handle_file_modified(&file, &config.build_options());
 
-    if config_path.exists() {
-        println!("[Config] Found config.json: {:?}", config_path);
-        AppConfig::from_file(&config_path).ok()
-    } else {
-        let cwd_config = PathBuf::from("config.json");
-        if cwd_config.exists() {
-            println!("[Config] Found config.json in current directory: {:?}", cwd_config);
-            AppConfig::from_file(&cwd_config).ok()
-        } else {
-            None
+        if !delta.is_empty() {
+            events::emit_graph_delta(emitter, &delta)?;
+        }
+
+        Ok(delta)
+    }
+
+    /// Applies a file system change reported for a path in the vault.
+    ///
+    /// Called by the vault watcher (see `start_vault_watch`) for every changed
+    /// path. Paths a scan wouldn't read are ignored: files without the `.md`
+    /// extension, files outside the configured roots and files rejected by the
+    /// scan filter. An existing file is applied with `handle_file_modified`,
+    /// which covers new notes as well; a note whose file no longer exists is
+    /// removed from the graph. As in a scan, a file whose name is already taken
+    /// by another existing note is skipped. A `graph-delta` event is emitted
+    /// unless nothing changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Changed path, as reported by the watcher
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `graph-delta` event
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(GraphDelta)` - Changes made to the graph; empty for ignored paths
+    /// * `Err(String)` - Error message if the scan patterns are invalid, the file
+    ///   could not be read or the event could not be emitted
+    pub fn handle_vault_event<E: EventEmitter>(
+        &self,
+        path: &Path,
+        emitter: &E,
+    ) -> Result<GraphDelta, String> {
+        let config = self.get_config();
+
+        if path.extension().is_none_or(|ext| ext != "md") {
+            return Ok(GraphDelta::default());
         }
+
+        let filter = config.scan_filter()?;
+        let in_vault = config.configured_roots().iter().any(|root| {
+            path.strip_prefix(root)
+                .is_ok_and(|relative| filter.allows(relative))
+        });
+
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            return Ok(GraphDelta::default());
+        };
+
+        if !in_vault {
+            return Ok(GraphDelta::default());
+        }
+
+        let owner = self
+            .lock_cache()
+            .get_node(name)
+            .map(|node| PathBuf::from(&node.file_path))
+            .filter(|owner| !owner.as_os_str().is_empty());
+
+        if path.is_file() {
+            if let Some(owner) = owner.filter(|owner| owner != path && owner.is_file()) {
+                println!(
+                    "[Watch] Skipping {:?}: a note named '{}' already exists at {:?}",
+                    path, name, owner
+                );
+                return Ok(GraphDelta::default());
+            }
+
+            return self.handle_file_modified(path, emitter);
+        }
+
+        if owner.as_deref() != Some(path) {
+            return Ok(GraphDelta::default());
+        }
+
+        let delta = self
+            .lock_cache()
+            .handle_file_removed(name, &config.build_options());
+
+        if !delta.is_empty() {
+            events::emit_graph_delta(emitter, &delta)?;
+        }
+
+        Ok(delta)
+    }
+
+    /// Starts watching the configured roots for changed notes.
+    ///
+    /// Keeps the graph current while notes are edited, created and deleted: a
+    /// thread runs a file system watcher on `root_dir` and `root_dirs` and
+    /// passes every changed path to `handle_vault_event` once no further event
+    /// arrived for it within `WATCH_DEBOUNCE`. Changes to a note processed less
+    /// than `modify_throttle_ms` ago are coalesced and applied once the window
+    /// ends (see `ModifyThrottle`). A watch that is already running is stopped
+    /// first, so this is also how changed roots are picked up. Roots
+    /// that can't be watched and changes that fail to apply are logged, and the
+    /// watch keeps running.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `graph-delta` events
+    ///
+    /// # Returns
+    ///
+    /// `true` if a watch was started, `false` if no root directory is configured.
+    pub fn start_vault_watch<E>(&self, emitter: E) -> bool
+    where
+        E: EventEmitter + Send + 'static,
+    {
+        self.stop_vault_watch();
+
+        let config = self.get_config();
+        let roots = config.configured_roots();
+
+        if roots.is_empty() {
+            return false;
+        }
+
+        let (stop, stopped) = mpsc::channel();
+        let state = self.clone();
+        let watched = roots.clone();
+        let mut throttle = ModifyThrottle::new(config.modify_throttle());
+
+        let handle = thread::spawn(move || {
+            let (sender, changes) = mpsc::channel::<DebounceEventResult>();
+
+            let mut debouncer = match new_debouncer(WATCH_DEBOUNCE, sender) {
+                Ok(debouncer) => debouncer,
+                Err(e) => {
+                    eprintln!("[Watch] Failed to start the file watcher: {}", e);
+                    return;
+                }
+            };
+
+            for root in &watched {
+                let watch = debouncer.watcher().watch(Path::new(root), RecursiveMode::Recursive);
+
+                if let Err(e) = watch {
+                    eprintln!("[Watch] Failed to watch {}: {}", root, e);
+                }
+            }
+
+            while let Err(TryRecvError::Empty) = stopped.try_recv() {
+                let timeout = throttle.next_due().map_or(WATCH_DEBOUNCE, |due| {
+                    due.saturating_duration_since(Instant::now()).min(WATCH_DEBOUNCE)
+                });
+
+                let mut paths: Vec<PathBuf> = match changes.recv_timeout(timeout) {
+                    Ok(Ok(events)) => events
+                        .into_iter()
+                        .map(|event| event.path)
+                        .filter(|path| throttle.offer(path, Instant::now()))
+                        .collect(),
+                    Ok(Err(e)) => {
+                        eprintln!("[Watch] File watcher error: {}", e);
+                        Vec::new()
+                    }
+                    Err(RecvTimeoutError::Timeout) => Vec::new(),
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+
+                paths.extend(throttle.take_due(Instant::now()));
+
+                for path in paths {
+                    if let Err(e) = state.handle_vault_event(&path, &emitter) {
+                        eprintln!("[Watch] Failed to apply change to {:?}: {}", path, e);
+                    }
+                }
+            }
+        });
+
+        *self.vault_watch.lock().unwrap() = Some(TimerThread { stop, handle });
+        println!("[Watch] Watching {:?}", roots);
+
+        true
+    }
+
+    /// Stops watching the vault, if a watch is running.
+    ///
+    /// Waits for a change that is being applied, so callers must not hold the
+    /// configuration or cache lock.
+    pub fn stop_vault_watch(&self) {
+        if stop_timer(&self.vault_watch) {
+            println!("[Watch] Vault watch stopped");
+        }
+    }
+
+    /// Starts the periodic full rescan configured by `rescan_interval_secs`.
+    ///
+    /// A safety net for long sessions: every interval, a timer thread runs
+    /// `reconcile_and_emit`, so changes the graph missed show up as `graph-delta`
+    /// events. A periodic rescan that is already running is stopped first, so
+    /// this is also how a changed interval or vault is picked up. Failed ticks
+    /// are logged and the timer keeps running.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `graph-delta` events
+    ///
+    /// # Returns
+    ///
+    /// `true` if a timer was started, `false` if `rescan_interval_secs` is not set.
+    pub fn start_periodic_rescan<E>(&self, emitter: E) -> bool
+    where
+        E: EventEmitter + Send + 'static,
+    {
+        self.stop_periodic_rescan();
+
+        let Some(secs) = self.get_config().rescan_interval_secs.filter(|secs| *secs > 0) else {
+            return false;
+        };
+
+        let interval = Duration::from_secs(secs);
+        let (stop, stopped) = mpsc::channel();
+        let state = self.clone();
+
+        let handle = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                if let Err(e) = state.reconcile_and_emit(&emitter) {
+                    eprintln!("[Rescan] Periodic rescan failed: {}", e);
+                }
+            }
+        });
+
+        *self.periodic_rescan.lock().unwrap() = Some(TimerThread { stop, handle });
+        println!("[Rescan] Rescanning every {} seconds", secs);
+
+        true
+    }
+
+    /// Stops the periodic rescan, if one is running.
+    ///
+    /// Waits for a tick that is in progress to finish, so callers must not hold
+    /// the configuration or cache lock.
+    pub fn stop_periodic_rescan(&self) {
+        if stop_timer(&self.periodic_rescan) {
+            println!("[Rescan] Periodic rescan stopped");
+        }
+    }
+
+    /// Starts watching the phantom-node template file, if `watch_template` is set.
+    ///
+    /// Templates don't affect the graph, so a changed template only matters to
+    /// a template preview. A timer thread checks the modification time and size
+    /// of `template_phantom_node` every `TEMPLATE_POLL_INTERVAL` and emits a
+    /// `template-updated` event (see `events::emit_template_updated`) when they
+    /// change, including when the file is created or deleted. The file may lie
+    /// outside the vault. A watch that is already running is stopped first.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `template-updated` events
+    ///
+    /// # Returns
+    ///
+    /// `true` if a watch was started, `false` if `watch_template` is off or no
+    /// template is configured.
+    pub fn start_template_watch<E>(&self, emitter: E) -> bool
+    where
+        E: EventEmitter + Send + 'static,
+    {
+        self.stop_template_watch();
+
+        let config = self.get_config();
+
+        if !config.watch_template {
+            return false;
+        }
+
+        let Some(template) = config.template_phantom_node.filter(|path| !path.is_empty()) else {
+            return false;
+        };
+
+        let path = PathBuf::from(template);
+        let interval = TEMPLATE_POLL_INTERVAL;
+        let (stop, stopped) = mpsc::channel();
+        let watched = path.clone();
+
+        let handle = thread::spawn(move || {
+            let mut last_stamp = file_stamp(&watched);
+
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                let stamp = file_stamp(&watched);
+
+                if stamp == last_stamp {
+                    continue;
+                }
+
+                last_stamp = stamp;
+
+                if let Err(e) = events::emit_template_updated(&emitter, &watched) {
+                    eprintln!("[Template] Failed to emit template-updated: {}", e);
+                }
+            }
+        });
+
+        *self.template_watch.lock().unwrap() = Some(TimerThread { stop, handle });
+        println!("[Template] Watching template {:?}", path);
+
+        true
+    }
+
+    /// Stops watching the template file, if a watch is running.
+    pub fn stop_template_watch(&self) {
+        if stop_timer(&self.template_watch) {
+            println!("[Template] Template watch stopped");
+        }
+    }
+
+    /// Populates the graph cache at startup.
+    ///
+    /// Restores the persisted cache if it was built from the configured roots
+    /// (`root_dir` and `root_dirs`) with the current settings and the vaults' files
+    /// are unchanged (same `VaultFingerprint`). Otherwise the vaults are scanned
+    /// from scratch. Does nothing if no root directory is configured or
+    /// `scan_on_startup` is off, leaving the cache empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the `rescan-complete`
+    ///   event of the fallback scan
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - The cache was restored from disk
+    /// * `Ok(false)` - The cache was rebuilt by a full scan, no root is configured,
+    ///   or startup scanning is disabled
+    /// * `Err(String)` - Error message if the fallback scan failed
+    pub fn restore_cache<E: EventEmitter>(&self, emitter: &E) -> Result<bool, String> {
+        let config = self.get_config();
+
+        if !config.scan_on_startup {
+            println!("[Cache] scan_on_startup is disabled, starting with an empty graph");
+            return Ok(false);
+        }
+
+        let roots = config.configured_roots();
+
+        if roots.is_empty() {
+            return Ok(false);
+        }
+
+        if let Some(cache_file) = &self.cache_file {
+            let filter = config.scan_filter()?;
+            let fingerprint = roots_fingerprint(&roots, &filter)?;
+            let key = CacheKey::new(&roots, &config);
+
+            match GraphCache::load(cache_file, &key, &fingerprint) {
+                Ok(Some(cache)) => {
+                    println!("[Cache] Restored graph cache from {:?}", cache_file);
+                    self.replace_cache(cache);
+                    return Ok(true);
+                }
+                Ok(None) => println!("[Cache] Persisted cache missing or stale, rescanning"),
+                Err(e) => eprintln!("[Cache] {}, rescanning", e),
+            }
+        }
+
+        self.rescan(&roots, emitter)?;
+
+        Ok(false)
+    }
+
+    /// Validates, applies and saves a new configuration.
+    ///
+    /// See the `set_config` command for which changes rebuild the graph and
+    /// restart the periodic rescan, the file watcher and the template watch.
+    /// These follow the new configuration even if saving the file fails, so the
+    /// running session never mixes the new settings with stale background work.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_config` - The complete configuration to apply
+    /// * `emitter` - Event emitter (usually the `AppHandle`) for the rescan event
+    ///   and the restarted background threads
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is invalid, in which case nothing
+    /// is changed. Otherwise the configuration is active, and the error of a
+    /// failed save or rescan is returned once every restart has run.
+    pub fn apply_config<E>(&self, mut new_config: AppConfig, emitter: E) -> Result<(), String>
+    where
+        E: EventEmitter + Clone + Send + 'static,
+    {
+        new_config.validate()?;
+
+        new_config.root_dir = new_config.root_dir.as_deref().map(normalize_root_dir);
+        new_config.root_dirs = new_config
+            .root_dirs
+            .iter()
+            .map(|root| normalize_root_dir(root))
+            .collect();
+
+        let previous = self.get_config();
+        let previous_roots = previous.configured_roots();
+        let roots = new_config.configured_roots();
+
+        self.update_config(new_config.clone());
+
+        let path = self.config_path();
+        let saved = new_config.save(&path);
+        if saved.is_ok() {
+            println!("[Config] Saved configuration to {:?}", path);
+        }
+
+        let graph_changed =
+            CacheKey::new(&roots, &new_config) != CacheKey::new(&previous_roots, &previous);
+
+        let rescanned = if graph_changed && !roots.is_empty() {
+            self.rescan(&roots, &emitter).map(|_| ())
+        } else {
+            Ok(())
+        };
+
+        if new_config.watch_template != previous.watch_template
+            || new_config.template_phantom_node != previous.template_phantom_node
+        {
+            self.start_template_watch(emitter.clone());
+        }
+
+        let roots_changed = roots != previous_roots;
+
+        if roots_changed || new_config.modify_throttle_ms != previous.modify_throttle_ms {
+            self.start_vault_watch(emitter.clone());
+        }
+
+        if roots_changed || new_config.rescan_interval_secs != previous.rescan_interval_secs {
+            self.start_periodic_rescan(emitter);
+        }
+
+        saved.and(rescanned)
+    }
+}
+
+impl AppConfig {
+    /// Loads configuration from a JSON file.
+    ///
+    /// Reads the file at the specified path and deserializes it into an `AppConfig`
+    /// structure. All fields in the JSON file are optional - missing fields will
+    /// be set to None (or empty for lists and maps).
+    ///
+    /// Parsing errors are reported in a human-readable form naming the offending
+    /// key, e.g. "`include_patterns` must be a list (line 3, column 24)". See
+    /// `parse_json` for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the JSON configuration file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(AppConfig)` - Successfully loaded and parsed configuration
+    /// * `Err(String)` - Error message if file reading or JSON parsing fails
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The file cannot be read (doesn't exist, permission denied, etc.)
+    /// - The file contains invalid JSON syntax
+    /// - The JSON structure doesn't match the expected schema
+    /// - The file contains an unknown key
+    pub fn from_file(path: &PathBuf) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Error reading configuration file: {}", e))?;
+
+        let config = Self::parse_json(&content)
+            .map_err(|e| format!("Error parsing JSON configuration {:?}: {}", path, e))?;
+
+        Ok(config)
+    }
+
+    /// Writes the configuration to a JSON file.
+    ///
+    /// The file is written pretty-printed in the same format `from_file` reads,
+    /// replacing any existing content.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the JSON configuration file to write
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the configuration can't be serialized or the
+    /// file can't be written.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize configuration: {}", e))?;
+
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to write configuration file {:?}: {}", path, e))
+    }
+
+    /// Checks that the configuration can be used as is.
+    ///
+    /// Unlike `validate_wiki_link_pattern`, which repairs a loaded file by
+    /// dropping a bad pattern, this rejects the whole configuration, so a
+    /// settings form can show the problem before anything is changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message for the first problem found:
+    /// - A configured root directory (`root_dir` or `root_dirs`) is not an existing directory
+    /// - An include or ignore pattern is not a valid glob
+    /// - `wiki_link_pattern` is not a valid regex or lacks a capture group
+    /// - `label_max_length` is 0
+    /// - `rescan_interval_secs` is 0
+    /// - `scan_threads` is 0
+    /// - `max_nodes` is 0
+    pub fn validate(&self) -> Result<(), String> {
+        for root in self.root_dir.iter().chain(&self.root_dirs) {
+            if !Path::new(root).is_dir() {
+                return Err(format!("Root directory '{}' is not an existing directory", root));
+            }
+        }
+
+        self.scan_filter()?;
+
+        if let Some(pattern) = &self.wiki_link_pattern {
+            LinkSyntax::new(pattern)?;
+        }
+
+        if self.label_max_length == Some(0) {
+            return Err("label_max_length must be greater than 0".to_string());
+        }
+
+        if self.rescan_interval_secs == Some(0) {
+            return Err("rescan_interval_secs must be greater than 0".to_string());
+        }
+
+        if self.scan_threads == Some(0) {
+            return Err("scan_threads must be greater than 0".to_string());
+        }
+
+        if self.max_nodes == Some(0) {
+            return Err("max_nodes must be greater than 0".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Parses configuration from a JSON string with field-level error messages.
+    ///
+    /// Deserialization tracks the path of the value being read, so type errors
+    /// name the exact key (including nested keys such as `a.b`) and the type
+    /// that was expected.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - JSON text of the configuration
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(AppConfig)` - Successfully parsed configuration
+    /// * `Err(String)` - Human-readable description of the first problem found
+    ///
+    /// # Error Messages
+    ///
+    /// - Syntax errors: "Invalid JSON at line 2, column 5: ..."
+    /// - Wrong types: "`root_dir` must be a string (line 2, column 15)"
+    /// - Unknown keys: "Unknown configuration key `root_directory`; expected one of ..."
+    pub fn parse_json(content: &str) -> Result<Self, String> {
+        let deserializer = &mut serde_json::Deserializer::from_str(content);
+
+        serde_path_to_error::deserialize(deserializer).map_err(|e| describe_json_error(&e))
+    }
+
+    /// Creates configuration from CLI arguments.
+    ///
+    /// Extracts configuration values from parsed command-line arguments.
+    /// Only `root_dir`, `template_phantom_node` and `previewer.offset` are currently
+    /// mapped; the `config` argument is used for file loading and not stored in the
+    /// configuration itself. Additional root directories, scan patterns, template
+    /// variables, group colors, the wiki-link pattern, symlink handling, link
+    /// direction, label formatting, the stub threshold and editor launching can only
+    /// be set from a configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - Parsed CLI arguments structure
+    ///
+    /// # Returns
+    ///
+    /// A new `AppConfig` with values populated from CLI arguments.
+    pub fn from_cli(args: &CliArgs) -> Self {
+        Self {
+            root_dir: args.root_dir.clone(),
+            root_dirs: Vec::new(),
+            template_phantom_node: args.template_phantom_node.clone(),
+            include_patterns: Vec::new(),
+            ignore_patterns: Vec::new(),
+            template_variables: HashMap::new(),
+            previewer: PreviewerConfig {
+                offset: args.previewer_offset,
+                skip_frontmatter: false,
+            },
+            group_colors: HashMap::new(),
+            wiki_link_pattern: None,
+            skip_symlinks: false,
+            skip_hidden: false,
+            undirected: false,
+            label_max_length: None,
+            strip_date_prefix: false,
+            namespaces: Vec::new(),
+            namespace_groups: false,
+            min_content_chars: None,
+            allow_self_links: false,
+            scan_on_startup: true,
+            node_size_scale: None,
+            rescan_interval_secs: None,
+            drop_excluded_links: false,
+            skip_blockquote_links: false,
+            file_metadata: false,
+            heading_sections: false,
+            watch_template: false,
+            scan_threads: None,
+            max_nodes: None,
+            modify_throttle_ms: None,
+            terminal_command: None,
+            detach_editor: false,
+        }
+    }
+
+    /// Merges two configurations with priority handling.
+    ///
+    /// Combines a base configuration with an override configuration, where the
+    /// override takes precedence. For each field, if the override contains a
+    /// value (Some, or a non-empty list or map), it is used; otherwise, the
+    /// base value is used. Flags are set if either configuration sets them,
+    /// except `scan_on_startup`, which defaults to on and is off if either
+    /// configuration turns it off.
+    ///
+    /// This implements the configuration hierarchy: CLI arguments override
+    /// file-based configuration, which overrides defaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The lower-priority configuration (e.g., from file or defaults)
+    /// * `override_config` - The higher-priority configuration (e.g., from CLI)
+    ///
+    /// # Returns
+    ///
+    /// A new `AppConfig` with merged values according to the priority rules.
+    pub fn merge(base: Self, override_config: Self) -> Self {
+        Self {
+            root_dir: override_config.root_dir.or(base.root_dir),
+            root_dirs: if override_config.root_dirs.is_empty() {
+                base.root_dirs
+            } else {
+                override_config.root_dirs
+            },
+            template_phantom_node: override_config.template_phantom_node.or(base.template_phantom_node),
+            include_patterns: if override_config.include_patterns.is_empty() {
+                base.include_patterns
+            } else {
+                override_config.include_patterns
+            },
+            ignore_patterns: if override_config.ignore_patterns.is_empty() {
+                base.ignore_patterns
+            } else {
+                override_config.ignore_patterns
+            },
+            template_variables: if override_config.template_variables.is_empty() {
+                base.template_variables
+            } else {
+                override_config.template_variables
+            },
+            previewer: PreviewerConfig {
+                offset: override_config.previewer.offset.or(base.previewer.offset),
+                skip_frontmatter: override_config.previewer.skip_frontmatter
+                    || base.previewer.skip_frontmatter,
+            },
+            group_colors: if override_config.group_colors.is_empty() {
+                base.group_colors
+            } else {
+                override_config.group_colors
+            },
+            wiki_link_pattern: override_config.wiki_link_pattern.or(base.wiki_link_pattern),
+            skip_symlinks: override_config.skip_symlinks || base.skip_symlinks,
+            skip_hidden: override_config.skip_hidden || base.skip_hidden,
+            undirected: override_config.undirected || base.undirected,
+            label_max_length: override_config.label_max_length.or(base.label_max_length),
+            strip_date_prefix: override_config.strip_date_prefix || base.strip_date_prefix,
+            namespaces: if override_config.namespaces.is_empty() {
+                base.namespaces
+            } else {
+                override_config.namespaces
+            },
+            namespace_groups: override_config.namespace_groups || base.namespace_groups,
+            min_content_chars: override_config.min_content_chars.or(base.min_content_chars),
+            allow_self_links: override_config.allow_self_links || base.allow_self_links,
+            scan_on_startup: override_config.scan_on_startup && base.scan_on_startup,
+            node_size_scale: override_config.node_size_scale.or(base.node_size_scale),
+            rescan_interval_secs: override_config
+                .rescan_interval_secs
+                .or(base.rescan_interval_secs),
+            drop_excluded_links: override_config.drop_excluded_links || base.drop_excluded_links,
+            skip_blockquote_links: override_config.skip_blockquote_links
+                || base.skip_blockquote_links,
+            file_metadata: override_config.file_metadata || base.file_metadata,
+            heading_sections: override_config.heading_sections || base.heading_sections,
+            watch_template: override_config.watch_template || base.watch_template,
+            scan_threads: override_config.scan_threads.or(base.scan_threads),
+            max_nodes: override_config.max_nodes.or(base.max_nodes),
+            modify_throttle_ms: override_config
+                .modify_throttle_ms
+                .or(base.modify_throttle_ms),
+            terminal_command: override_config.terminal_command.or(base.terminal_command),
+            detach_editor: override_config.detach_editor || base.detach_editor,
+        }
+    }
+
+    /// Returns every root directory to scan when `primary` is scanned.
+    ///
+    /// `primary` comes first, followed by the configured `root_dirs` that differ
+    /// from it, so the primary root wins name collisions.
+    pub fn scan_roots(&self, primary: &str) -> Vec<String> {
+        let mut roots = vec![primary.to_string()];
+
+        for root in &self.root_dirs {
+            if !roots.contains(root) {
+                roots.push(root.clone());
+            }
+        }
+
+        roots
+    }
+
+    /// Returns the root directories scanned at startup.
+    ///
+    /// These are `root_dir` followed by `root_dirs` (see `scan_roots`). When
+    /// only `root_dirs` is configured, its first entry acts as the primary root.
+    /// Empty if no root directory is configured at all.
+    pub fn configured_roots(&self) -> Vec<String> {
+        match self.root_dir.as_deref().or(self.root_dirs.first().map(String::as_str)) {
+            Some(primary) => self.scan_roots(primary),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the minimum interval between two reparses of a note.
+    ///
+    /// `modify_throttle_ms`, or `DEFAULT_MODIFY_THROTTLE_MS` if not set.
+    pub fn modify_throttle(&self) -> Duration {
+        Duration::from_millis(self.modify_throttle_ms.unwrap_or(DEFAULT_MODIFY_THROTTLE_MS))
+    }
+
+    /// Builds the scan filter described by this configuration.
+    ///
+    /// Combines `include_patterns`, `ignore_patterns`, `skip_symlinks` and `skip_hidden`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message naming the first invalid glob pattern.
+    pub fn scan_filter(&self) -> Result<ScanFilter, String> {
+        Ok(ScanFilter::new(&self.include_patterns, &self.ignore_patterns)?
+            .skipping_symlinks(self.skip_symlinks)
+            .skipping_hidden(self.skip_hidden))
+    }
+
+    /// Returns the number of threads used to read notes during a scan.
+    ///
+    /// Uses `scan_threads` when it is set, and the available parallelism of the
+    /// machine otherwise (or a single thread if that can't be determined). A
+    /// configured 0 is treated as 1; `validate` rejects it up front.
+    pub fn scan_threads(&self) -> usize {
+        self.scan_threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+            .max(1)
+    }
+
+    /// Returns the wiki-link syntax to parse notes with.
+    ///
+    /// Uses `wiki_link_pattern` when it is set and valid, and the default
+    /// `[[target]]` syntax otherwise. Invalid patterns are already dropped with a
+    /// warning by `validate_wiki_link_pattern` at load time, so the fallback only
+    /// matters for configurations built elsewhere. Links in blockquotes are
+    /// ignored if `skip_blockquote_links` is set.
+    pub fn link_syntax(&self) -> LinkSyntax {
+        self.wiki_link_pattern
+            .as_deref()
+            .and_then(|pattern| LinkSyntax::new(pattern).ok())
+            .unwrap_or_default()
+            .skipping_blockquotes(self.skip_blockquote_links)
+    }
+
+    /// Returns the graph build options described by this configuration.
+    ///
+    /// Combines the wiki-link syntax (see `link_syntax`) with the label
+    /// formatting from `label_max_length`, `strip_date_prefix` and `namespaces`,
+    /// the stub threshold `min_content_chars`, the self-link handling
+    /// `allow_self_links`, the namespace grouping `namespace_groups`, the node
+    /// size scale `node_size_scale`, the excluded-note handling
+    /// `drop_excluded_links`, the node file metadata `file_metadata`, the
+    /// node sections `heading_sections` and the `configured_roots` the notes'
+    /// namespaces are relative to.
+    pub fn build_options(&self) -> BuildOptions {
+        BuildOptions {
+            link_syntax: self.link_syntax(),
+            label_format: LabelFormat {
+                max_length: self.label_max_length,
+                strip_date_prefix: self.strip_date_prefix,
+                namespaces: self.namespaces.clone(),
+            },
+            min_content_chars: self.min_content_chars,
+            allow_self_links: self.allow_self_links,
+            namespace_groups: self.namespace_groups,
+            roots: self.configured_roots().into_iter().map(PathBuf::from).collect(),
+            node_size_scale: self.node_size_scale.unwrap_or_default(),
+            drop_excluded_links: self.drop_excluded_links,
+            file_metadata: self.file_metadata,
+            heading_sections: self.heading_sections,
+        }
+    }
+
+    /// Drops an unusable `wiki_link_pattern`, logging a warning.
+    ///
+    /// A pattern that is not a valid regex or has no capture group for the link
+    /// target is removed, so the default `[[target]]` syntax is used instead of
+    /// failing every scan.
+    pub fn validate_wiki_link_pattern(&mut self) {
+        if let Some(pattern) = &self.wiki_link_pattern {
+            if let Err(e) = LinkSyntax::new(pattern) {
+                println!("[Config] Warning: {}, using default [[...]] links", e);
+                self.wiki_link_pattern = None;
+            }
+        }
+    }
+
+    /// Describes every configuration field with its default and current value.
+    ///
+    /// Lets the frontend render a settings form without hard-coding the fields.
+    /// Values are taken from the JSON serialization of the default and of this
+    /// configuration, so they use the same format as `config.json`.
+    ///
+    /// # Returns
+    ///
+    /// One `ConfigField` per entry in `CONFIG_FIELDS`, in form order.
+    pub fn schema(&self) -> Vec<ConfigField> {
+        let defaults = serde_json::to_value(Self::default()).unwrap_or_default();
+        let current = serde_json::to_value(self).unwrap_or_default();
+
+        let lookup = |json: &serde_json::Value, name: &str| {
+            let pointer = format!("/{}", name.replace('.', "/"));
+            json.pointer(&pointer).cloned().unwrap_or_default()
+        };
+
+        CONFIG_FIELDS
+            .iter()
+            .map(|&(name, field_type, optional, description)| ConfigField {
+                name: name.to_string(),
+                field_type,
+                optional,
+                description: description.to_string(),
+                default: lookup(&defaults, name),
+                value: lookup(&current, name),
+            })
+            .collect()
+    }
+}
+
+/// Converts a path-aware serde error into a message users can act on.
+///
+/// serde reports type mismatches in terms of Rust types ("expected usize"), which
+/// are translated into plain language ("must be a non-negative integer").
+fn describe_json_error(error: &serde_path_to_error::Error<serde_json::Error>) -> String {
+    let inner = error.inner();
+    let line = inner.line();
+    let column = inner.column();
+
+    let full_message = inner.to_string();
+    let message = full_message
+        .rsplit_once(" at line ")
+        .map_or(full_message.as_str(), |(message, _)| message);
+
+    if inner.classify() != Category::Data {
+        return format!("Invalid JSON at line {}, column {}: {}", line, column, message);
+    }
+
+    if let Some(rest) = message.strip_prefix("unknown field ") {
+        let (key, expected) = rest.split_once(", ").unwrap_or((rest, ""));
+        return format!("Unknown configuration key {}; {}", key, expected);
+    }
+
+    let path = error.path().to_string();
+
+    match message.rsplit_once(", expected ") {
+        Some((_, expected)) => format!(
+            "`{}` must be {} (line {}, column {})",
+            path,
+            describe_expected_type(expected),
+            line,
+            column
+        ),
+        None => format!("`{}`: {} (line {}, column {})", path, message, line, column),
+    }
+}
+
+/// Maps serde's description of an expected type to plain language.
+fn describe_expected_type(expected: &str) -> &str {
+    match expected {
+        "u8" | "u16" | "u32" | "u64" | "usize" => "a non-negative integer",
+        "i8" | "i16" | "i32" | "i64" | "isize" => "an integer",
+        "f32" | "f64" => "a number",
+        "a boolean" => "true or false",
+        "a sequence" => "a list",
+        "a map" => "an object",
+        other => other,
+    }
+}
+
+/// Where the configuration file was found during startup.
+///
+/// Serialized in snake_case (`"cli"`, `"exe_dir"`, `"cwd"`, `"default"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigOrigin {
+    /// Path given explicitly with `--config`
+    Cli,
+    /// `config.json` next to the executable
+    ExeDir,
+    /// `config.json` in the current working directory
+    Cwd,
+    /// No configuration file found; defaults were used
+    Default,
+}
+
+/// Describes the configuration file used at startup.
+///
+/// Recorded in `AppState` so that support requests can tell which file (if
+/// any) the running application actually read.
+///
+/// # Fields
+///
+/// * `path` - Resolved path of the configuration file, or None when no file was found
+/// * `origin` - Which search location produced the file
+/// * `loaded` - Whether the file was successfully read and parsed. A `config.json`
+///   found in a default location that fails to parse is reported with `loaded: false`,
+///   since the application then silently falls back to defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSource {
+    pub path: Option<String>,
+    pub origin: ConfigOrigin,
+    pub loaded: bool,
+}
+
+impl Default for ConfigSource {
+    /// Creates a source describing the built-in default configuration.
+    fn default() -> Self {
+        Self {
+            path: None,
+            origin: ConfigOrigin::Default,
+            loaded: false,
+        }
+    }
+}
+
+/// Loads and merges configuration from all available sources.
+///
+/// This is the main entry point for configuration loading. It orchestrates the
+/// complete configuration loading and merging process following this priority order:
+/// 1. Parse CLI arguments
+/// 2. Load JSON configuration (from --config path or default locations)
+/// 3. Merge CLI arguments over JSON configuration
+/// 4. Normalize `root_dir` (see `normalize_root_dir`)
+/// 5. Return the final merged configuration
+///
+/// The function also logs the configuration loading process and final values to
+/// stdout for debugging purposes.
+///
+/// # Returns
+///
+/// * `Ok((AppConfig, ConfigSource))` - Successfully loaded and merged configuration,
+///   together with a description of the file it came from
+/// * `Err(String)` - Error message if a specified configuration file fails to load
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The `--config` specified file fails to load or parse
+/// - Note: Missing default config.json is NOT an error - it falls back to defaults
+///
+/// # Configuration Search Locations
+///
+/// When no `--config` is specified, searches for config.json in:
+/// 1. Directory containing the executable
+/// 2. Current working directory
+///
+/// If no configuration file is found, uses default values.
+pub fn load_config() -> Result<(AppConfig, ConfigSource), String> {
+    let args = CliArgs::parse();
+
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from));
+
+    let mut source = locate_config(args.config.as_ref(), exe_dir.as_deref(), Path::new("."));
+
+    let json_config = match (&source.path, source.origin) {
+        (Some(config_path), ConfigOrigin::Cli) => {
+            println!("[Config] Loading configuration from: {:?}", config_path);
+            let config = AppConfig::from_file(&PathBuf::from(config_path))?;
+            source.loaded = true;
+            config
+        }
+        (Some(config_path), _) => {
+            println!("[Config] Found config.json: {:?}", config_path);
+            match AppConfig::from_file(&PathBuf::from(config_path)) {
+                Ok(config) => {
+                    println!("[Config] Loaded configuration from {}", config_path);
+                    source.loaded = true;
+                    config
+                }
+                Err(e) => {
+                    println!("[Config] {}, using defaults", e);
+                    AppConfig::default()
+                }
+            }
+        }
+        (None, _) => {
+            println!("[Config] config.json not found, using defaults");
+            AppConfig::default()
+        }
+    };
+
+    let cli_config = AppConfig::from_cli(&args);
+
+    let mut final_config = AppConfig::merge(json_config, cli_config);
+    final_config.root_dir = final_config.root_dir.as_deref().map(normalize_root_dir);
+    final_config.root_dirs = final_config
+        .root_dirs
+        .iter()
+        .map(|root| normalize_root_dir(root))
+        .collect();
+    final_config.validate_wiki_link_pattern();
+
+    println!("[Config] Final configuration:");
+    println!("  root_dir: {:?}", final_config.root_dir);
+    println!("  root_dirs: {:?}", final_config.root_dirs);
+    println!("  template_phantom_node: {:?}", final_config.template_phantom_node);
+    println!("  include_patterns: {:?}", final_config.include_patterns);
+    println!("  ignore_patterns: {:?}", final_config.ignore_patterns);
+    println!("  template_variables: {:?}", final_config.template_variables);
+    println!("  previewer.offset: {:?}", final_config.previewer.offset);
+    println!("  previewer.skip_frontmatter: {:?}", final_config.previewer.skip_frontmatter);
+    println!("  group_colors: {:?}", final_config.group_colors);
+    println!("  wiki_link_pattern: {:?}", final_config.wiki_link_pattern);
+    println!("  skip_symlinks: {:?}", final_config.skip_symlinks);
+    println!("  skip_hidden: {:?}", final_config.skip_hidden);
+    println!("  undirected: {:?}", final_config.undirected);
+    println!("  label_max_length: {:?}", final_config.label_max_length);
+    println!("  strip_date_prefix: {:?}", final_config.strip_date_prefix);
+    println!("  namespaces: {:?}", final_config.namespaces);
+    println!("  namespace_groups: {:?}", final_config.namespace_groups);
+    println!("  min_content_chars: {:?}", final_config.min_content_chars);
+    println!("  allow_self_links: {:?}", final_config.allow_self_links);
+    println!("  scan_on_startup: {:?}", final_config.scan_on_startup);
+    println!("  node_size_scale: {:?}", final_config.node_size_scale);
+    println!("  rescan_interval_secs: {:?}", final_config.rescan_interval_secs);
+    println!("  drop_excluded_links: {:?}", final_config.drop_excluded_links);
+    println!("  skip_blockquote_links: {:?}", final_config.skip_blockquote_links);
+    println!("  file_metadata: {:?}", final_config.file_metadata);
+    println!("  heading_sections: {:?}", final_config.heading_sections);
+    println!("  watch_template: {:?}", final_config.watch_template);
+    println!("  scan_threads: {:?}", final_config.scan_threads);
+    println!("  max_nodes: {:?}", final_config.max_nodes);
+    println!("  modify_throttle_ms: {:?}", final_config.modify_throttle_ms);
+    println!("  terminal_command: {:?}", final_config.terminal_command);
+    println!("  detach_editor: {:?}", final_config.detach_editor);
+
+    Ok((final_config, source))
+}
+
+/// Normalizes a configured root directory path.
+///
+/// Users write root directories in many forms (`C:\notes\`, `/home/me/notes/`,
+/// `C:/notes`), which leads to doubled or mixed separators once note names are
+/// joined onto the root and makes node `file_path` strings inconsistent. This
+/// function:
+/// 1. Trims surrounding whitespace
+/// 2. Converts both `/` and `\` to the platform separator
+/// 3. Collapses repeated separators (except a leading `\\` UNC prefix)
+/// 4. Strips trailing separators, keeping filesystem roots like `/` or `C:\` intact
+///
+/// # Arguments
+///
+/// * `raw` - Root directory as written in the configuration or on the CLI
+///
+/// # Returns
+///
+/// The normalized path string, e.g. `/home/me/notes` for `/home/me/notes/`
+/// (or `C:\notes` for `C:/notes/` on Windows).
+pub fn normalize_root_dir(raw: &str) -> String {
+    let separator = std::path::MAIN_SEPARATOR;
+    let raw = raw.trim();
+
+    let mut normalized = String::with_capacity(raw.len());
+
+    for (index, c) in raw.chars().enumerate() {
+        let c = if c == '/' || c == '\\' { separator } else { c };
+
+        if c == separator && index > 1 && normalized.ends_with(separator) {
+            continue;
+        }
+
+        normalized.push(c);
+    }
+
+    let trimmed = normalized.trim_end_matches(separator);
+
+    if trimmed.is_empty() && !normalized.is_empty() {
+        separator.to_string()
+    } else if trimmed.len() == 2 && trimmed.ends_with(':') {
+        format!("{}{}", trimmed, separator)
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Determines which configuration file should be loaded.
+///
+/// Resolves the configuration file location without reading it. This provides a
+/// convenient way to configure the application without command-line arguments,
+/// and keeps the search testable by taking all locations as parameters.
+///
+/// # Search Order
+///
+/// 1. `cli_config` - The `--config` path, used as-is even if it doesn't exist
+///    (loading it will then report a proper error)
+/// 2. `<exe_dir>/config.json` - Useful for portable installations
+/// 3. `<cwd>/config.json` - Useful for development
+///
+/// # Arguments
+///
+/// * `cli_config` - Path passed via `--config`, if any
+/// * `exe_dir` - Directory containing the executable, if it could be determined
+/// * `cwd` - Current working directory
+///
+/// # Returns
+///
+/// A `ConfigSource` with `loaded: false`; the caller sets `loaded` after parsing
+/// the file successfully.
+pub fn locate_config(cli_config: Option<&PathBuf>, exe_dir: Option<&Path>, cwd: &Path) -> ConfigSource {
+    let found = |path: PathBuf, origin| ConfigSource {
+        path: Some(path.to_string_lossy().to_string()),
+        origin,
+        loaded: false,
+    };
+
+    if let Some(config_path) = cli_config {
+        return found(config_path.clone(), ConfigOrigin::Cli);
+    }
+
+    if let Some(exe_dir) = exe_dir {
+        let exe_config = exe_dir.join("config.json");
+        if exe_config.exists() {
+            return found(exe_config, ConfigOrigin::ExeDir);
+        }
+    }
+
+    let cwd_config = cwd.join("config.json");
+    if cwd_config.exists() {
+        return found(cwd_config, ConfigOrigin::Cwd);
+    }
+
+    ConfigSource::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{state_for, temp_vault, Recorder};
+    use crate::undo::FileChange;
+
+    fn root_of(dir: &Path) -> Vec<String> {
+        vec![dir.to_string_lossy().to_string()]
+    }
+
+    fn ids(nodes: &[crate::graph::Node]) -> Vec<&str> {
+        nodes.iter().map(|node| node.id.as_str()).collect()
+    }
+
+    #[test]
+    fn config_origin() {
+        let exe = temp_vault("config-exe");
+        let cwd = temp_vault("config-cwd");
+        let none = temp_vault("config-none");
+        let cli = PathBuf::from("/x/c.json");
+
+        assert_eq!(
+            locate_config(Some(&cli), Some(&exe), &cwd).origin,
+            ConfigOrigin::Cli
+        );
+        assert_eq!(
+            locate_config(None, Some(&exe), &none).origin,
+            ConfigOrigin::Default
+        );
+
+        fs::write(cwd.join("config.json"), "{}").unwrap();
+        assert_eq!(
+            locate_config(None, Some(&exe), &cwd).origin,
+            ConfigOrigin::Cwd
+        );
+
+        fs::write(exe.join("config.json"), "{}").unwrap();
+        let source = locate_config(None, Some(&exe), &cwd);
+        assert_eq!(source.origin, ConfigOrigin::ExeDir);
+        assert!(source.path.unwrap().ends_with("config.json"));
+    }
+
+    #[test]
+    fn json_errors_name_the_key() {
+        let e = AppConfig::parse_json("{\n \"include_patterns\": \"x\"\n}").unwrap_err();
+        assert!(
+            e.starts_with("`include_patterns` must be a list (line 2"),
+            "{}",
+            e
+        );
+
+        let e = AppConfig::parse_json("{\"roo_dir\": \"x\"}").unwrap_err();
+        assert!(
+            e.starts_with("Unknown configuration key `roo_dir`; expected one of"),
+            "{}",
+            e
+        );
+
+        let e = AppConfig::parse_json("{\"root_dir\": }").unwrap_err();
+        assert!(e.starts_with("Invalid JSON at line 1"), "{}", e);
+
+        assert!(AppConfig::parse_json("{\"template_variables\": {\"a\": 1}}").is_err());
+        assert!(AppConfig::parse_json("{\"root_dir\": null}").is_ok());
+    }
+
+    #[test]
+    fn cli_offset_wins_over_file() {
+        let mut file = AppConfig::default();
+        file.previewer.offset = Some(1);
+        let mut cli = AppConfig::default();
+        cli.previewer.offset = Some(2);
+
+        assert_eq!(
+            AppConfig::merge(file.clone(), cli).previewer.offset,
+            Some(2)
+        );
+        assert_eq!(
+            AppConfig::merge(file, AppConfig::default())
+                .previewer
+                .offset,
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn root_dir_normalization() {
+        assert_eq!(normalize_root_dir("/home/me/notes/"), "/home/me/notes");
+        assert_eq!(normalize_root_dir("/home//me\\notes\\\\"), "/home/me/notes");
+        assert_eq!(normalize_root_dir("/"), "/");
+        assert_eq!(normalize_root_dir("C:\\notes\\"), "C:/notes");
+        assert_eq!(normalize_root_dir("C:\\"), "C:/");
+        assert_eq!(normalize_root_dir(""), "");
+
+        let joined = PathBuf::from(normalize_root_dir("/a/b/")).join("x.md");
+        assert_eq!(joined.to_str().unwrap(), "/a/b/x.md");
+    }
+
+    #[test]
+    fn group_colors_round_trip() {
+        let config = AppConfig::parse_json("{\"group_colors\": {\"important\": \"red\"}}").unwrap();
+        let back = AppConfig::parse_json(&serde_json::to_string(&config).unwrap()).unwrap();
+
+        assert_eq!(back.group_colors["important"], "red");
+    }
+
+    #[test]
+    fn invalid_link_pattern_falls_back_to_default() {
+        let mut config = AppConfig {
+            wiki_link_pattern: Some("(".to_string()),
+            ..AppConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        config.validate_wiki_link_pattern();
+
+        assert!(config.wiki_link_pattern.is_none());
+        assert_eq!(config.link_syntax().targets("[[a]] ((b))"), vec!["a"]);
+    }
+
+    #[test]
+    fn validation_rejects_zero_limits() {
+        assert!(AppConfig::default().validate().is_ok());
+
+        for config in [
+            AppConfig {
+                rescan_interval_secs: Some(0),
+                ..AppConfig::default()
+            },
+            AppConfig {
+                scan_threads: Some(0),
+                ..AppConfig::default()
+            },
+            AppConfig {
+                max_nodes: Some(0),
+                ..AppConfig::default()
+            },
+            AppConfig {
+                label_max_length: Some(0),
+                ..AppConfig::default()
+            },
+            AppConfig {
+                root_dir: Some("/definitely/missing".to_string()),
+                ..AppConfig::default()
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+
+        let config = AppConfig {
+            scan_threads: Some(0),
+            ..AppConfig::default()
+        };
+        assert_eq!(config.scan_threads(), 1);
+        assert!(AppConfig::default().scan_threads() >= 1);
+    }
+
+    #[test]
+    fn modify_throttle_defaults() {
+        assert_eq!(
+            AppConfig::default().modify_throttle(),
+            Duration::from_millis(DEFAULT_MODIFY_THROTTLE_MS)
+        );
+
+        let config = AppConfig {
+            modify_throttle_ms: Some(0),
+            ..AppConfig::default()
+        };
+        assert_eq!(config.modify_throttle(), Duration::ZERO);
+    }
+
+    #[test]
+    fn schema_lists_every_field() {
+        let mut config = AppConfig::default();
+        config.previewer.offset = Some(3);
+        let schema = config.schema();
+
+        let json = serde_json::to_value(AppConfig::default()).unwrap();
+        let mut keys: Vec<String> = Vec::new();
+        for (key, value) in json.as_object().unwrap() {
+            match value.as_object().filter(|_| key == "previewer") {
+                Some(nested) => keys.extend(nested.keys().map(|k| format!("{}.{}", key, k))),
+                None => keys.push(key.clone()),
+            }
+        }
+        let mut names: Vec<String> = schema.iter().map(|field| field.name.clone()).collect();
+        keys.sort();
+        names.sort();
+        assert_eq!(keys, names);
+
+        let field = |name: &str| {
+            schema
+                .iter()
+                .find(|field| field.name == name)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(field("root_dir").field_type, ConfigFieldType::String);
+        assert!(field("root_dir").optional);
+        assert_eq!(field("root_dirs").field_type, ConfigFieldType::StringList);
+        assert_eq!(field("group_colors").field_type, ConfigFieldType::StringMap);
+        assert_eq!(field("skip_hidden").field_type, ConfigFieldType::Bool);
+        assert_eq!(
+            field("previewer.offset").field_type,
+            ConfigFieldType::Integer
+        );
+        assert_eq!(field("previewer.offset").value, serde_json::json!(3));
+        assert_eq!(field("previewer.offset").default, serde_json::Value::Null);
+
+        for field in &schema {
+            let matches_type = match field.field_type {
+                ConfigFieldType::String => field.default.is_string() || field.default.is_null(),
+                ConfigFieldType::Integer => field.default.is_u64() || field.default.is_null(),
+                ConfigFieldType::Bool => field.default.is_boolean(),
+                ConfigFieldType::StringList => field.default.is_array(),
+                ConfigFieldType::StringMap => field.default.is_object(),
+            };
+            assert!(matches_type, "{}", field.name);
+            assert_eq!(field.optional, field.default.is_null(), "{}", field.name);
+        }
+    }
+
+    #[test]
+    fn node_size_scale_serializes_lowercase() {
+        let config = AppConfig {
+            node_size_scale: Some(crate::graph::NodeSizeScale::Sqrt),
+            ..AppConfig::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&config).unwrap()["node_size_scale"],
+            "sqrt"
+        );
+    }
+
+    #[test]
+    fn rescan_emits_one_completion_event() {
+        let dir = temp_vault("config-rescan-event");
+        fs::write(dir.join("a.md"), "[[b]] [[c]]").unwrap();
+        let state = state_for(&dir);
+        let recorder = Recorder::default();
+
+        let graph = state.rescan(&root_of(&dir), &recorder).unwrap();
+
+        assert_eq!(graph.nodes.len(), 3);
+        let events = recorder.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, events::RESCAN_COMPLETE_EVENT);
+        assert_eq!(events[0].1["node_count"], 3);
+        assert_eq!(events[0].1["edge_count"], 2);
+        assert_eq!(state.lock_cache().graph().nodes.len(), 3);
+    }
+
+    #[test]
+    fn multiple_roots_merge_into_one_graph() {
+        let a = temp_vault("config-roots-a");
+        let b = temp_vault("config-roots-b");
+        fs::write(a.join("x.md"), "[[y]]").unwrap();
+        fs::write(a.join("dup.md"), "a").unwrap();
+        fs::write(b.join("y.md"), "[[x]]").unwrap();
+        fs::write(b.join("dup.md"), "b").unwrap();
+        let config = AppConfig {
+            root_dir: Some(a.to_string_lossy().to_string()),
+            root_dirs: vec![
+                b.to_string_lossy().to_string(),
+                a.to_string_lossy().to_string(),
+            ],
+            ..AppConfig::default()
+        };
+        let roots = config.configured_roots();
+        assert_eq!(roots.len(), 2);
+        let state = AppState::new(config, Default::default());
+
+        let graph = state.rescan(&roots, &Recorder::default()).unwrap();
+
+        assert_eq!((graph.nodes.len(), graph.edges.len()), (3, 2));
+        assert!(graph
+            .nodes
+            .iter()
+            .all(|node| node.group.as_deref() != Some("phantom")));
+        let dup = graph.nodes.iter().find(|node| node.id == "dup").unwrap();
+        assert!(dup.file_path.contains("config-roots-a"));
+    }
+
+    #[test]
+    fn restore_cache_detects_stale_caches() {
+        let dir = temp_vault("config-restore");
+        let vault = dir.join("vault");
+        fs::create_dir_all(&vault).unwrap();
+        fs::write(vault.join("a.md"), "#t [[b]]").unwrap();
+        fs::write(vault.join("b.md"), "x").unwrap();
+        let file = dir.join("data").join("graph-cache.json");
+        let state = state_for(&vault).with_cache_file(Some(file.clone()));
+        let recorder = Recorder::default();
+
+        assert!(!state.restore_cache(&recorder).unwrap());
+        assert!(file.exists());
+        assert!(state.restore_cache(&recorder).unwrap());
+        assert_eq!(recorder.events().len(), 1);
+
+        fs::write(vault.join("c.md"), "y").unwrap();
+        assert!(!state.restore_cache(&recorder).unwrap());
+        assert_eq!(state.lock_cache().graph().nodes.len(), 3);
+        assert!(state.restore_cache(&recorder).unwrap());
+
+        fs::rename(vault.join("c.md"), vault.join("d.md")).unwrap();
+        assert!(!state.restore_cache(&recorder).unwrap());
+
+        let mut config = state.get_config();
+        config.ignore_patterns = vec!["d.md".to_string()];
+        state.update_config(config);
+        assert!(!state.restore_cache(&recorder).unwrap());
+        assert_eq!(state.lock_cache().graph().nodes.len(), 2);
+    }
+
+    #[test]
+    fn scan_on_startup_toggle() {
+        let dir = temp_vault("config-startup");
+        fs::write(dir.join("a.md"), "[[b]]").unwrap();
+
+        let state = state_for(&dir);
+        assert!(state.get_config().scan_on_startup);
+        assert!(!state.restore_cache(&Recorder::default()).unwrap());
+        assert_eq!(state.lock_cache().graph().nodes.len(), 2);
+
+        let state = state_for(&dir);
+        state.config.lock().unwrap().scan_on_startup = false;
+        let recorder = Recorder::default();
+        assert!(!state.restore_cache(&recorder).unwrap());
+        assert!(state.lock_cache().graph().nodes.is_empty());
+        assert!(recorder.events().is_empty());
+
+        assert!(AppConfig::parse_json("{}").unwrap().scan_on_startup);
+        let off = AppConfig::parse_json(r#"{"scan_on_startup": false}"#).unwrap();
+        assert!(!AppConfig::merge(off.clone(), AppConfig::default()).scan_on_startup);
+        assert!(!AppConfig::merge(AppConfig::default(), off).scan_on_startup);
+    }
+
+    #[test]
+    fn background_scan_updates_shared_state() {
+        let dir = temp_vault("config-background");
+        fs::write(dir.join("a.md"), "[[b]]").unwrap();
+        let state = state_for(&dir);
+        let recorder = Recorder::default();
+
+        state
+            .restore_cache_in_background(recorder.clone())
+            .join()
+            .unwrap();
+
+        assert_eq!(state.lock_cache().graph().nodes.len(), 2);
+        let events = recorder.events();
+        assert_eq!(
+            recorder.names(),
+            vec![events::RESCAN_COMPLETE_EVENT, events::GRAPH_READY_EVENT]
+        );
+        assert_eq!(events[1].1["node_count"], 2);
+        assert_eq!(events[1].1["restored"], false);
+        assert!(events[1].1["error"].is_null());
+
+        let state = state_for(&dir.join("missing"));
+        let recorder = Recorder::default();
+        state
+            .restore_cache_in_background(recorder.clone())
+            .join()
+            .unwrap();
+        assert!(recorder.events()[0].1["error"].is_string());
+    }
+
+    #[test]
+    fn reconcile_picks_up_missed_changes() {
+        let dir = temp_vault("config-reconcile");
+        fs::write(dir.join("a.md"), "[[b]]").unwrap();
+        fs::write(dir.join("b.md"), "").unwrap();
+        let state = state_for(&dir);
+        let roots = root_of(&dir);
+        let recorder = Recorder::default();
+        state.rescan(&roots, &recorder).unwrap();
+        assert!(state.reconcile(&roots, &recorder).unwrap().is_empty());
+
+        fs::write(dir.join("c.md"), "[[a]]").unwrap();
+        fs::remove_file(dir.join("b.md")).unwrap();
+        let delta = state.reconcile(&roots, &recorder).unwrap();
+        assert_eq!(ids(&delta.added_nodes), vec!["c"]);
+        assert_eq!(delta.added_edges.len(), 1);
+        let b = delta
+            .updated_nodes
+            .iter()
+            .find(|node| node.id == "b")
+            .unwrap();
+        assert_eq!(b.group.as_deref(), Some("phantom"));
+        assert!(state.lock_cache().contains_node("c"));
+
+        fs::write(dir.join("a.md"), "").unwrap();
+        let delta = state.reconcile(&roots, &recorder).unwrap();
+        assert_eq!(delta.removed_nodes, vec!["b"]);
+        assert!(!state.lock_cache().contains_node("b"));
+    }
+
+    #[test]
+    fn periodic_rescan_emits_deltas() {
+        let dir = temp_vault("config-periodic");
+        fs::write(dir.join("a.md"), "").unwrap();
+        let state = state_for(&dir);
+        assert!(!state.start_periodic_rescan(Recorder::default()));
+        state.rescan(&root_of(&dir), &Recorder::default()).unwrap();
+
+        fs::write(dir.join("b.md"), "[[a]]").unwrap();
+        let recorder = Recorder::default();
+        assert_eq!(
+            state
+                .reconcile_and_emit(&recorder)
+                .unwrap()
+                .added_nodes
+                .len(),
+            1
+        );
+        assert!(state.reconcile_and_emit(&recorder).unwrap().is_empty());
+        let deltas = |recorder: &Recorder| {
+            recorder
+                .events()
+                .into_iter()
+                .filter(|(name, _)| name == events::GRAPH_DELTA_EVENT)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(deltas(&recorder).len(), 1);
+
+        state.config.lock().unwrap().rescan_interval_secs = Some(1);
+        let recorder = Recorder::default();
+        assert!(state.start_periodic_rescan(recorder.clone()));
+        fs::write(dir.join("c.md"), "").unwrap();
+        thread::sleep(Duration::from_millis(1500));
+        state.stop_periodic_rescan();
+
+        assert!(state.periodic_rescan.lock().unwrap().is_none());
+        assert!(state.lock_cache().get_node("c").is_some());
+        assert_eq!(deltas(&recorder)[0].1["added_nodes"][0]["id"], "c");
+    }
+
+    #[test]
+    fn template_watch_emits_on_edit() {
+        let dir = temp_vault("config-template-watch");
+        let template = temp_vault("config-template-watch-outside").join("tpl.md");
+        fs::write(&template, "# {{title}}").unwrap();
+        let state = state_for(&dir);
+        state.config.lock().unwrap().template_phantom_node =
+            Some(template.to_string_lossy().to_string());
+        let recorder = Recorder::default();
+
+        assert!(!state.start_template_watch(recorder.clone()));
+        state.config.lock().unwrap().watch_template = true;
+        assert!(state.start_template_watch(recorder.clone()));
+        thread::sleep(TEMPLATE_POLL_INTERVAL + Duration::from_millis(200));
+        assert!(recorder.events().is_empty());
+
+        fs::write(&template, "# {{title}}\nedited").unwrap();
+        thread::sleep(TEMPLATE_POLL_INTERVAL * 2 + Duration::from_millis(200));
+        state.stop_template_watch();
+
+        let events = recorder.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, events::TEMPLATE_UPDATED_EVENT);
+        assert_eq!(events[0].1["exists"], true);
+        assert_eq!(events[0].1["path"], template.to_string_lossy().to_string());
+    }
+
+    #[test]
+    fn modified_file_with_identical_content_is_skipped() {
+        let dir = temp_vault("config-modified");
+        let note = dir.join("a.md");
+        fs::write(&note, "[[x]]").unwrap();
+        let state = state_for(&dir);
+        state.rescan(&root_of(&dir), &Recorder::default()).unwrap();
+        let recorder = Recorder::default();
+
+        assert!(state
+            .handle_file_modified(&note, &recorder)
+            .unwrap()
+            .is_empty());
+
+        fs::write(&note, "[[b]]").unwrap();
+        let delta = state.handle_file_modified(&note, &recorder).unwrap();
+        assert_eq!(ids(&delta.added_nodes), vec!["b"]);
+        assert_eq!(delta.removed_nodes, vec!["x"]);
+
+        assert!(state
+            .handle_file_modified(&note, &recorder)
+            .unwrap()
+            .is_empty());
+        assert_eq!(recorder.names(), vec![events::GRAPH_DELTA_EVENT]);
+    }
+
+    #[test]
+    fn vault_events_add_update_and_remove_notes() {
+        let dir = temp_vault("config-vault-events");
+        fs::write(dir.join("a.md"), "[[b]]").unwrap();
+        let state = state_for(&dir);
+        state.rescan(&root_of(&dir), &Recorder::default()).unwrap();
+        let recorder = Recorder::default();
+
+        fs::write(dir.join("b.md"), "[[c]]").unwrap();
+        let delta = state
+            .handle_vault_event(&dir.join("b.md"), &recorder)
+            .unwrap();
+        assert_eq!(ids(&delta.updated_nodes), vec!["b"]);
+        assert_eq!(ids(&delta.added_nodes), vec!["c"]);
+
+        fs::remove_file(dir.join("b.md")).unwrap();
+        let delta = state
+            .handle_vault_event(&dir.join("b.md"), &recorder)
+            .unwrap();
+        assert_eq!(delta.removed_nodes, vec!["c"]);
+        assert_eq!(
+            state.lock_cache().get_node("b").unwrap().group.as_deref(),
+            Some("phantom")
+        );
+
+        assert_eq!(
+            recorder.names(),
+            vec![events::GRAPH_DELTA_EVENT, events::GRAPH_DELTA_EVENT]
+        );
+    }
+
+    #[test]
+    fn vault_events_outside_the_scan_are_ignored() {
+        let dir = temp_vault("config-vault-ignored");
+        let outside = temp_vault("config-vault-ignored-outside");
+        fs::create_dir_all(dir.join(".trash")).unwrap();
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a.md"), "x").unwrap();
+        let state = state_for(&dir);
+        state.config.lock().unwrap().skip_hidden = true;
+        state.rescan(&root_of(&dir), &Recorder::default()).unwrap();
+        let recorder = Recorder::default();
+
+        for path in [
+            dir.join(".trash/note.md"),
+            dir.join("notes.txt"),
+            outside.join("elsewhere.md"),
+            dir.join("sub/a.md"),
+        ] {
+            fs::write(&path, "[[ghost]]").unwrap();
+            assert!(
+                state
+                    .handle_vault_event(&path, &recorder)
+                    .unwrap()
+                    .is_empty(),
+                "{:?}",
+                path
+            );
+        }
+
+        fs::remove_file(dir.join("sub/a.md")).unwrap();
+        assert!(state
+            .handle_vault_event(&dir.join("sub/a.md"), &recorder)
+            .unwrap()
+            .is_empty());
+        assert!(state
+            .lock_cache()
+            .get_node("a")
+            .is_some_and(|node| !node.file_path.is_empty()));
+        assert!(recorder.events().is_empty());
+    }
+
+    #[test]
+    fn vault_watch_applies_changes() {
+        let dir = temp_vault("config-vault-watch");
+        let state = state_for(&dir);
+        state.rescan(&root_of(&dir), &Recorder::default()).unwrap();
+        let recorder = Recorder::default();
+
+        assert!(state.start_vault_watch(recorder.clone()));
+        thread::sleep(Duration::from_millis(200));
+        fs::write(dir.join("new.md"), "[[other]]").unwrap();
+        thread::sleep(WATCH_DEBOUNCE * 4);
+        state.stop_vault_watch();
+
+        assert!(state.vault_watch.lock().unwrap().is_none());
+        assert!(state.lock_cache().get_node("new").is_some());
+        assert!(recorder
+            .names()
+            .contains(&events::GRAPH_DELTA_EVENT.to_string()));
+
+        let state = AppState::new(AppConfig::default(), Default::default());
+        assert!(!state.start_vault_watch(Recorder::default()));
+    }
+
+    #[test]
+    fn apply_config_restarts_even_if_saving_fails() {
+        let dir = temp_vault("config-apply");
+        fs::write(dir.join("a.md"), "[[b]]").unwrap();
+        let source = ConfigSource {
+            path: Some(dir.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let state = AppState::new(AppConfig::default(), source);
+        let recorder = Recorder::default();
+
+        let invalid = AppConfig {
+            scan_threads: Some(0),
+            ..AppConfig::default()
+        };
+        assert!(state.apply_config(invalid, recorder.clone()).is_err());
+        assert!(recorder.names().is_empty());
+
+        let config = AppConfig {
+            root_dir: Some(dir.to_string_lossy().to_string()),
+            ..AppConfig::default()
+        };
+        let err = state.apply_config(config, recorder.clone()).unwrap_err();
+        assert!(state.vault_watch.lock().unwrap().is_some());
+        state.stop_vault_watch();
+        state.stop_periodic_rescan();
+
+        assert!(err.contains("Failed to write configuration file"), "{}", err);
+        assert!(state.get_config().root_dir.is_some());
+        assert!(state.lock_cache().get_node("a").is_some());
+        assert_eq!(recorder.names(), vec![events::RESCAN_COMPLETE_EVENT]);
+    }
+
+    #[test]
+    fn throttle_coalesces_changes_within_the_window() {
+        let window = Duration::from_millis(100);
+        let mut throttle = ModifyThrottle::new(window);
+        let note = Path::new("/v/a.md");
+        let other = Path::new("/v/b.md");
+        let start = Instant::now();
+
+        assert!(throttle.offer(note, start));
+        assert!(throttle.offer(other, start));
+        assert!(!throttle.offer(note, start + Duration::from_millis(10)));
+        assert!(!throttle.offer(note, start + Duration::from_millis(20)));
+        assert_eq!(throttle.next_due(), Some(start + window));
+
+        assert!(throttle
+            .take_due(start + Duration::from_millis(50))
+            .is_empty());
+        assert_eq!(throttle.take_due(start + window), vec![note.to_path_buf()]);
+        assert_eq!(throttle.next_due(), None);
+
+        assert!(!throttle.offer(note, start + window + Duration::from_millis(10)));
+        assert!(throttle.offer(other, start + window + Duration::from_millis(10)));
+
+        let mut disabled = ModifyThrottle::new(Duration::ZERO);
+        assert!(disabled.offer(note, start));
+        assert!(disabled.offer(note, start));
+    }
+
+    #[test]
+    fn empty_operations_are_not_recorded() {
+        let state = AppState::new(AppConfig::default(), Default::default());
+
+        state.record_operation(Operation {
+            description: "nothing".to_string(),
+            changes: Vec::new(),
+        });
+        assert!(state.pop_operation().is_none());
+
+        let changes = vec![FileChange::Created {
+            path: PathBuf::from("/v/a.md"),
+        }];
+        state.record_operation(Operation {
+            description: "create".to_string(),
+            changes,
+        });
+        assert_eq!(state.pop_operation().unwrap().description, "create");
     }
 }
@@ -0,0 +1,362 @@
+//! Backend-to-frontend event emission.
+//!
+//! This module defines the events the backend pushes to the frontend and a small
+//! `EventEmitter` abstraction over Tauri's `AppHandle`. Code that emits events
+//! takes an `&impl EventEmitter` instead of a concrete `AppHandle`, so it can be
+//! driven by any emitter (e.g. one that records events for inspection).
+//!
+//! # Events
+//!
+//! - `rescan-complete`: A full rebuild of the graph cache finished
+//! - `graph-begin`, `graph-chunk`, `graph-end`: The graph is being streamed in
+//!   chunks (see `emit_graph_stream`)
+//! - `graph-delta`: The graph cache changed by the enclosed `GraphDelta`
+//! - `graph-ready`: The graph cache was populated at startup
+//! - `template-updated`: The watched phantom-node template file changed
+
+use crate::cache::GraphDelta;
+use crate::graph::{Edge, GraphData, Node};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+/// Name of the event emitted after every full rebuild of the graph cache.
+pub const RESCAN_COMPLETE_EVENT: &str = "rescan-complete";
+
+/// Name of the event opening a streamed graph transfer.
+pub const GRAPH_BEGIN_EVENT: &str = "graph-begin";
+
+/// Name of the events carrying the chunks of a streamed graph.
+pub const GRAPH_CHUNK_EVENT: &str = "graph-chunk";
+
+/// Name of the event closing a streamed graph transfer.
+pub const GRAPH_END_EVENT: &str = "graph-end";
+
+/// Name of the event carrying incremental changes of the graph cache.
+pub const GRAPH_DELTA_EVENT: &str = "graph-delta";
+
+/// Name of the event emitted once the startup population of the graph cache finished.
+pub const GRAPH_READY_EVENT: &str = "graph-ready";
+
+/// Name of the event emitted when the watched template file changed.
+pub const TEMPLATE_UPDATED_EVENT: &str = "template-updated";
+
+/// Default maximum number of nodes or edges per `graph-chunk` event.
+pub const GRAPH_CHUNK_SIZE: usize = 500;
+
+/// Abstraction over anything that can deliver events to the frontend.
+pub trait EventEmitter {
+    /// Emits an event with a serializable payload to all listeners.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the event could not be delivered.
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) -> Result<(), String>;
+}
+
+impl EventEmitter for AppHandle {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) -> Result<(), String> {
+        self.emit(event, payload).map_err(|e| e.to_string())
+    }
+}
+
+/// Payload of the `rescan-complete` event.
+///
+/// # Fields
+///
+/// * `node_count` - Number of nodes in the rebuilt graph
+/// * `edge_count` - Number of edges in the rebuilt graph
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RescanComplete {
+    pub node_count: usize,
+    pub edge_count: usize,
+}
+
+/// Notifies the frontend that the graph cache was rebuilt from a full scan.
+///
+/// Must be called exactly once per rebuild, after the new cache is in place, so
+/// the frontend can safely refresh its graph as soon as the event arrives.
+///
+/// # Arguments
+///
+/// * `emitter` - Event emitter (usually the `AppHandle`)
+/// * `graph` - The graph now stored in the cache
+///
+/// # Errors
+///
+/// Returns an error message if the event could not be emitted.
+pub fn emit_rescan_complete(emitter: &impl EventEmitter, graph: &GraphData) -> Result<(), String> {
+    emitter.emit_event(
+        RESCAN_COMPLETE_EVENT,
+        RescanComplete {
+            node_count: graph.nodes.len(),
+            edge_count: graph.edges.len(),
+        },
+    )
+}
+
+/// Payload of the `graph-begin` event.
+///
+/// # Fields
+///
+/// * `node_count` - Total number of nodes that will be streamed
+/// * `edge_count` - Total number of edges that will be streamed
+/// * `chunk_count` - Number of `graph-chunk` events that will follow
+/// * `directed` - Whether edges should be drawn with arrows (see `GraphData::directed`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphBegin {
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub chunk_count: usize,
+    pub directed: bool,
+}
+
+/// Payload of a `graph-chunk` event.
+///
+/// A chunk carries either nodes or edges. All node chunks are sent before the
+/// first edge chunk, so every edge arrives after both of its endpoints.
+///
+/// # Fields
+///
+/// * `index` - Position of the chunk, starting at 0
+/// * `nodes` - Nodes in this chunk
+/// * `edges` - Edges in this chunk
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphChunk {
+    pub index: usize,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+/// Payload of the `graph-end` event.
+///
+/// # Fields
+///
+/// * `chunk_count` - Number of `graph-chunk` events that were sent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphEnd {
+    pub chunk_count: usize,
+}
+
+/// Streams a graph to the frontend in chunks.
+///
+/// Sends a `graph-begin` event with the totals, then the nodes and edges in
+/// `graph-chunk` events of at most `chunk_size` items each, and finally a
+/// `graph-end` event. This lets the frontend build very large graphs
+/// incrementally instead of parsing one giant JSON object.
+///
+/// # Arguments
+///
+/// * `emitter` - Event emitter (usually the `AppHandle`)
+/// * `graph` - The graph to stream
+/// * `chunk_size` - Maximum number of nodes or edges per chunk (at least 1)
+///
+/// # Returns
+///
+/// The number of `graph-chunk` events sent.
+///
+/// # Errors
+///
+/// Returns an error message if an event could not be emitted. The stream is
+/// then incomplete and no `graph-end` event is sent.
+pub fn emit_graph_stream(
+    emitter: &impl EventEmitter,
+    graph: &GraphData,
+    chunk_size: usize,
+) -> Result<usize, String> {
+    let chunk_size = chunk_size.max(1);
+    let chunk_count =
+        graph.nodes.len().div_ceil(chunk_size) + graph.edges.len().div_ceil(chunk_size);
+
+    emitter.emit_event(
+        GRAPH_BEGIN_EVENT,
+        GraphBegin {
+            node_count: graph.nodes.len(),
+            edge_count: graph.edges.len(),
+            chunk_count,
+            directed: graph.directed,
+        },
+    )?;
+
+    let node_chunks = graph.nodes.chunks(chunk_size).map(|nodes| (nodes.to_vec(), Vec::new()));
+    let edge_chunks = graph.edges.chunks(chunk_size).map(|edges| (Vec::new(), edges.to_vec()));
+
+    for (index, (nodes, edges)) in node_chunks.chain(edge_chunks).enumerate() {
+        emitter.emit_event(GRAPH_CHUNK_EVENT, GraphChunk { index, nodes, edges })?;
+    }
+
+    emitter.emit_event(GRAPH_END_EVENT, GraphEnd { chunk_count })?;
+
+    Ok(chunk_count)
+}
+
+/// Sends incremental graph changes to the frontend.
+///
+/// The payload is the `GraphDelta` itself, so the frontend can patch its graph
+/// in place instead of reloading it.
+///
+/// # Arguments
+///
+/// * `emitter` - Event emitter (usually the `AppHandle`)
+/// * `delta` - Changes to apply, as computed by `GraphCache::diff`
+///
+/// # Errors
+///
+/// Returns an error message if the event could not be emitted.
+pub fn emit_graph_delta(emitter: &impl EventEmitter, delta: &GraphDelta) -> Result<(), String> {
+    emitter.emit_event(GRAPH_DELTA_EVENT, delta.clone())
+}
+
+/// Payload of the `graph-ready` event.
+///
+/// # Fields
+///
+/// * `node_count` - Number of nodes in the cache
+/// * `edge_count` - Number of edges in the cache
+/// * `restored` - Whether the cache was restored from disk rather than scanned
+/// * `error` - Error message if populating the cache failed; the graph is then empty
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphReady {
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub restored: bool,
+    pub error: Option<String>,
+}
+
+/// Notifies the frontend that the startup population of the graph cache finished.
+///
+/// Sent exactly once per launch, also when populating failed or was skipped,
+/// so the frontend can always leave its loading state.
+///
+/// # Arguments
+///
+/// * `emitter` - Event emitter (usually the `AppHandle`)
+/// * `graph` - The graph now stored in the cache
+/// * `result` - Outcome of `AppState::restore_cache`
+///
+/// # Errors
+///
+/// Returns an error message if the event could not be emitted.
+pub fn emit_graph_ready(
+    emitter: &impl EventEmitter,
+    graph: &GraphData,
+    result: &Result<bool, String>,
+) -> Result<(), String> {
+    emitter.emit_event(
+        GRAPH_READY_EVENT,
+        GraphReady {
+            node_count: graph.nodes.len(),
+            edge_count: graph.edges.len(),
+            restored: matches!(result, Ok(true)),
+            error: result.as_ref().err().cloned(),
+        },
+    )
+}
+
+/// Payload of the `template-updated` event.
+///
+/// # Fields
+///
+/// * `path` - Path of the template file, as configured
+/// * `exists` - Whether the file exists after the change (`false` if it was deleted)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TemplateUpdated {
+    pub path: String,
+    pub exists: bool,
+}
+
+/// Notifies the frontend that the template file was modified, created or deleted.
+///
+/// Templates don't affect the graph; the event lets the frontend refresh a
+/// template preview (see `AppState::start_template_watch`).
+///
+/// # Arguments
+///
+/// * `emitter` - Event emitter (usually the `AppHandle`)
+/// * `path` - Path of the template file
+///
+/// # Errors
+///
+/// Returns an error message if the event could not be emitted.
+pub fn emit_template_updated(emitter: &impl EventEmitter, path: &Path) -> Result<(), String> {
+    emitter.emit_event(
+        TEMPLATE_UPDATED_EVENT,
+        TemplateUpdated {
+            path: path.to_string_lossy().to_string(),
+            exists: path.exists(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{build_graph_with, BuildOptions};
+    use crate::testing::{markdown_file, Recorder};
+
+    #[test]
+    fn rescan_complete_carries_counts() {
+        let recorder = Recorder::default();
+        let graph = build_graph_with(vec![markdown_file("a", "[[b]]")], &BuildOptions::default());
+
+        emit_rescan_complete(&recorder, &graph).unwrap();
+
+        let events = recorder.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, RESCAN_COMPLETE_EVENT);
+        assert_eq!(events[0].1["node_count"], 2);
+        assert_eq!(events[0].1["edge_count"], 1);
+    }
+
+    #[test]
+    fn graph_stream_covers_every_node_and_edge() {
+        let files = (0..7)
+            .map(|i| markdown_file(&format!("n{i}"), &format!("[[n{}]] [[x{i}]]", (i + 1) % 7)))
+            .collect();
+        let graph = build_graph_with(files, &BuildOptions::default());
+        assert_eq!((graph.nodes.len(), graph.edges.len()), (14, 14));
+        let recorder = Recorder::default();
+
+        assert_eq!(emit_graph_stream(&recorder, &graph, 5).unwrap(), 6);
+
+        let events = recorder.events();
+        assert_eq!(events.len(), 8);
+        assert_eq!(events[0].0, GRAPH_BEGIN_EVENT);
+        assert_eq!(events[0].1["chunk_count"], 6);
+        assert_eq!(events[7].0, GRAPH_END_EVENT);
+
+        let chunks = &events[1..7];
+        assert!(chunks.iter().all(|(name, _)| name == GRAPH_CHUNK_EVENT));
+        let count = |key: &str| -> usize {
+            chunks
+                .iter()
+                .map(|(_, chunk)| chunk[key].as_array().unwrap().len())
+                .sum()
+        };
+        assert_eq!((count("nodes"), count("edges")), (14, 14));
+        assert!(chunks[2].1["edges"].as_array().unwrap().is_empty());
+        assert!(chunks[3].1["nodes"].as_array().unwrap().is_empty());
+
+        let recorder = Recorder::default();
+        assert_eq!(
+            emit_graph_stream(&recorder, &GraphData::new(), 5).unwrap(),
+            0
+        );
+        assert_eq!(recorder.names(), vec![GRAPH_BEGIN_EVENT, GRAPH_END_EVENT]);
+    }
+
+    #[test]
+    fn graph_ready_reports_the_outcome() {
+        let graph = build_graph_with(vec![markdown_file("a", "")], &BuildOptions::default());
+        let recorder = Recorder::default();
+
+        emit_graph_ready(&recorder, &graph, &Ok(true)).unwrap();
+        emit_graph_ready(&recorder, &graph, &Err("boom".to_string())).unwrap();
+
+        let events = recorder.events();
+        assert_eq!(events[0].1["restored"], true);
+        assert!(events[0].1["error"].is_null());
+        assert_eq!(events[1].1["restored"], false);
+        assert_eq!(events[1].1["error"], "boom");
+    }
+}
@@ -14,6 +14,8 @@
 //!
 //! Node size (`value` field) is determined by the number of incoming links (backlinks).
 //! Notes that are referenced more frequently appear larger in the visualization.
+//! The `size` field holds `value` scaled by the configured `NodeSizeScale`, so a few
+//! heavily-linked hubs don't dwarf every other node.
 //!
 //! # Performance
 //!
@@ -22,9 +24,14 @@
 //! number of edges.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use crate::scanner::{scan_directory, MarkdownFile};
-use crate::parser;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::UNIX_EPOCH;
+use crate::scanner::MarkdownFile;
+use crate::parser::{self, Frontmatter, LinkSyntax, ParsedContent};
+use regex::Regex;
 
 /// Represents a node in the knowledge graph.
 ///
@@ -37,22 +44,39 @@ use crate::parser;
 /// * `id` - Unique identifier (typically the file name without extension)
 /// * `label` - Display name shown in the visualization
 /// * `value` - Node size metric based on number of incoming links (backlinks)
+/// * `size` - `value` scaled with the graph's `node_size_scale`, for rendering
 /// * `group` - Optional grouping identifier (e.g., "phantom" for broken links)
 /// * `file_path` - Full file system path (empty for phantom nodes)
 /// * `hashtags` - List of hashtags found in the file content
+/// * `color` - Display color resolved from the node's group (see `apply_group_colors`)
+/// * `size_bytes` - File size in bytes, if file metadata is enabled (None for phantoms)
+/// * `modified` - Last modification time in milliseconds since the Unix epoch, if
+///   file metadata is enabled (None for phantoms)
+/// * `sections` - Texts of the note's H1 and H2 headings, if heading sections are
+///   enabled (empty for phantoms)
 ///
 /// # Serialization
 ///
 /// This structure is serialized to JSON and sent to the frontend for vis-network
-/// rendering. All fields are included in the JSON output.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// rendering. All fields are included in the JSON output, except the file
+/// metadata fields and `sections` when they are not set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Node {
     pub id: String,
     pub label: String,
     pub value: usize,
+    #[serde(default)]
+    pub size: f64,
     pub group: Option<String>,
     pub file_path: String,
     pub hashtags: Vec<String>,
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<String>,
 }
 
 /// Represents a directed edge between two nodes in the graph.
@@ -69,7 +93,7 @@ pub struct Node {
 ///
 /// Serialized to JSON for vis-network rendering. Vis-network uses these edges
 /// to draw connections between nodes.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Edge {
     pub from: String,
     pub to: String,
@@ -84,20 +108,43 @@ pub struct Edge {
 ///
 /// * `nodes` - Vector of all nodes (files and phantoms) in the graph
 /// * `edges` - Vector of all directed connections between nodes
+/// * `directed` - Whether links should be presented as directed. Edges are always
+///   stored with a direction; when `false` (the `undirected` setting), the
+///   frontend should render them without arrows.
+/// * `node_size_scale` - Scale used to derive each node's `size` from its `value`
+/// * `aliases` - Frontmatter aliases mapped to the note declaring them (see
+///   `build_alias_map`); omitted from JSON when empty
+/// * `truncated` - Whether nodes were left out to respect the configured
+///   `max_nodes` (see `truncate_graph`); omitted from JSON when `false`
 ///
 /// # JSON Format
 ///
 /// The serialized format matches vis-network's expected data structure:
 /// ```json
 /// {
-///   "nodes": [{"id": "note1", "label": "note1", "value": 5, ...}],
-///   "edges": [{"from": "note1", "to": "note2"}]
+///   "nodes": [{"id": "note1", "label": "note1", "value": 5, "size": 5.0, ...}],
+///   "edges": [{"from": "note1", "to": "note2"}],
+///   "directed": true,
+///   "node_size_scale": "linear"
 /// }
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphData {
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
+    #[serde(default = "default_directed")]
+    pub directed: bool,
+    #[serde(default)]
+    pub node_size_scale: NodeSizeScale,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// Graphs are directed unless configured otherwise.
+fn default_directed() -> bool {
+    true
 }
 
 impl GraphData {
@@ -108,13 +155,321 @@ impl GraphData {
     ///
     /// # Returns
     ///
-    /// A new `GraphData` instance with no nodes or edges.
+    /// A new directed `GraphData` instance with no nodes or edges.
     pub fn new() -> Self {
         Self {
             nodes: Vec::new(),
             edges: Vec::new(),
+            directed: true,
+            node_size_scale: NodeSizeScale::default(),
+            aliases: BTreeMap::new(),
+            truncated: false,
+        }
+    }
+
+    /// Recomputes every node's `size` from its `value` with `node_size_scale`.
+    ///
+    /// Called after node values change, e.g. when the cache adds or removes a note.
+    pub fn rescale_nodes(&mut self) {
+        let scale = self.node_size_scale;
+
+        for node in &mut self.nodes {
+            node.size = scale.apply(node.value);
+        }
+    }
+}
+
+/// Scale applied to a node's incoming link count to get its display size.
+///
+/// With linear sizing a few heavily-linked hubs make every other node tiny; the
+/// logarithmic and square-root scales compress large counts. Every scale maps a
+/// count of 0 to a size of 0.
+///
+/// Serialized in lowercase (`"linear"`, `"log"`, `"sqrt"`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeSizeScale {
+    /// Size equals the link count
+    #[default]
+    Linear,
+    /// Size is `ln(1 + count)`
+    Log,
+    /// Size is the square root of the link count
+    Sqrt,
+}
+
+impl NodeSizeScale {
+    /// Returns the display size for a node with `value` incoming links.
+    pub fn apply(self, value: usize) -> f64 {
+        let value = value as f64;
+
+        match self {
+            Self::Linear => value,
+            Self::Log => value.ln_1p(),
+            Self::Sqrt => value.sqrt(),
+        }
+    }
+}
+
+/// Node in the D3 force-directed layout format.
+///
+/// Carries the same display attributes as `Node`, without file metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct D3Node {
+    pub id: String,
+    pub label: String,
+    pub value: usize,
+    pub size: f64,
+    pub group: Option<String>,
+    pub color: Option<String>,
+}
+
+/// Link in the D3 force-directed layout format.
+///
+/// Equivalent to `Edge`, using D3's `source`/`target` naming.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct D3Link {
+    pub source: String,
+    pub target: String,
+}
+
+/// Graph in the shape expected by D3's force simulation.
+///
+/// # JSON Format
+///
+/// ```json
+/// {
+///   "nodes": [{"id": "note1", "label": "note1", "value": 5, "size": 5.0, "group": null}],
+///   "links": [{"source": "note1", "target": "note2"}]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct D3Graph {
+    pub nodes: Vec<D3Node>,
+    pub links: Vec<D3Link>,
+}
+
+impl From<&GraphData> for D3Graph {
+    /// Converts vis-network graph data into the D3 format.
+    ///
+    /// Node and edge order is preserved, so the result has exactly as many nodes
+    /// and links as the source graph has nodes and edges.
+    fn from(graph: &GraphData) -> Self {
+        Self {
+            nodes: graph
+                .nodes
+                .iter()
+                .map(|node| D3Node {
+                    id: node.id.clone(),
+                    label: node.label.clone(),
+                    value: node.value,
+                    size: node.size,
+                    group: node.group.clone(),
+                    color: node.color.clone(),
+                })
+                .collect(),
+            links: graph
+                .edges
+                .iter()
+                .map(|edge| D3Link {
+                    source: edge.from.clone(),
+                    target: edge.to.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Leading `YYYY-MM-DD ` date removed by `LabelFormat::apply` with `strip_date_prefix`.
+///
+/// Compiled once, as labels are formatted for every node of every build.
+static DATE_PREFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}\s+").unwrap());
+
+/// Display formatting applied to node labels.
+///
+/// Only labels are affected; node IDs stay untouched so wiki-links keep
+/// resolving.
+///
+/// # Fields
+///
+/// * `max_length` - Maximum number of characters kept; longer labels are cut
+///   and end with `…`. None disables truncation.
+/// * `strip_date_prefix` - Whether a leading `YYYY-MM-DD ` date (as in daily
+///   note names like `2025-01-31 Standup`) is removed
+/// * `namespaces` - Namespace prefixes (without the trailing `/`) removed from the
+///   start of labels, so `people/Alice` is shown as `Alice` for namespace `people`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LabelFormat {
+    pub max_length: Option<usize>,
+    pub strip_date_prefix: bool,
+    pub namespaces: Vec<String>,
+}
+
+impl LabelFormat {
+    /// Formats a label: strips the namespace, then the date prefix, then truncates.
+    ///
+    /// A label consisting of nothing but a namespace or a date is kept as is, so
+    /// it never becomes empty.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let format = LabelFormat {
+    ///     max_length: Some(5),
+    ///     strip_date_prefix: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(format.apply("2025-01-31 Standup notes"), "Stand…");
+    /// ```
+    pub fn apply(&self, label: &str) -> String {
+        let mut label = label;
+
+        if let Some(namespace) = self.namespace(label) {
+            label = &label[namespace.len() + 1..];
+        }
+
+        if self.strip_date_prefix {
+            if let Some(prefix) = DATE_PREFIX.find(label) {
+                if prefix.end() < label.len() {
+                    label = &label[prefix.end()..];
+                }
+            }
+        }
+
+        match self.max_length {
+            Some(max_length) if label.chars().count() > max_length => {
+                let truncated: String = label.chars().take(max_length).collect();
+                format!("{}…", truncated.trim_end())
+            }
+            _ => label.to_string(),
         }
     }
+
+    /// Returns the configured namespace a name starts with, if any.
+    ///
+    /// A name is in namespace `people` if it starts with `people/` and has
+    /// something after the slash. When several configured namespaces match, the
+    /// first one listed wins.
+    pub fn namespace<'a>(&'a self, name: &str) -> Option<&'a str> {
+        self.namespaces
+            .iter()
+            .map(|namespace| namespace.trim_end_matches('/'))
+            .find(|namespace| {
+                name.strip_prefix(namespace)
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .is_some_and(|rest| !rest.is_empty())
+            })
+    }
+}
+
+/// Options controlling how notes are turned into a graph.
+///
+/// The defaults are `[[...]]` wiki-links, unmodified labels, every file as a
+/// note and no self-links.
+///
+/// # Fields
+///
+/// * `link_syntax` - Wiki-link syntax used to find links in each note
+/// * `label_format` - Formatting applied to every node label
+/// * `min_content_chars` - Minimum number of characters (excluding frontmatter and
+///   surrounding whitespace) a file needs to become a note; see `is_stub`
+/// * `allow_self_links` - Keep links from a note to itself as self-loop edges.
+///   When false they are dropped and don't count towards the note's backlinks.
+/// * `namespace_groups` - Put notes in one of the `label_format.namespaces` into a
+///   group named after the namespace; see `namespace_group` and `phantom_group`
+/// * `roots` - Root directories the notes were scanned from. A note's namespace
+///   is its folder relative to its root, so notes outside every root have none.
+/// * `node_size_scale` - Scale deriving each node's `size` from its incoming link count
+/// * `drop_excluded_links` - Drop links to notes excluded through their frontmatter
+///   (see `is_excluded_from_graph`) instead of showing them as phantom nodes
+/// * `file_metadata` - Read each note's file size and modification time into its
+///   node; costs one `stat` per note
+/// * `heading_sections` - Copy each note's top-level headings into its node's
+///   `sections` (see `parser::extract_sections`)
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    pub link_syntax: LinkSyntax,
+    pub label_format: LabelFormat,
+    pub min_content_chars: Option<usize>,
+    pub allow_self_links: bool,
+    pub namespace_groups: bool,
+    pub roots: Vec<PathBuf>,
+    pub node_size_scale: NodeSizeScale,
+    pub drop_excluded_links: bool,
+    pub file_metadata: bool,
+    pub heading_sections: bool,
+}
+
+impl BuildOptions {
+    /// Returns `true` if a file is too short to count as a note.
+    ///
+    /// Stub files are left out of the graph: if other notes link to them they
+    /// appear as phantom nodes, otherwise they don't appear at all. The body is
+    /// measured without frontmatter and surrounding whitespace, so a file holding
+    /// only a frontmatter block is a stub for any positive minimum.
+    pub fn is_stub(&self, content: &str) -> bool {
+        let Some(min_content_chars) = self.min_content_chars else {
+            return false;
+        };
+
+        let (_, body) = parser::split_frontmatter(content);
+
+        body.trim().chars().count() < min_content_chars
+    }
+
+    /// Returns `true` if a file doesn't become a note.
+    ///
+    /// That is the case for stub files (see `is_stub`) and for notes excluded
+    /// through their frontmatter (see `is_excluded_from_graph`).
+    pub fn skips_file(&self, content: &str) -> bool {
+        self.is_stub(content) || is_excluded_from_graph(content)
+    }
+
+    /// Returns `true` if a link from `from` to `to` becomes an edge.
+    ///
+    /// Only self-links are ever dropped, and only when `allow_self_links` is off.
+    pub fn keeps_link(&self, from: &str, to: &str) -> bool {
+        self.allow_self_links || from != to
+    }
+
+    /// Returns the group a note gets from its namespace.
+    ///
+    /// Node IDs are file stems, so the namespace is taken from the note's path
+    /// relative to the root it was scanned from: `people/Alice.md` gives
+    /// `Some("people")` when `people` is one of the configured namespaces and
+    /// `namespace_groups` is on; `None` otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the note's file
+    pub fn namespace_group(&self, path: &Path) -> Option<String> {
+        if !self.namespace_groups {
+            return None;
+        }
+
+        let relative = self.roots.iter().find_map(|root| path.strip_prefix(root).ok())?;
+        let name: Vec<String> = relative
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        self.label_format.namespace(&name.join("/")).map(str::to_string)
+    }
+
+    /// Returns the group of a phantom node.
+    ///
+    /// A phantom node's ID is the link target, so `[[people/Alice]]` without a
+    /// matching note is in namespace `people`. With `namespace_groups` on, that
+    /// namespace is the group; otherwise the group is `phantom`.
+    pub fn phantom_group(&self, node_id: &str) -> String {
+        self.namespace_groups
+            .then(|| self.label_format.namespace(node_id))
+            .flatten()
+            .unwrap_or("phantom")
+            .to_string()
+    }
 }
 
 /// Constructs a graph from a collection of markdown files.
@@ -128,16 +483,36 @@ impl GraphData {
 /// # Arguments
 ///
 /// * `files` - Vector of parsed markdown files with their content
+/// * `options` - Link syntax, label formatting and stub threshold to apply
 ///
 /// # Returns
 ///
 /// A complete `GraphData` structure with all nodes and edges populated.
 ///
+/// # Options
+///
+/// Wiki-links are extracted with the configured link syntax, node labels are
+/// formatted with the configured `LabelFormat`, stub files below
+/// `min_content_chars` and notes excluded through their frontmatter are skipped
+/// (see `BuildOptions::skips_file`), self-links are kept only if
+/// `allow_self_links` is set, and node sizes are scaled with `node_size_scale`.
+///
+/// Links to a skipped file produce a phantom node, like links to a missing
+/// note. With `drop_excluded_links`, links to excluded notes are dropped instead,
+/// so the excluded note doesn't appear at all.
+///
+/// A link containing a path separator (`[[../shared/note]]`, `[[./sub/note]]`)
+/// is resolved relative to the directory of the note containing it (see
+/// `resolve_relative_link`); other links resolve by note name and alias.
+///
 /// # Algorithm Details
 ///
 /// ## Phase 1: Edge Creation and Link Counting
 /// - Iterates through all files and their wiki-links
+/// - Resolves each link target: a note with that file name wins, otherwise a note
+///   declaring it in its frontmatter `aliases` (see `build_alias_map`)
 /// - Creates edges regardless of whether target files exist
+/// - Drops self-links (a note linking to itself) unless `allow_self_links` is set
 /// - Maintains a `link_counts` HashMap to track incoming links per node
 /// - Tracks which nodes are referenced and whether they exist
 ///
@@ -146,10 +521,13 @@ impl GraphData {
 /// - Sets `value` field based on incoming link count (for node sizing)
 /// - Extracts hashtags from file content for categorization
 /// - Marks nodes as existing in the tracking HashMap
+/// - Uses the frontmatter `title` (or first alias) as the label, see `preferred_label`
+/// - Applies frontmatter `group` and `color` overrides, see `note_node`
 ///
 /// ## Phase 3: Phantom Node Creation
 /// - Identifies referenced nodes that don't have corresponding files
-/// - Creates phantom nodes with `group: "phantom"` for visual distinction
+/// - Creates phantom nodes with `group: "phantom"` for visual distinction, or
+///   their namespace as group (see `BuildOptions::phantom_group`)
 /// - Phantom nodes have empty `file_path` and no hashtags
 /// - Still includes backlink count for sizing
 ///
@@ -165,7 +543,19 @@ impl GraphData {
 /// - `note.md` becomes node ID "note"
 /// - Wiki-link `[[note]]` targets node ID "note"
 /// - Files with the same name in different directories will collide
-pub fn build_graph(files: Vec<MarkdownFile>) -> GraphData {
+/// - Wiki-link `[[Bob]]` targets node ID "bob-smith" if no `Bob.md` exists and
+///   `bob-smith.md` declares `aliases: [Bob]`
+pub fn build_graph_with(files: Vec<MarkdownFile>, options: &BuildOptions) -> GraphData {
+    let (files, skipped): (Vec<MarkdownFile>, Vec<MarkdownFile>) = files
+        .into_iter()
+        .partition(|file| !options.skips_file(&file.content));
+
+    let dropped_targets: HashSet<String> = skipped
+        .into_iter()
+        .filter(|file| options.drop_excluded_links && is_excluded_from_graph(&file.content))
+        .map(|file| file.name)
+        .collect();
+
     let mut graph = GraphData::new();
     let mut link_counts: HashMap<String, usize> = HashMap::new();
     let mut all_referenced_nodes: HashMap<String, bool> = HashMap::new();
@@ -175,10 +565,36 @@ pub fn build_graph(files: Vec<MarkdownFile>) -> GraphData {
         .map(|f| (f.name.clone(), f))
         .collect();
 
-    for file in &files {
-        let parsed = parser::parse_markdown(&file.content);
+    let note_paths = note_path_index(files.iter().map(|file| (file.path.as_path(), &file.name)));
 
+    let parsed_files: Vec<ParsedContent> = files
+        .iter()
+        .map(|file| parser::parse_markdown_with(&file.content, &options.link_syntax))
+        .collect();
+
+    graph.aliases = build_alias_map(
+        files
+            .iter()
+            .zip(&parsed_files)
+            .map(|(file, parsed)| (file.name.as_str(), parsed.frontmatter.as_ref())),
+    );
+
+    for (file, parsed) in files.iter().zip(&parsed_files) {
         for link in &parsed.wiki_links {
+            let is_note = |name: &str| file_map.contains_key(name);
+            let link = match resolve_relative_link(&file.path, link, &note_paths) {
+                Some(note) => note,
+                None => resolve_link_target(link, is_note, &graph.aliases),
+            };
+
+            if dropped_targets.contains(&link) && !file_map.contains_key(&link) {
+                continue;
+            }
+
+            if !options.keeps_link(&file.name, &link) {
+                continue;
+            }
+
             graph.edges.push(Edge {
                 from: file.name.clone(),
                 to: link.clone(),
@@ -186,22 +602,15 @@ pub fn build_graph(files: Vec<MarkdownFile>) -> GraphData {
 
             *link_counts.entry(link.clone()).or_insert(0) += 1;
 
-            all_referenced_nodes.insert(link.clone(), file_map.contains_key(link));
+            let exists = file_map.contains_key(&link);
+            all_referenced_nodes.insert(link, exists);
         }
     }
 
-    for file in &files {
-        let parsed = parser::parse_markdown(&file.content);
+    for (file, parsed) in files.iter().zip(&parsed_files) {
         let incoming_links = *link_counts.get(&file.name).unwrap_or(&0);
 
-        graph.nodes.push(Node {
-            id: file.name.clone(),
-            label: file.name.clone(),
-            value: incoming_links,
-            group: None,
-            file_path: file.path.to_string_lossy().to_string(),
-            hashtags: parsed.hashtags,
-        });
+        graph.nodes.push(note_node(file, parsed, incoming_links, options));
 
         all_referenced_nodes.insert(file.name.clone(), true);
     }
@@ -210,56 +619,1059 @@ pub fn build_graph(files: Vec<MarkdownFile>) -> GraphData {
         if !file_exists {
             let incoming_links = *link_counts.get(node_name).unwrap_or(&0);
 
-            graph.nodes.push(Node {
-                id: node_name.clone(),
-                label: format!("{}", node_name),
-                value: incoming_links,
-                group: Some("phantom".to_string()),
-                file_path: String::new(),
-                hashtags: Vec::new(),
-            });
+            graph.nodes.push(phantom_node(node_name, incoming_links, options));
         }
     }
 
+    graph.node_size_scale = options.node_size_scale;
+    graph.rescale_nodes();
+
     graph
 }
 
-/// Scans a directory and builds a graph in a single operation.
+/// Builds the node of an existing note.
+///
+/// Besides the label (see `preferred_label`), a note can style itself through
+/// frontmatter:
+/// - `group: important` sets the node's `group`. The reserved `phantom` group
+///   can't be claimed by a real note and is ignored.
+/// - `color: "#f59e0b"` sets the node's `color` directly, taking precedence over
+///   any color configured for its group in `group_colors`.
+///
+/// Without a frontmatter group, a note in a namespace folder may be grouped by
+/// its namespace (see `BuildOptions::namespace_group`).
+///
+/// With `BuildOptions::file_metadata`, the file size and modification time are
+/// read from the file system; they stay None if the file can't be read. With
+/// `BuildOptions::heading_sections`, the note's top-level headings become its
+/// `sections`.
+///
+/// # Arguments
+///
+/// * `file` - The scanned note
+/// * `parsed` - The note's parsed content
+/// * `value` - Number of incoming links, used for node sizing. The node's `size` is
+///   left unscaled until `GraphData::rescale_nodes`.
+/// * `options` - Label formatting, namespace grouping, file metadata and sections to apply
+pub fn note_node(
+    file: &MarkdownFile,
+    parsed: &ParsedContent,
+    value: usize,
+    options: &BuildOptions,
+) -> Node {
+    let frontmatter = parsed.frontmatter.as_ref();
+    let frontmatter_str = |key: &str| {
+        frontmatter
+            .and_then(|frontmatter| frontmatter.get_str(key))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let metadata = options
+        .file_metadata
+        .then(|| fs::metadata(&file.path).ok())
+        .flatten();
+
+    Node {
+        id: file.name.clone(),
+        label: options.label_format.apply(&preferred_label(&file.name, frontmatter)),
+        value,
+        size: value as f64,
+        group: frontmatter_str("group")
+            .filter(|group| group != "phantom")
+            .or_else(|| options.namespace_group(&file.path)),
+        file_path: file.path.to_string_lossy().to_string(),
+        hashtags: parsed.hashtags.clone(),
+        color: frontmatter_str("color"),
+        size_bytes: metadata.as_ref().map(|metadata| metadata.len()),
+        modified: metadata
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|modified| modified.as_millis() as u64),
+        sections: if options.heading_sections {
+            parsed.sections.iter().map(|heading| heading.text.clone()).collect()
+        } else {
+            Vec::new()
+        },
+    }
+}
+
+/// Builds the node of a referenced but non-existent note.
 ///
-/// This convenience function combines directory scanning and graph construction,
-/// providing a simple one-call interface for the most common use case.
+/// Phantom nodes are the nodes with an empty `file_path`. Their group is
+/// `phantom`, or their namespace (see `BuildOptions::phantom_group`).
 ///
 /// # Arguments
 ///
-/// * `path` - Path to the directory containing markdown files
+/// * `node_id` - The link target the phantom node stands for
+/// * `value` - Number of incoming links, used for node sizing. The node's `size` is
+///   left unscaled until `GraphData::rescale_nodes`.
+/// * `options` - Label formatting and namespace grouping to apply
+pub fn phantom_node(node_id: &str, value: usize, options: &BuildOptions) -> Node {
+    Node {
+        id: node_id.to_string(),
+        label: options.label_format.apply(node_id),
+        value,
+        size: value as f64,
+        group: Some(options.phantom_group(node_id)),
+        file_path: String::new(),
+        hashtags: Vec::new(),
+        color: None,
+        size_bytes: None,
+        modified: None,
+        sections: Vec::new(),
+    }
+}
+
+/// Chooses the display label for a file node.
+///
+/// Notes can declare a nicer name than their file stem through frontmatter: a
+/// non-empty `title` wins, otherwise the first non-empty entry of `aliases` is
+/// used. Without either, the label is the node ID itself. Only the label is
+/// affected; the node ID always stays the file stem so wiki-links keep
+/// resolving by file name.
+///
+/// # Arguments
+///
+/// * `node_id` - The node ID (file name without extension)
+/// * `frontmatter` - The note's parsed frontmatter, if any
+pub fn preferred_label(node_id: &str, frontmatter: Option<&Frontmatter>) -> String {
+    let Some(frontmatter) = frontmatter else {
+        return node_id.to_string();
+    };
+
+    let title = frontmatter
+        .get_str("title")
+        .map(str::trim)
+        .filter(|title| !title.is_empty());
+
+    if let Some(title) = title {
+        return title.to_string();
+    }
+
+    frontmatter
+        .get_list("aliases")
+        .iter()
+        .map(|alias| alias.trim())
+        .find(|alias| !alias.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| node_id.to_string())
+}
+
+/// Returns `true` if a note opts out of the graph through its frontmatter.
+///
+/// A note is excluded with `graph: false` or `exclude_from_graph: true`
+/// (values compared case-insensitively), e.g. for private or scratch notes.
+pub fn is_excluded_from_graph(content: &str) -> bool {
+    let Some(frontmatter) = parser::parse_frontmatter(content) else {
+        return false;
+    };
+
+    let flag = |key: &str, value: &str| {
+        frontmatter
+            .get_str(key)
+            .is_some_and(|flag| flag.trim().eq_ignore_ascii_case(value))
+    };
+
+    flag("graph", "false") || flag("exclude_from_graph", "true")
+}
+
+/// Returns the aliases a note declares in its frontmatter `aliases` key.
+///
+/// Entries are trimmed; empty entries are skipped.
+pub fn note_aliases(frontmatter: Option<&Frontmatter>) -> Vec<String> {
+    frontmatter
+        .map(|frontmatter| frontmatter.get_list("aliases"))
+        .unwrap_or_default()
+        .iter()
+        .map(|alias| alias.trim())
+        .filter(|alias| !alias.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Maps every alias declared in the notes' frontmatter to the note declaring it.
+///
+/// Aliases equal to the name of an existing note are left out, since links
+/// resolve to exact file-name matches first. When several notes declare the
+/// same alias, the note whose name sorts first wins, independent of scan
+/// order, and a warning is logged.
+///
+/// # Arguments
+///
+/// * `notes` - Each note's name and parsed frontmatter
 ///
 /// # Returns
 ///
-/// * `Ok(GraphData)` - Successfully constructed graph with all nodes and edges
-/// * `Err(String)` - Error message if directory scanning fails
+/// A map from alias to the name of the note declaring it.
+pub fn build_alias_map<'a>(
+    notes: impl Iterator<Item = (&'a str, Option<&'a Frontmatter>)>,
+) -> BTreeMap<String, String> {
+    let mut notes: Vec<(&str, Vec<String>)> = notes
+        .map(|(name, frontmatter)| (name, note_aliases(frontmatter)))
+        .collect();
+    notes.sort_by(|a, b| a.0.cmp(b.0));
+
+    let names: HashSet<&str> = notes.iter().map(|(name, _)| *name).collect();
+    let mut aliases: BTreeMap<String, String> = BTreeMap::new();
+
+    for (name, note_aliases) in &notes {
+        for alias in note_aliases {
+            if names.contains(alias.as_str()) {
+                continue;
+            }
+
+            match aliases.get(alias) {
+                Some(owner) if owner != name => println!(
+                    "[Graph] Warning: alias '{}' is declared by '{}' and '{}', using '{}'",
+                    alias, owner, name, owner
+                ),
+                Some(_) => {}
+                None => {
+                    aliases.insert(alias.clone(), name.to_string());
+                }
+            }
+        }
+    }
+
+    aliases
+}
+
+/// Resolves a link target to the ID of the note it refers to.
 ///
-/// # Errors
+/// A note whose name equals the target wins; otherwise the note declaring the
+/// target as an alias. Unresolved targets are returned unchanged and become
+/// phantom nodes.
 ///
-/// Returns an error if:
-/// - The specified path doesn't exist or isn't a directory
-/// - File system permissions prevent reading directories or files
-/// - Markdown files contain invalid UTF-8 encoding
+/// # Arguments
 ///
-/// # Examples
+/// * `target` - Link target as extracted by the parser
+/// * `is_note` - Returns `true` if a note with the given name exists
+/// * `aliases` - Alias map built by `build_alias_map`
+pub fn resolve_link_target(
+    target: &str,
+    is_note: impl Fn(&str) -> bool,
+    aliases: &BTreeMap<String, String>,
+) -> String {
+    if is_note(target) {
+        return target.to_string();
+    }
+
+    aliases.get(target).cloned().unwrap_or_else(|| target.to_string())
+}
+
+/// Returns `true` if a link target is a path (`../shared/note`, `sub/note`).
+pub fn is_path_link(target: &str) -> bool {
+    target.contains(['/', '\\'])
+}
+
+/// Lexically normalizes a path, resolving `.` and `..` components.
 ///
-/// ```ignore
-/// // Scan a notes directory and get the complete graph
-/// let graph = scan_and_build_graph("/home/user/notes")?;
-/// println!("Graph has {} nodes and {} edges",
-///          graph.nodes.len(), graph.edges.len());
-/// ```
+/// The file system is not consulted, so symlinks are not followed and the path
+/// doesn't need to exist. A `..` at the start of a relative path is kept.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            _ => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Indexes notes by their normalized file path, for `resolve_relative_link`.
 ///
-/// # Performance
+/// # Arguments
+///
+/// * `notes` - Path and ID of each note
+pub fn note_path_index<'a>(
+    notes: impl IntoIterator<Item = (&'a Path, &'a String)>,
+) -> HashMap<PathBuf, String> {
+    notes
+        .into_iter()
+        .map(|(path, id)| (normalize_path(path), id.clone()))
+        .collect()
+}
+
+/// Resolves a path link relative to the directory of the note containing it.
+///
+/// In multi-folder vaults, `[[../shared/note]]` in `projects/plan.md` refers to
+/// `shared/note.md`, whatever other notes named `note` exist. Both `/` and `\`
+/// separate path segments, and a file with any of the `MARKDOWN_EXTENSIONS` is
+/// found.
+///
+/// # Arguments
+///
+/// * `source` - Path of the note containing the link
+/// * `target` - Link target as extracted by the parser
+/// * `note_paths` - Notes by normalized path, built by `note_path_index`
+///
+/// # Returns
+///
+/// The ID of the note at the resolved path, or None if the target is not a path
+/// (see `is_path_link`) or no note exists there. Such links resolve by name.
+pub fn resolve_relative_link(
+    source: &Path,
+    target: &str,
+    note_paths: &HashMap<PathBuf, String>,
+) -> Option<String> {
+    if !is_path_link(target) {
+        return None;
+    }
+
+    let mut path = source.parent()?.to_path_buf();
+    path.extend(target.split(['/', '\\']).filter(|segment| !segment.is_empty()));
+    let path = normalize_path(&path);
+
+    parser::MARKDOWN_EXTENSIONS.iter().find_map(|extension| {
+        let mut file = path.clone().into_os_string();
+        file.push(".");
+        file.push(extension);
+
+        note_paths.get(&PathBuf::from(file)).cloned()
+    })
+}
+
+/// Color used for phantom nodes when `group_colors` doesn't configure one.
+pub const DEFAULT_PHANTOM_COLOR: &str = "#6b7280";
+
+/// Resolves the display color for a node group.
+///
+/// Colors configured in `group_colors` always win. Phantom nodes fall back to
+/// `DEFAULT_PHANTOM_COLOR`; other groups (and nodes without a group) get no
+/// color, leaving styling to the frontend.
+///
+/// # Arguments
+///
+/// * `group` - The node's group, if any
+/// * `group_colors` - Configured mapping of group names to CSS colors
+pub fn resolve_group_color(
+    group: Option<&str>,
+    group_colors: &HashMap<String, String>,
+) -> Option<String> {
+    let group = group?;
+
+    match group_colors.get(group) {
+        Some(color) => Some(color.clone()),
+        None if group == "phantom" => Some(DEFAULT_PHANTOM_COLOR.to_string()),
+        None => None,
+    }
+}
+
+/// Sets the `color` of every node from its group.
+///
+/// Applied after `build_graph_with` so that graph construction stays independent of
+/// configuration. See `resolve_group_color` for the resolution rules. Nodes that
+/// already have a color (set through frontmatter) keep it.
+///
+/// # Arguments
+///
+/// * `graph` - Graph whose nodes should be colored
+/// * `group_colors` - Configured mapping of group names to CSS colors
+pub fn apply_group_colors(graph: &mut GraphData, group_colors: &HashMap<String, String>) {
+    for node in graph.nodes.iter_mut().filter(|node| node.color.is_none()) {
+        node.color = resolve_group_color(node.group.as_deref(), group_colors);
+    }
+}
+
+/// Tag condition an edge's endpoints must satisfy to be kept by `filter_edges_by_tag`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeTagFilter {
+    /// Keep edges between notes that have at least one hashtag in common
+    SharedTag,
+    /// Keep edges between notes that both carry this hashtag (without the `#`),
+    /// compared case-insensitively
+    Tag(String),
+}
+
+impl EdgeTagFilter {
+    /// Returns `true` if an edge between these two nodes passes the filter.
+    pub fn keeps(&self, from: &Node, to: &Node) -> bool {
+        match self {
+            Self::SharedTag => from.hashtags.iter().any(|tag| to.hashtags.contains(tag)),
+            Self::Tag(tag) => {
+                let key = parser::tag_key(tag);
+                let carries = |node: &Node| node.hashtags.iter().any(|t| parser::tag_key(t) == key);
+
+                carries(from) && carries(to)
+            }
+        }
+    }
+}
+
+/// Returns a copy of a graph with only the edges accepted by a predicate.
+///
+/// The predicate receives the source and target node of each edge. Nodes are
+/// kept unchanged (including their `value`, which still counts all incoming
+/// links), so a filtered graph shows the same notes with fewer connections.
+/// Edges whose endpoints are missing from the graph are dropped.
 ///
-/// Performance is dominated by file I/O and scales linearly with the number and
-/// size of markdown files in the directory tree.
-pub fn scan_and_build_graph(path: &str) -> Result<GraphData, String> {
-    let files = scan_directory(path)?;
-    let graph = build_graph(files);
-    Ok(graph)
+/// # Arguments
+///
+/// * `graph` - Graph to filter, e.g. the result of `build_graph_with`
+/// * `keep` - Returns `true` for edges (by source and target node) to keep
+pub fn filter_edges(graph: &GraphData, keep: impl Fn(&Node, &Node) -> bool) -> GraphData {
+    let nodes: HashMap<&str, &Node> =
+        graph.nodes.iter().map(|node| (node.id.as_str(), node)).collect();
+
+    let edges = graph
+        .edges
+        .iter()
+        .filter(|edge| match (nodes.get(edge.from.as_str()), nodes.get(edge.to.as_str())) {
+            (Some(from), Some(to)) => keep(from, to),
+            _ => false,
+        })
+        .cloned()
+        .collect();
+
+    GraphData {
+        nodes: graph.nodes.clone(),
+        edges,
+        directed: graph.directed,
+        node_size_scale: graph.node_size_scale,
+        aliases: graph.aliases.clone(),
+        truncated: graph.truncated,
+    }
+}
+
+/// Returns a copy of a graph keeping only edges whose endpoints pass a tag filter.
+///
+/// Phantom nodes carry no hashtags, so edges to them are always dropped. See
+/// `filter_edges` for how nodes are treated.
+pub fn filter_edges_by_tag(graph: &GraphData, filter: &EdgeTagFilter) -> GraphData {
+    filter_edges(graph, |from, to| filter.keeps(from, to))
+}
+
+/// How a note's hashtags are matched against a set of tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagMatch {
+    /// The note carries at least one of the tags
+    Any,
+    /// The note carries every one of the tags
+    All,
+}
+
+impl TagMatch {
+    /// Parses a mode name, `any` or `all` (case-insensitive).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message for any other name.
+    pub fn parse(mode: &str) -> Result<Self, String> {
+        match mode.trim().to_lowercase().as_str() {
+            "any" => Ok(Self::Any),
+            "all" => Ok(Self::All),
+            _ => Err(format!("Invalid tag match mode {:?}, expected \"any\" or \"all\"", mode)),
+        }
+    }
+
+    /// Returns `true` if a note with these hashtags matches the tags.
+    ///
+    /// Tags (without the `#`) are compared case-insensitively.
+    pub fn matches(self, tags: &[String], hashtags: &[String]) -> bool {
+        let carries = |tag: &String| {
+            let key = parser::tag_key(tag);
+            hashtags.iter().any(|hashtag| parser::tag_key(hashtag) == key)
+        };
+
+        match self {
+            Self::Any => tags.iter().any(carries),
+            Self::All => tags.iter().all(carries),
+        }
+    }
+}
+
+/// Returns the subgraph induced by the nodes passing a predicate.
+///
+/// Keeps the nodes for which `keep` returns `true` and the edges whose source
+/// and target are both kept; edges leaving the kept set are dropped. Nodes keep
+/// their `value` and `size` from the full graph, so sizes stay comparable
+/// between views.
+///
+/// # Arguments
+///
+/// * `graph` - Graph to take the subgraph of
+/// * `keep` - Returns `true` for nodes to keep
+pub fn induced_subgraph(graph: &GraphData, keep: impl Fn(&Node) -> bool) -> GraphData {
+    let nodes: Vec<Node> = graph.nodes.iter().filter(|node| keep(node)).cloned().collect();
+    let ids: HashSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+
+    let edges = graph
+        .edges
+        .iter()
+        .filter(|edge| ids.contains(edge.from.as_str()) && ids.contains(edge.to.as_str()))
+        .cloned()
+        .collect();
+
+    GraphData {
+        nodes,
+        edges,
+        directed: graph.directed,
+        node_size_scale: graph.node_size_scale,
+        aliases: graph.aliases.clone(),
+        truncated: graph.truncated,
+    }
+}
+
+/// Limits a graph to its `max_nodes` highest-value nodes.
+///
+/// Very large graphs can freeze the frontend renderer. If the graph has more
+/// than `max_nodes` nodes, only the nodes with the highest `value` (incoming
+/// link count) are kept, ties broken by ID, together with the edges between
+/// them (see `induced_subgraph`), and `truncated` is set so the frontend can
+/// warn that it shows part of the graph. Smaller graphs are returned unchanged.
+///
+/// # Arguments
+///
+/// * `graph` - Graph to limit
+/// * `max_nodes` - Maximum number of nodes to keep (see `AppConfig::max_nodes`)
+pub fn truncate_graph(graph: GraphData, max_nodes: usize) -> GraphData {
+    if graph.nodes.len() <= max_nodes {
+        return graph;
+    }
+
+    let mut ranked: Vec<&Node> = graph.nodes.iter().collect();
+    ranked.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.id.cmp(&b.id)));
+
+    let kept: HashSet<&str> = ranked
+        .into_iter()
+        .take(max_nodes)
+        .map(|node| node.id.as_str())
+        .collect();
+
+    let mut truncated = induced_subgraph(&graph, |node| kept.contains(node.id.as_str()));
+    truncated.truncated = true;
+
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::{scan_directory, ScanFilter};
+    use crate::testing::{markdown_file, temp_vault};
+
+    fn build(files: Vec<MarkdownFile>) -> GraphData {
+        build_graph_with(files, &BuildOptions::default())
+    }
+
+    fn node<'a>(graph: &'a GraphData, id: &str) -> &'a Node {
+        graph.nodes.iter().find(|node| node.id == id).unwrap()
+    }
+
+    fn edges(graph: &GraphData) -> Vec<(&str, &str)> {
+        let mut edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn d3_layout_renames_fields() {
+        let graph = build(vec![
+            markdown_file("a", "[[b]] [[p]]"),
+            markdown_file("b", "[[a]]"),
+        ]);
+        let d3 = D3Graph::from(&graph);
+
+        assert_eq!(
+            (d3.nodes.len(), d3.links.len()),
+            (graph.nodes.len(), graph.edges.len())
+        );
+
+        let json = serde_json::to_value(&d3).unwrap();
+        assert!(json["links"][0]["source"].is_string());
+        assert!(json.get("edges").is_none());
+    }
+
+    #[test]
+    fn group_colors() {
+        let mut colors = HashMap::new();
+        colors.insert("important".to_string(), "red".to_string());
+
+        assert_eq!(
+            resolve_group_color(Some("important"), &colors).as_deref(),
+            Some("red")
+        );
+        assert_eq!(
+            resolve_group_color(Some("phantom"), &colors).as_deref(),
+            Some(DEFAULT_PHANTOM_COLOR)
+        );
+        assert_eq!(resolve_group_color(None, &colors), None);
+    }
+
+    #[test]
+    fn label_from_frontmatter() {
+        let graph = build(vec![
+            markdown_file("a", "---\ntitle: Alpha Note\n---\n[[b]]"),
+            markdown_file("b", "---\naliases: [Beta, B]\n---\nx"),
+            markdown_file("c", "plain"),
+        ]);
+
+        assert_eq!(node(&graph, "a").label, "Alpha Note");
+        assert_eq!(node(&graph, "b").label, "Beta");
+        assert_eq!(node(&graph, "c").label, "c");
+    }
+
+    #[test]
+    fn frontmatter_group_and_color() {
+        let mut graph = build(vec![
+            markdown_file("a", "---\ngroup: important\n---\n[[x]]"),
+            markdown_file("b", "---\ngroup: phantom\ncolor: \"#fff\"\n---\n"),
+        ]);
+        let mut colors = HashMap::new();
+        colors.insert("important".to_string(), "#f00".to_string());
+
+        apply_group_colors(&mut graph, &colors);
+
+        assert_eq!(node(&graph, "a").group.as_deref(), Some("important"));
+        assert_eq!(node(&graph, "a").color.as_deref(), Some("#f00"));
+        assert_eq!(node(&graph, "b").group, None);
+        assert_eq!(node(&graph, "b").color.as_deref(), Some("#fff"));
+        assert_eq!(node(&graph, "x").group.as_deref(), Some("phantom"));
+    }
+
+    #[test]
+    fn custom_link_syntax_builds_edges() {
+        let options = BuildOptions {
+            link_syntax: LinkSyntax::new(r"\(\(([^)]+)\)\)").unwrap(),
+            ..Default::default()
+        };
+
+        let graph = build_graph_with(vec![markdown_file("a", "((b)) [[c]]")], &options);
+
+        assert_eq!(edges(&graph), vec![("a", "b")]);
+    }
+
+    #[test]
+    fn label_format() {
+        let format = LabelFormat {
+            max_length: Some(5),
+            strip_date_prefix: true,
+            ..Default::default()
+        };
+        assert_eq!(format.apply("2025-01-31 Standup notes"), "Stand…");
+        assert_eq!(format.apply("2025-01-31"), "2025-…");
+
+        let strip = LabelFormat {
+            strip_date_prefix: true,
+            ..Default::default()
+        };
+        assert_eq!(strip.apply("2025-01-31 Standup"), "Standup");
+
+        let short = LabelFormat {
+            max_length: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(short.apply("abc"), "abc");
+        assert_eq!(short.apply("ab cd"), "ab…");
+
+        let options = BuildOptions {
+            label_format: LabelFormat {
+                max_length: Some(4),
+                strip_date_prefix: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let graph = build_graph_with(
+            vec![markdown_file("2024-05-01 Long name", "[[phantom target]]")],
+            &options,
+        );
+        assert_eq!(node(&graph, "2024-05-01 Long name").label, "Long…");
+        assert_eq!(node(&graph, "phantom target").label, "phan…");
+    }
+
+    #[test]
+    fn edges_filtered_by_tag() {
+        let graph = build(vec![
+            markdown_file("a", "#x #y [[b]] [[c]] [[ghost]]"),
+            markdown_file("b", "#x [[c]]"),
+            markdown_file("c", "#y [[a]]"),
+        ]);
+
+        let shared = filter_edges_by_tag(&graph, &EdgeTagFilter::SharedTag);
+        assert_eq!(edges(&shared), vec![("a", "b"), ("a", "c"), ("c", "a")]);
+        assert_eq!(shared.nodes.len(), graph.nodes.len());
+
+        let x = filter_edges_by_tag(&graph, &EdgeTagFilter::Tag("x".to_string()));
+        assert_eq!(edges(&x), vec![("a", "b")]);
+
+        let none = filter_edges_by_tag(&graph, &EdgeTagFilter::Tag("zzz".to_string()));
+        assert!(none.edges.is_empty());
+    }
+
+    #[test]
+    fn tag_filter_ignores_case() {
+        let graph = build(vec![
+            markdown_file("a", "#todo"),
+            markdown_file("b", "#Todo #other"),
+        ]);
+        let filter = EdgeTagFilter::Tag("TODO".to_string());
+        let with_tag = |tag: &str| Node {
+            hashtags: vec![tag.to_string()],
+            ..graph.nodes[0].clone()
+        };
+
+        assert!(filter.keeps(&with_tag("todo"), &with_tag("Todo")));
+        assert!(!filter.keeps(&with_tag("todo"), &with_tag("other")));
+    }
+
+    #[test]
+    fn stub_notes_become_phantoms() {
+        let options = BuildOptions {
+            min_content_chars: Some(10),
+            ..Default::default()
+        };
+
+        let graph = build_graph_with(
+            vec![
+                markdown_file("big", "A substantial note linking [[stub]] and [[fm]]"),
+                markdown_file("stub", "  tiny  "),
+                markdown_file("fm", "---\ntitle: Long frontmatter title here\n---\n"),
+                markdown_file("lonely", "x"),
+            ],
+            &options,
+        );
+
+        assert!(node(&graph, "big").group.is_none());
+        assert_eq!(node(&graph, "stub").group.as_deref(), Some("phantom"));
+        assert_eq!(node(&graph, "fm").group.as_deref(), Some("phantom"));
+        assert!(graph.nodes.iter().all(|node| node.id != "lonely"));
+        assert_eq!(graph.edges.len(), 2);
+    }
+
+    #[test]
+    fn link_extensions_are_stripped() {
+        let graph = build(vec![
+            markdown_file(
+                "a",
+                "[[note.md]] [[note.markdown]] [[Note.MD]] [[v1.2]] [[.md]]",
+            ),
+            markdown_file("note", "x"),
+        ]);
+
+        let mut ids: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec![".md", "Note", "a", "note", "v1.2"]);
+        assert_eq!(node(&graph, "note").value, 2);
+    }
+
+    #[test]
+    fn self_links() {
+        let files = || {
+            vec![
+                markdown_file("a", "[[a]] [[b]]"),
+                markdown_file("b", "[[a]]"),
+            ]
+        };
+
+        let graph = build(files());
+        assert_eq!(edges(&graph), vec![("a", "b"), ("b", "a")]);
+        assert_eq!(node(&graph, "a").value, 1);
+
+        let options = BuildOptions {
+            allow_self_links: true,
+            ..Default::default()
+        };
+        let graph = build_graph_with(files(), &options);
+        assert_eq!(edges(&graph), vec![("a", "a"), ("a", "b"), ("b", "a")]);
+        assert_eq!(node(&graph, "a").value, 2);
+    }
+
+    #[test]
+    fn namespaces() {
+        let format = LabelFormat {
+            namespaces: vec!["people".to_string(), "proj/".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(format.apply("people/Alice"), "Alice");
+        assert_eq!(format.apply("proj/x/y"), "x/y");
+        assert_eq!(format.apply("people/"), "people/");
+        assert_eq!(format.apply("peoples/Bob"), "peoples/Bob");
+
+        let dir = temp_vault("graph-namespaces");
+        fs::create_dir_all(dir.join("people")).unwrap();
+        fs::create_dir_all(dir.join("proj")).unwrap();
+        fs::write(dir.join("a.md"), "[[people/Alice]] [[people/Bob]] [[ghost]]").unwrap();
+        fs::write(dir.join("people/Bob.md"), "").unwrap();
+        fs::write(dir.join("proj/z.md"), "---\ngroup: work\n---\n").unwrap();
+        let files = || scan_directory(dir.to_str().unwrap(), &ScanFilter::default(), 1).unwrap();
+
+        let options = BuildOptions {
+            label_format: format,
+            namespace_groups: true,
+            roots: vec![dir.clone()],
+            ..Default::default()
+        };
+        let graph = build_graph_with(files(), &options);
+        assert_eq!(node(&graph, "people/Alice").label, "Alice");
+        assert_eq!(node(&graph, "people/Alice").group.as_deref(), Some("people"));
+        assert_eq!(node(&graph, "people/Alice").file_path, "");
+        assert_eq!(node(&graph, "ghost").group.as_deref(), Some("phantom"));
+        assert_eq!(node(&graph, "Bob").label, "Bob");
+        assert_eq!(node(&graph, "Bob").group.as_deref(), Some("people"));
+        assert_eq!(node(&graph, "z").group.as_deref(), Some("work"));
+        assert_eq!(node(&graph, "a").group, None);
+
+        let outside = BuildOptions {
+            roots: vec![dir.join("people")],
+            ..options.clone()
+        };
+        assert_eq!(node(&build_graph_with(files(), &outside), "Bob").group, None);
+
+        let options = BuildOptions {
+            namespace_groups: false,
+            ..options
+        };
+        let graph = build_graph_with(files(), &options);
+        assert_eq!(node(&graph, "Bob").group, None);
+        assert_eq!(node(&graph, "people/Alice").group.as_deref(), Some("phantom"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn node_size_scale() {
+        let counts = [0usize, 1, 3, 8, 99];
+        let scaled =
+            |scale: NodeSizeScale| counts.iter().map(|&c| scale.apply(c)).collect::<Vec<_>>();
+
+        assert_eq!(
+            scaled(NodeSizeScale::Linear),
+            vec![0.0, 1.0, 3.0, 8.0, 99.0]
+        );
+        assert_eq!(
+            scaled(NodeSizeScale::Sqrt),
+            vec![0.0, 1.0, 3f64.sqrt(), 8f64.sqrt(), 99f64.sqrt()]
+        );
+        let log = scaled(NodeSizeScale::Log);
+        assert_eq!(log[0], 0.0);
+        assert!((log[4] - 100f64.ln()).abs() < 1e-12);
+        assert!(log.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let files = vec![
+            markdown_file("a", "[[hub]]"),
+            markdown_file("b", "[[hub]] [[hub]] [[hub]]"),
+            markdown_file("c", ""),
+        ];
+        let options = BuildOptions {
+            node_size_scale: NodeSizeScale::Log,
+            ..Default::default()
+        };
+        let graph = build_graph_with(files.clone(), &options);
+        assert_eq!(node(&graph, "hub").value, 4);
+        assert!((node(&graph, "hub").size - 5f64.ln()).abs() < 1e-12);
+        assert_eq!(node(&graph, "c").size, 0.0);
+        assert_eq!(node(&build(files), "hub").size, 4.0);
+    }
+
+    #[test]
+    fn alias_resolution() {
+        let graph = build(vec![
+            markdown_file("a", "[[Bob]] [[bob-smith]] [[Carol]]"),
+            markdown_file("bob-smith", "---\naliases: [Bob, Robert]\n---\n"),
+            markdown_file("Carol", "---\naliases: [Bob]\n---\n"),
+        ]);
+        assert_eq!(
+            edges(&graph),
+            vec![("a", "Carol"), ("a", "Carol"), ("a", "bob-smith")]
+        );
+        assert_eq!(graph.aliases.get("Bob").map(String::as_str), Some("Carol"));
+        assert!(graph
+            .nodes
+            .iter()
+            .all(|node| node.group.as_deref() != Some("phantom")));
+
+        let graph = build(vec![
+            markdown_file("a", "[[Bob]]"),
+            markdown_file("bob-smith", "---\naliases: [Bob]\n---\n"),
+            markdown_file("Bob", ""),
+        ]);
+        assert_eq!(graph.edges[0].to, "Bob");
+        assert!(graph.aliases.is_empty());
+
+        let graph = build(vec![
+            markdown_file("x", "---\naliases: [X1]\n---\n"),
+            markdown_file("a", "[[X1]]"),
+        ]);
+        assert_eq!(node(&graph, "x").value, 1);
+    }
+
+    #[test]
+    fn empty_link_targets_make_no_nodes() {
+        let graph = build(vec![
+            markdown_file("a", "[[ ]] [[  note  ]]"),
+            markdown_file("note", ""),
+        ]);
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(edges(&graph), vec![("a", "note")]);
+    }
+
+    #[test]
+    fn excluded_by_frontmatter() {
+        let files = vec![
+            markdown_file("a", "[[secret]] [[b]] [[scratch]]"),
+            markdown_file("secret", "---\ngraph: false\n---\n[[b]] #private"),
+            markdown_file("scratch", "---\nexclude_from_graph: True\n---\n"),
+            markdown_file("b", "---\ngraph: true\n---\n"),
+        ];
+
+        let graph = build(files.clone());
+        assert_eq!(node(&graph, "secret").group.as_deref(), Some("phantom"));
+        assert!(node(&graph, "secret").hashtags.is_empty());
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges.len(), 3);
+
+        let options = BuildOptions {
+            drop_excluded_links: true,
+            ..Default::default()
+        };
+        let graph = build_graph_with(files, &options);
+        let mut ids: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(edges(&graph), vec![("a", "b")]);
+    }
+
+    #[test]
+    fn relative_links_resolve_from_the_source_directory() {
+        let file = |path: &str, content: &str| MarkdownFile {
+            path: PathBuf::from(path),
+            content: content.to_string(),
+            name: Path::new(path)
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+        };
+        let graph = build(vec![
+            file("/v/shared/note.md", "shared"),
+            file("/v/projects/sub/deep.md", "deep"),
+            file("/v/other/bare.md", "bare"),
+            file(
+                "/v/projects/plan.md",
+                "[[../shared/note]] [[./sub/deep]] [[sub/deep.md]] [[bare]] [[../missing/x]] \
+                 [[..\\shared\\note#H|s]]",
+            ),
+        ]);
+
+        let mut targets: Vec<&str> = graph
+            .edges
+            .iter()
+            .filter(|e| e.from == "plan")
+            .map(|e| e.to.as_str())
+            .collect();
+        targets.sort();
+        assert_eq!(
+            targets,
+            vec!["../missing/x", "bare", "deep", "deep", "note", "note"]
+        );
+        assert!(node(&graph, "../missing/x").file_path.is_empty());
+    }
+
+    #[test]
+    fn file_metadata_only_on_notes() {
+        let dir = crate::testing::temp_vault("graph-metadata");
+        fs::write(dir.join("a.md"), "hello [[ghost]]").unwrap();
+        let files = vec![MarkdownFile {
+            path: dir.join("a.md"),
+            content: "hello [[ghost]]".to_string(),
+            name: "a".to_string(),
+        }];
+
+        let graph = build(files.clone());
+        assert!(graph
+            .nodes
+            .iter()
+            .all(|n| n.size_bytes.is_none() && n.modified.is_none()));
+
+        let options = BuildOptions {
+            file_metadata: true,
+            ..Default::default()
+        };
+        let graph = build_graph_with(files, &options);
+        let note = node(&graph, "a");
+        assert_eq!(note.size_bytes, Some(15));
+        assert!(note.modified.unwrap() > 1_600_000_000_000);
+        assert!(serde_json::to_value(note)
+            .unwrap()
+            .get("size_bytes")
+            .is_some());
+
+        let ghost = node(&graph, "ghost");
+        assert_eq!((ghost.size_bytes, ghost.modified), (None, None));
+        let json = serde_json::to_value(ghost).unwrap();
+        assert!(json.get("size_bytes").is_none() && json.get("modified").is_none());
+    }
+
+    #[test]
+    fn heading_sections_on_nodes() {
+        let files = vec![
+            markdown_file("a", "intro\n## Setup\n# Main Title\n### Deep\n# Second\n"),
+            markdown_file("b", "[[ghost]]"),
+        ];
+
+        assert!(build(files.clone())
+            .nodes
+            .iter()
+            .all(|node| node.sections.is_empty()));
+
+        let options = BuildOptions {
+            heading_sections: true,
+            ..Default::default()
+        };
+        let graph = build_graph_with(files, &options);
+        assert_eq!(
+            node(&graph, "a").sections,
+            vec!["Setup", "Main Title", "Second"]
+        );
+        assert!(node(&graph, "ghost").sections.is_empty());
+    }
+
+    #[test]
+    fn truncation_keeps_the_highest_value_nodes() {
+        let graph = build(vec![
+            markdown_file("a", "[[hub]] [[b]] [[c]]"),
+            markdown_file("d", "[[hub]] [[b]]"),
+            markdown_file("e", "[[hub]] [[x]]"),
+            markdown_file("hub", "[[b]]"),
+            markdown_file("b", "[[hub]]"),
+            markdown_file("c", ""),
+        ]);
+
+        let small = truncate_graph(graph.clone(), graph.nodes.len());
+        assert!(!small.truncated);
+        assert_eq!(
+            (small.nodes.len(), small.edges.len()),
+            (graph.nodes.len(), graph.edges.len())
+        );
+        assert!(serde_json::to_value(&small)
+            .unwrap()
+            .get("truncated")
+            .is_none());
+
+        let truncated = truncate_graph(graph, 3);
+        assert!(truncated.truncated);
+        let mut ids: Vec<&str> = truncated
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["b", "c", "hub"]);
+        assert_eq!(edges(&truncated), vec![("b", "hub"), ("hub", "b")]);
+        assert_eq!(serde_json::to_value(&truncated).unwrap()["truncated"], true);
+    }
 }
@@ -1,33 +1,67 @@
-use chrono::{Datelike, Local};
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Maximum number of substitution passes performed by `replace_variables_with`.
+///
+/// Each pass may expose new placeholders coming from the values of custom
+/// variables (e.g. a custom variable whose value contains `{{date}}`), so the
+/// replacement is repeated until nothing changes. This limit stops the loop for
+/// self-referential or cyclic custom variables.
+pub const MAX_REPLACEMENT_PASSES: usize = 10;
+
+/// Regex pattern matching a `{{name}}` placeholder; group 1 is the variable name.
+pub const PLACEHOLDER_PATTERN: &str = r"\{\{([^{}]+)\}\}";
 
 /// Replaces template variable placeholders with their current values.
 ///
 /// This function processes a template string and substitutes template variables
-/// with their corresponding values based on the current date and time. The function
-/// uses the system's local timezone for all date calculations.
+/// with their corresponding values based on the current date and time, the title
+/// of the note being created and the custom variables. The function uses the
+/// system's local timezone for all date calculations. Custom values may
+/// themselves contain placeholders (built-in or custom), which are expanded in
+/// subsequent passes.
 ///
 /// # Supported Variables
 ///
 /// * `{{date}}` - Current date in YYYY-MM-DD format (e.g., "2025-11-25")
 /// * `{{week}}` - Current ISO week number as a string (e.g., "47")
+/// * `{{uuid}}` - A random version 4 UUID (e.g., "67e55044-10b1-426f-9247-bb680e5fe0c8")
+/// * `{{title}}` - The title as given (e.g., "My New Note"), if a title is given
+/// * `{{slug}}` - The title as a file-name-safe slug (see `slugify`), if a title is given
+/// * `{{name}}` - The value of the custom variable `name`
+///
+/// A single `{{uuid}}` value is generated per call, so every occurrence in the
+/// template receives the same identifier.
 ///
 /// # Arguments
 ///
-/// * `template` - A string slice containing the template with variable placeholders
+/// * `template` - Template text with variable placeholders
+/// * `title` - Optional title of the note being created; without it, `{{title}}`
+///   and `{{slug}}` are left unchanged
+/// * `custom` - Custom variable names (without braces) mapped to their values
 ///
 /// # Returns
 ///
-/// A new `String` with all recognized template variables replaced by their current values.
-/// Unrecognized placeholders are left unchanged in the output.
+/// The template with all known placeholders expanded. Unknown placeholders are
+/// left unchanged.
+///
+/// # Recursion Limit
+///
+/// Replacement stops as soon as a pass leaves the text unchanged, or after
+/// `MAX_REPLACEMENT_PASSES` passes. For a cyclic definition such as
+/// `a = "{{b}}"`, `b = "{{a}}"` the result therefore still contains one of the
+/// cyclic placeholders instead of looping forever.
 ///
 /// # Examples
 ///
-/// ```
-/// use mdgraph_lib::helpers::replace_variables;
+/// ```ignore
+/// let mut custom = HashMap::new();
+/// custom.insert("created".to_string(), "Created on {{date}}".to_string());
 ///
-/// let template = "Date: {{date}}, Week: {{week}}";
-/// let result = replace_variables(template);
-/// // result: "Date: 2025-11-25, Week: 47" (values depend on current date)
+/// let result = replace_variables_with("{{created}}, week {{week}}", None, &custom);
+/// // result: "Created on 2025-11-25, week 47" (values depend on current date)
 /// ```
 ///
 /// # Variable Details
@@ -42,13 +76,364 @@ use chrono::{Datelike, Local};
 /// - Week 1 is the first week with a Thursday in the new year
 /// - Week numbers range from 1 to 52 or 53
 /// - The week number is returned as a plain number string without leading zeros
-pub fn replace_variables(template: &str) -> String {
+pub fn replace_variables_with(
+    template: &str,
+    title: Option<&str>,
+    custom: &HashMap<String, String>,
+) -> String {
     let now = Local::now();
 
     let date_str = now.format("%Y-%m-%d").to_string();
     let week_num = now.iso_week().week().to_string();
+    let uuid = Uuid::new_v4().to_string();
+
+    let mut result = template.to_string();
+
+    for _ in 0..MAX_REPLACEMENT_PASSES {
+        let mut next = result
+            .replace("{{date}}", &date_str)
+            .replace("{{week}}", &week_num)
+            .replace("{{uuid}}", &uuid);
+
+        if let Some(title) = title {
+            next = next
+                .replace("{{title}}", title)
+                .replace("{{slug}}", &slugify(title));
+        }
+
+        for (name, value) in custom {
+            next = next.replace(&format!("{{{{{}}}}}", name), value);
+        }
+
+        if next == result {
+            break;
+        }
+
+        result = next;
+    }
+
+    result
+}
+
+/// Replaces template variables and reports the placeholders that were left over.
+///
+/// Performs exactly the substitution of `replace_variables_with` and then
+/// collects every `{{name}}` placeholder remaining in the result, so callers can
+/// warn about typos such as `{{dtae}}` instead of silently writing them into a
+/// note. Placeholders left over from cyclic custom variables are reported too.
+///
+/// # Arguments
+///
+/// * `template` - Template text with variable placeholders
+/// * `title` - Optional title of the note being created (see `replace_variables_with`)
+/// * `custom` - Custom variable names (without braces) mapped to their values
+///
+/// # Returns
+///
+/// The processed text and the names of the unreplaced variables (without
+/// braces), sorted and without duplicates.
+///
+/// # Examples
+///
+/// ```ignore
+/// let (text, unknown) = replace_variables_checked("{{date}} {{dtae}}", None, &HashMap::new());
+/// // text: "2025-11-25 {{dtae}}", unknown: ["dtae"]
+/// ```
+pub fn replace_variables_checked(
+    template: &str,
+    title: Option<&str>,
+    custom: &HashMap<String, String>,
+) -> (String, Vec<String>) {
+    let result = replace_variables_with(template, title, custom);
+    let unknown = unreplaced_variables(&result);
+
+    (result, unknown)
+}
+
+/// Returns the names of all `{{name}}` placeholders in a text.
+///
+/// # Returns
+///
+/// Variable names without braces, sorted and without duplicates.
+pub fn unreplaced_variables(text: &str) -> Vec<String> {
+    let re = Regex::new(PLACEHOLDER_PATTERN).unwrap();
+
+    let names: BTreeSet<&str> = re
+        .captures_iter(text)
+        .filter_map(|cap| cap.get(1))
+        .map(|name| name.as_str())
+        .collect();
+
+    names.into_iter().map(str::to_string).collect()
+}
+
+/// Converts a title into a file-name-safe slug.
+///
+/// The title is lowercased, whitespace and dashes become single dashes, and all
+/// other non-alphanumeric characters are removed. Leading and trailing dashes are
+/// trimmed. Unicode letters and digits are kept as they are.
+///
+/// # Arguments
+///
+/// * `title` - Title to convert
+///
+/// # Returns
+///
+/// The slug, e.g. `"hello-world-2025"` for `"Hello, World! 2025"`.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+
+    for c in title.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if (c.is_whitespace() || c == '-') && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Score for every needle character found in the haystack.
+const FUZZY_MATCH_SCORE: i64 = 16;
+
+/// Bonus for a match directly following the previous match.
+const FUZZY_CONSECUTIVE_BONUS: i64 = 16;
+
+/// Bonus for a match at the start of a word (start of the text, after a
+/// non-alphanumeric character, or an uppercase letter following a lowercase one).
+const FUZZY_WORD_START_BONUS: i64 = 24;
+
+/// Penalty per haystack character skipped between two matches.
+const FUZZY_GAP_PENALTY: i64 = 3;
+
+/// Penalty per haystack character before the first match, up to `FUZZY_MAX_LEADING_PENALTY`.
+const FUZZY_LEADING_PENALTY: i64 = 1;
+
+/// Maximum total penalty for characters before the first match.
+const FUZZY_MAX_LEADING_PENALTY: i64 = 10;
+
+/// Scores how well a needle fuzzily matches a haystack, fzf-style.
+///
+/// The needle matches if its characters appear in the haystack in order, not
+/// necessarily next to each other (`grh` matches `graph`). Matching is
+/// case-insensitive. Each needle character is matched at its earliest possible
+/// position, and the score rewards contiguous runs and matches at word starts
+/// while penalizing skipped characters, so exact prefixes rank above scattered
+/// subsequences.
+///
+/// # Arguments
+///
+/// * `needle` - Search query
+/// * `haystack` - Text to match against (e.g. a note name)
+///
+/// # Returns
+///
+/// * `Some(i64)` - Match score; higher is better. An empty needle scores 0.
+/// * `None` - The needle is not a subsequence of the haystack
+///
+/// # Examples
+///
+/// ```ignore
+/// assert!(fuzzy_score("gra", "graph") > fuzzy_score("grh", "graph"));
+/// assert_eq!(fuzzy_score("xyz", "graph"), None);
+/// ```
+pub fn fuzzy_score(needle: &str, haystack: &str) -> Option<i64> {
+    let haystack: Vec<char> = haystack.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for needle_char in needle.chars().flat_map(char::to_lowercase) {
+        let offset = haystack[position..]
+            .iter()
+            .position(|c| c.to_lowercase().eq(std::iter::once(needle_char)))?;
+        let index = position + offset;
+
+        score += FUZZY_MATCH_SCORE;
+
+        match previous_match {
+            Some(previous) if previous + 1 == index => score += FUZZY_CONSECUTIVE_BONUS,
+            Some(previous) => score -= FUZZY_GAP_PENALTY * (index - previous - 1) as i64,
+            None => score -= (FUZZY_LEADING_PENALTY * index as i64).min(FUZZY_MAX_LEADING_PENALTY),
+        }
+
+        if is_word_start(&haystack, index) {
+            score += FUZZY_WORD_START_BONUS;
+        }
+
+        previous_match = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
+
+/// Returns `true` if the character at `index` starts a word.
+fn is_word_start(text: &[char], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|i| text[i]) else {
+        return true;
+    };
+
+    !previous.is_alphanumeric() || (previous.is_lowercase() && text[index].is_uppercase())
+}
+
+/// Computes the Jaccard similarity of two sets.
+///
+/// The similarity is the size of the intersection divided by the size of the
+/// union: 1.0 for identical sets and 0.0 for disjoint ones. Two empty sets have
+/// a similarity of 0.0, so empty notes never count as duplicates of each other.
+pub fn jaccard_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let intersection = a.intersection(b).count();
+    let union = a.len() + b.len() - intersection;
+
+    if union == 0 {
+        return 0.0;
+    }
+
+    intersection as f64 / union as f64
+}
+
+/// Parses one bound of a date range into milliseconds since the Unix epoch.
+///
+/// Accepts an RFC 3339 timestamp (`2025-01-31T18:00:00Z`) or an ISO date
+/// (`2025-01-31`) in local time. A date stands for the start of the day, or for
+/// its last millisecond if `end_of_day` is set, so a date range is inclusive.
+///
+/// # Errors
+///
+/// Returns an error naming the value if it is neither format, or if it lies
+/// before the Unix epoch.
+pub fn parse_range_bound(value: &str, end_of_day: bool) -> Result<u64, String> {
+    let value = value.trim();
+
+    let millis = if let Ok(instant) = DateTime::parse_from_rfc3339(value) {
+        instant.timestamp_millis()
+    } else {
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+            format!("Invalid date {:?}: expected YYYY-MM-DD or an RFC 3339 timestamp", value)
+        })?;
+        let day = if end_of_day { date.succ_opt() } else { Some(date) };
+        let midnight = day
+            .and_then(|day| day.and_hms_opt(0, 0, 0))
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+            .ok_or_else(|| format!("Date out of range: {:?}", value))?;
+
+        midnight.timestamp_millis() - i64::from(end_of_day)
+    };
+
+    u64::try_from(millis).map_err(|_| format!("Date before 1970: {:?}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_custom_variables_expand() {
+        let mut custom = HashMap::new();
+        custom.insert("created".to_string(), "on {{date}}".to_string());
+
+        let result = replace_variables_with("x {{created}} {{unknown}}", None, &custom);
+
+        assert!(result.starts_with("x on 20"), "{}", result);
+        assert!(result.ends_with("{{unknown}}"), "{}", result);
+    }
+
+    #[test]
+    fn self_referential_variables_terminate() {
+        let mut custom = HashMap::new();
+        custom.insert("a".to_string(), "{{b}}".to_string());
+        custom.insert("b".to_string(), "{{a}}".to_string());
+        custom.insert("self".to_string(), "[{{self}}]".to_string());
+
+        let result = replace_variables_with("{{a}} {{self}}", None, &custom);
+
+        assert!(result.contains("{{"), "{}", result);
+    }
+
+    #[test]
+    fn slug_and_uuid_variables() {
+        assert_eq!(
+            slugify("Hello, World!  Again - now"),
+            "hello-world-again-now"
+        );
+        assert_eq!(slugify("  Привет мир?"), "привет-мир");
+
+        let result = replace_variables_with(
+            "{{uuid}}|{{slug}}|{{title}}",
+            Some("My Note"),
+            &HashMap::new(),
+        );
+        let parts: Vec<&str> = result.split('|').collect();
+
+        assert!(Uuid::parse_str(parts[0]).is_ok(), "{}", parts[0]);
+        assert_eq!(parts[0].as_bytes()[14], b'4');
+        assert_eq!(&parts[1..], &["my-note", "My Note"]);
+    }
+
+    #[test]
+    fn unknown_variables_are_reported() {
+        let mut custom = HashMap::new();
+        custom.insert("proj".to_string(), "X {{date}}".to_string());
+
+        let (text, unknown) = replace_variables_checked(
+            "{{proj}} {{unknown}} {{title}} {{unknown}} {{dtae}}",
+            Some("T"),
+            &custom,
+        );
+
+        assert!(text.starts_with("X 20"), "{}", text);
+        assert!(text.contains(" T "), "{}", text);
+        assert!(text.contains("{{unknown}}"), "{}", text);
+        assert_eq!(unknown, vec!["dtae", "unknown"]);
+
+        let (_, none) = replace_variables_checked("{{date}}", None, &custom);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn fuzzy_score_ranks_prefix_over_scattered() {
+        let prefix = fuzzy_score("gra", "graph").unwrap();
+        let scattered = fuzzy_score("grh", "graph").unwrap();
+
+        assert!(prefix > scattered);
+        assert_eq!(fuzzy_score("xyz", "graph"), None);
+        assert_eq!(fuzzy_score("hg", "graph"), None);
+        assert_eq!(fuzzy_score("", "graph"), Some(0));
+        assert!(fuzzy_score("GR", "graph").is_some());
+        assert!(fuzzy_score("gv", "graph-view").unwrap() > fuzzy_score("gv", "gravy").unwrap());
+        assert!(fuzzy_score("gv", "GraphView").unwrap() > fuzzy_score("gv", "gravy").unwrap());
+    }
+
+    #[test]
+    fn jaccard_similarity_of_sets() {
+        let set = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<HashSet<_>>();
+
+        assert_eq!(
+            jaccard_similarity(&set(&["a", "b"]), &set(&["a", "b"])),
+            1.0
+        );
+        assert_eq!(
+            jaccard_similarity(&set(&["a", "b"]), &set(&["b", "c"])),
+            1.0 / 3.0
+        );
+        assert_eq!(jaccard_similarity(&set(&["a"]), &set(&["b"])), 0.0);
+        assert_eq!(jaccard_similarity(&set(&[]), &set(&[])), 0.0);
+    }
+
+    #[test]
+    fn range_bounds() {
+        let start = parse_range_bound("2025-01-05", false).unwrap();
+        let end = parse_range_bound("2025-01-05", true).unwrap();
 
-    template
-        .replace("{{date}}", &date_str)
-        .replace("{{week}}", &week_num)
+        assert_eq!(end - start, 24 * 3_600_000 - 1);
+        assert_eq!(
+            parse_range_bound("1970-01-01T00:00:01Z", false).unwrap(),
+            1000
+        );
+        assert!(parse_range_bound("2025-13-01", false).is_err());
+        assert!(parse_range_bound("yesterday", false).is_err());
+        assert!(parse_range_bound("1960-01-01T00:00:00Z", false).is_err());
+    }
 }
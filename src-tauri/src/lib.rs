@@ -14,6 +14,10 @@
 //! - `config`: Configuration management with CLI and JSON file support
 //! - `helpers`: Template variable replacement utilities
 //! - `templates`: Template loading and file creation from templates
+//! - `cache`: In-memory cache of the last built graph for fast queries
+//! - `events`: Events emitted from the backend to the frontend
+//! - `undo`: Log of reversible note modifications for undo
+//! - `testing`: Fixtures shared by the unit tests (test builds only)
 
 mod scanner;
 mod parser;
@@ -22,9 +26,26 @@ mod commands;
 mod config;
 mod helpers;
 mod templates;
+mod cache;
+mod events;
+mod undo;
+#[cfg(test)]
+mod testing;
 
 use commands::{
-    create_phantom_node, get_config, open_file, scan_folder,
+    append_to_note, check_links, complete_link, count_links, create_phantom_node,
+    diff_since_snapshot, export_backlinks_report, find_heading, find_notes_by_section,
+    find_similar_notes, get_adjacency_matrix, get_all_tags, get_app_info, get_cached_graph,
+    get_case_mismatch_links, get_config, get_config_schema, get_config_source, get_edges_for,
+    get_frontmatter, get_graph_d3, get_graph_filtered_by_tag, get_graph_for_tags, get_hubs,
+    get_link_counts, get_link_positions, get_neighbors, get_note_outline, get_note_path,
+    get_notes_by_tag, get_notes_in_range, get_notes_with_broken_links, get_outgoing_links,
+    get_pagerank, get_phantom_clusters, get_recent_notes, get_sinks, get_tag_clusters,
+    get_tag_subgraph, get_tag_tree, get_unlinked_mentions, get_vault_extensions, health_check,
+    import_notes, inspect_note, list_notes, open_file, organize_by_tag, parse_content, read_note,
+    read_note_by_path, read_note_with_context, read_notes, reconcile, rename_note, rename_tag,
+    resolve_link, reveal_in_file_manager, scan_folder, search_notes, set_config, snapshot_graph,
+    stream_graph, undo_last,
 };
 use config::{load_config, AppState};
 use tauri::Manager;
@@ -34,8 +55,13 @@ use tauri::Manager;
 /// This function performs the following initialization steps:
 /// 1. Configures the main window with transparency and acrylic effects (Windows only)
 /// 2. Loads application configuration from CLI arguments or JSON files
-/// 3. Registers command handlers for frontend-backend communication
-/// 4. Starts the Tauri runtime
+/// 3. Starts restoring the persisted graph cache (or scanning `root_dir` if it is
+///    stale) on a background thread, which emits `graph-ready` when done
+/// 4. Starts the periodic rescan if `rescan_interval_secs` is configured, the
+///    template watch if `watch_template` is set, and the file watcher of the
+///    configured roots, which applies edited notes as `graph-delta` events
+/// 5. Registers command handlers for frontend-backend communication
+/// 6. Starts the Tauri runtime, stopping the periodic rescan and both watches on exit
 ///
 /// # Platform-Specific Behavior
 ///
@@ -61,6 +87,66 @@ use tauri::Manager;
 /// - `get_config`: Retrieves the current application configuration
 /// - `open_file`: Opens a file in the nvim editor
 /// - `create_phantom_node`: Creates a markdown file from a phantom node using a template
+/// - `get_hubs`: Returns the most-connected notes from the graph cache
+/// - `get_sinks`: Returns notes that are linked to but never link out
+/// - `check_links`: Reports wiki-links that resolve to no existing note
+/// - `append_to_note`: Appends a line of text to a note (journaling)
+/// - `get_config_source`: Reports which configuration file was loaded at startup
+/// - `read_note`: Reads a note for preview, skipping `previewer.offset` lines
+/// - `get_edges_for`: Returns a node's outgoing and incoming edges
+/// - `get_graph_d3`: Returns the cached graph in D3 `nodes`/`links` format
+/// - `rename_tag`: Renames a hashtag in every note and updates the cache
+/// - `get_notes_by_tag`: Returns the notes carrying a tag, optionally including child tags
+/// - `read_note_with_context`: Reads a note for preview with snippets of the notes linking to it
+/// - `get_cached_graph`: Returns the cached graph restored or built at startup
+/// - `snapshot_graph`: Remembers the current graph for later diffs
+/// - `diff_since_snapshot`: Reports graph changes since the last snapshot
+/// - `get_recent_notes`: Lists the most recently modified notes
+/// - `get_note_path`: Returns the absolute file path of a note
+/// - `reveal_in_file_manager`: Shows a note's file in the system file manager
+/// - `get_neighbors`: Returns a node's neighbors, following links both ways if `undirected` is set
+/// - `parse_content`: Parses unsaved markdown content for live previews
+/// - `get_adjacency_matrix`: Returns the cached graph as a sparse adjacency matrix
+/// - `count_links`: Counts how many times one note links to another
+/// - `get_tag_tree`: Returns the hashtags as a tree of hierarchical tags with note counts
+/// - `health_check`: Reports configuration problems and graph cache size for diagnostics
+/// - `import_notes`: Creates a batch of notes, skipping names that already exist
+/// - `search_notes`: Fuzzy-searches notes by name, ranking contiguous and word-start matches first
+/// - `find_similar_notes`: Reports pairs of notes with near-duplicate content
+/// - `get_graph_filtered_by_tag`: Returns the graph keeping only edges between notes sharing a tag
+/// - `undo_last`: Reverts the most recent note modification
+/// - `get_outgoing_links`: Lists a note's link targets, flagging the ones that don't exist
+/// - `stream_graph`: Emits the cached graph in chunks via graph-begin/graph-chunk/graph-end events
+/// - `export_backlinks_report`: Writes a markdown report of each note's backlinks
+/// - `get_config_schema`: Describes each configuration field for a settings form
+/// - `set_config`: Validates, applies and saves a new configuration
+/// - `complete_link`: Suggests note names for wiki-link autocompletion
+/// - `get_pagerank`: Ranks notes by PageRank importance
+/// - `read_note_by_path`: Reads a note by file path for preview
+/// - `get_vault_extensions`: Counts vault files by extension
+/// - `reconcile`: Rescans and emits the changes as a graph delta
+/// - `get_app_info`: Returns version and build information
+/// - `get_link_counts`: Returns a node's incoming and outgoing link counts
+/// - `get_notes_with_broken_links`: Lists the notes containing broken links
+/// - `inspect_note`: Reads a note fresh from disk and returns its links, tags and frontmatter
+/// - `get_tag_clusters`: Groups notes into clusters by their dominant hashtag
+/// - `organize_by_tag`: Moves every note carrying a hashtag into a folder
+/// - `get_graph_for_tags`: Returns the notes matching any or all of a set of tags with their links
+/// - `get_case_mismatch_links`: Reports broken links that match an existing note ignoring case
+/// - `get_note_outline`: Returns the headings of a note
+/// - `find_heading`: Searches the headings of every note
+/// - `list_notes`: Lists the IDs of all notes
+/// - `get_notes_in_range`: Lists the notes modified within a date range
+/// - `get_tag_subgraph`: Returns the notes carrying a hashtag and the links among them
+/// - `get_frontmatter`: Returns a note's frontmatter as JSON without reading its body
+/// - `find_notes_by_section`: Lists the notes with an H1/H2 section matching a query
+/// - `resolve_link`: Resolves a raw wiki-link to the ID of the node it points to
+/// - `read_notes`: Reads several notes for preview, listing the ones that can't be read
+/// - `get_unlinked_mentions`: Lists the lines mentioning a note in plain text without linking to it
+/// - `get_phantom_clusters`: Returns the connected components consisting only of phantom nodes
+/// - `rename_note`: Renames a note and rewrites the links to it
+/// - `get_link_positions`: Returns the source positions of a note's wiki-links and hashtags
+/// - `get_all_tags`: Lists every hashtag used in the vault
 ///
 /// # Panics
 ///
@@ -90,13 +176,26 @@ pub fn run() {
                 }
             }
 
-            let config = load_config().unwrap_or_else(|e| {
+            let (config, config_source) = load_config().unwrap_or_else(|e| {
                 eprintln!("[Error] Failed to load configuration: {}", e);
                 eprintln!("[Info] Using empty configuration");
-                config::AppConfig::default()
+                (config::AppConfig::default(), config::ConfigSource::default())
             });
 
-            app.manage(AppState::new(config));
+            let cache_file = app
+                .path()
+                .app_data_dir()
+                .ok()
+                .map(|dir| dir.join(cache::CACHE_FILE_NAME));
+
+            let state = AppState::new(config, config_source).with_cache_file(cache_file);
+
+            state.restore_cache_in_background(app.handle().clone());
+            state.start_periodic_rescan(app.handle().clone());
+            state.start_template_watch(app.handle().clone());
+            state.start_vault_watch(app.handle().clone());
+
+            app.manage(state);
 
             Ok(())
         })
@@ -104,8 +203,76 @@ pub fn run() {
             scan_folder,
             get_config,
             open_file,
-            create_phantom_node
+            create_phantom_node,
+            get_hubs,
+            get_sinks,
+            check_links,
+            append_to_note,
+            get_config_source,
+            read_note,
+            get_edges_for,
+            get_graph_d3,
+            rename_tag,
+            get_notes_by_tag,
+            read_note_with_context,
+            get_cached_graph,
+            snapshot_graph,
+            diff_since_snapshot,
+            get_recent_notes,
+            get_note_path,
+            reveal_in_file_manager,
+            get_neighbors,
+            parse_content,
+            get_adjacency_matrix,
+            count_links,
+            get_tag_tree,
+            health_check,
+            import_notes,
+            search_notes,
+            find_similar_notes,
+            get_graph_filtered_by_tag,
+            undo_last,
+            get_outgoing_links,
+            stream_graph,
+            export_backlinks_report,
+            get_config_schema,
+            set_config,
+            complete_link,
+            get_pagerank,
+            read_note_by_path,
+            get_vault_extensions,
+            reconcile,
+            get_app_info,
+            get_link_counts,
+            get_notes_with_broken_links,
+            inspect_note,
+            get_tag_clusters,
+            organize_by_tag,
+            get_graph_for_tags,
+            get_case_mismatch_links,
+            get_note_outline,
+            find_heading,
+            list_notes,
+            get_notes_in_range,
+            get_tag_subgraph,
+            get_frontmatter,
+            find_notes_by_section,
+            resolve_link,
+            read_notes,
+            get_unlinked_mentions,
+            get_phantom_clusters,
+            rename_note,
+            get_link_positions,
+            get_all_tags
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                state.stop_periodic_rescan();
+                state.stop_template_watch();
+                state.stop_vault_watch();
+            }
+        });
 }
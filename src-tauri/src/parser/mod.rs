@@ -7,7 +7,15 @@
 //! # Supported Patterns
 //!
 //! - Wiki-links: `[[title]]` - Double square brackets for internal links
-//! - Hashtags: `#tag` - Hash symbol followed by word characters
+//! - Hashtags: `#tag` - Hash symbol followed by word characters, optionally
+//!   hierarchical with `/` separators (`#project/frontend`)
+//! - Frontmatter: a leading `---` ... `---` block with simple `key: value` pairs
+//!
+//! # Frontmatter Support
+//!
+//! Only a small, line-based subset of YAML is understood: scalar values,
+//! inline arrays (`key: [a, b]`) and block sequences (`key:` followed by
+//! `- item` lines). Nested mappings and multi-line strings are ignored.
 //!
 //! # Performance
 //!
@@ -17,6 +25,28 @@
 //! as a bottleneck.
 
 use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::Range;
+
+/// Regex pattern matching a wiki-link; capture group 1 is the link target.
+///
+/// This is the default link syntax; see `LinkSyntax` for custom delimiters.
+pub const WIKI_LINK_PATTERN: &str = r"\[\[([^\]]+)\]\]";
+
+/// Regex pattern matching a hashtag; capture group 1 is the tag name.
+///
+/// Hierarchical tags (`#project/frontend`) are matched as a single tag.
+const HASHTAG_PATTERN: &str = r"#(\w+(?:/\w+)*)";
+
+/// Regex pattern matching a complete, valid tag name (without the `#`).
+const TAG_NAME_PATTERN: &str = r"^\w+(?:/\w+)*$";
+
+/// Regex pattern matching a URL or the destination of a markdown link.
+///
+/// A `#` inside these is a fragment (`https://example.com/page#setup`,
+/// `[text](page#setup)`), not a hashtag.
+const URL_PATTERN: &str = r"[a-zA-Z][a-zA-Z0-9+.-]*://\S+|\]\([^)]*\)";
 
 /// Result of parsing a markdown file.
 ///
@@ -27,25 +57,203 @@ use regex::Regex;
 /// # Fields
 ///
 /// * `wiki_links` - List of wiki-link targets without brackets (e.g., ["note1", "note2"])
-/// * `hashtags` - List of hashtag names without the hash symbol (e.g., ["tag1", "tag2"])
+/// * `hashtags` - List of hashtag names without the hash symbol (e.g., ["tag1", "tag2"]),
+///   including tags declared in the frontmatter `tags:` key
+/// * `frontmatter` - Parsed frontmatter block, if the content starts with one
+/// * `sections` - Top-level headings (see `extract_sections`) in document order
 #[derive(Debug, Clone)]
 pub struct ParsedContent {
     pub wiki_links: Vec<String>,
     pub hashtags: Vec<String>,
+    pub frontmatter: Option<Frontmatter>,
+    pub sections: Vec<Heading>,
+}
+
+impl ParsedContent {
+    /// Returns the text of the first H1 heading, the note's section title.
+    pub fn section_title(&self) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|heading| heading.level == 1)
+            .map(|heading| heading.text.as_str())
+    }
+}
+
+/// Serializable form of `ParsedContent` returned to the frontend.
+///
+/// # Fields
+///
+/// * `wiki_links` - Wiki-link targets in document order
+/// * `hashtags` - Inline and frontmatter tags without the `#`
+/// * `frontmatter` - Frontmatter key/value pairs, or null without a frontmatter block
+/// * `sections` - Top-level (H1 and H2) headings in document order
+/// * `section_title` - Text of the first H1 heading, or null without one
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedContentDto {
+    pub wiki_links: Vec<String>,
+    pub hashtags: Vec<String>,
+    pub frontmatter: Option<BTreeMap<String, FrontmatterValue>>,
+    pub sections: Vec<Heading>,
+    pub section_title: Option<String>,
+}
+
+impl From<ParsedContent> for ParsedContentDto {
+    fn from(parsed: ParsedContent) -> Self {
+        let section_title = parsed.section_title().map(str::to_string);
+
+        Self {
+            wiki_links: parsed.wiki_links,
+            hashtags: parsed.hashtags,
+            frontmatter: parsed.frontmatter.map(|frontmatter| frontmatter.fields),
+            sections: parsed.sections,
+            section_title,
+        }
+    }
+}
+
+/// A single frontmatter value.
+///
+/// Serialized untagged, so scalars become JSON strings and lists become JSON arrays.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum FrontmatterValue {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+/// Parsed frontmatter key/value pairs.
+///
+/// Keys are kept in sorted order for deterministic output.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Frontmatter {
+    pub fields: BTreeMap<String, FrontmatterValue>,
+}
+
+impl Frontmatter {
+    /// Returns the scalar value of a key, if present and not a list.
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        match self.fields.get(key) {
+            Some(FrontmatterValue::Scalar(value)) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns the values of a key as a list.
+    ///
+    /// A scalar value is returned as a single-element list, and a missing key
+    /// as an empty list.
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        match self.fields.get(key) {
+            Some(FrontmatterValue::Scalar(value)) => vec![value.clone()],
+            Some(FrontmatterValue::List(values)) => values.clone(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// File extensions stripped from link targets by `normalize_link_target`.
+pub const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// Compiled wiki-link syntax used to extract links from note content.
+///
+/// Defaults to the `[[target]]` syntax. Vaults that use other delimiters (e.g.
+/// `((target))`) can supply their own regex through `LinkSyntax::new`; the first
+/// capture group of each match is taken as the link target.
+///
+/// Links inside blockquote lines (quoted or cited text) are followed unless the
+/// syntax is built with `skipping_blockquotes`.
+#[derive(Debug, Clone)]
+pub struct LinkSyntax {
+    regex: Regex,
+    skip_blockquotes: bool,
+}
+
+impl Default for LinkSyntax {
+    /// Returns the standard `[[target]]` wiki-link syntax.
+    fn default() -> Self {
+        Self {
+            regex: Regex::new(WIKI_LINK_PATTERN).unwrap(),
+            skip_blockquotes: false,
+        }
+    }
+}
+
+impl LinkSyntax {
+    /// Compiles a custom wiki-link pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Regex matching a whole link, with capture group 1 as the target
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the pattern is not a valid regex or has no
+    /// capture group.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| format!("Invalid wiki_link_pattern {:?}: {}", pattern, e))?;
+
+        if regex.captures_len() < 2 {
+            return Err(format!(
+                "Invalid wiki_link_pattern {:?}: must contain a capture group for the link target",
+                pattern
+            ));
+        }
+
+        Ok(Self {
+            regex,
+            skip_blockquotes: false,
+        })
+    }
+
+    /// Sets whether links on blockquote lines (starting with `>`) are ignored.
+    pub fn skipping_blockquotes(mut self, skip: bool) -> Self {
+        self.skip_blockquotes = skip;
+        self
+    }
+
+    /// Returns the link targets found in `text`, in order of appearance.
+    ///
+    /// With `skipping_blockquotes`, lines that start with `>` (after optional
+    /// indentation) are left out.
+    pub fn targets(&self, text: &str) -> Vec<String> {
+        if !self.skip_blockquotes {
+            return extract_wiki_links(text, &self.regex);
+        }
+
+        text.lines()
+            .filter(|line| !is_blockquote_line(line))
+            .flat_map(|line| extract_wiki_links(line, &self.regex))
+            .collect()
+    }
+
+    /// Returns the byte ranges of the whole links (delimiters included) in `text`.
+    pub fn link_spans(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex.find_iter(text).map(|link| link.range()).collect()
+    }
 }
 
 /// Parses markdown content and extracts all wiki-links and hashtags.
 ///
+/// Uses the default `[[target]]` link syntax; see `parse_markdown_with` for
+/// custom syntaxes.
+///
 /// This is the main entry point for markdown parsing. It delegates to specialized
 /// extraction functions for each pattern type and combines the results.
 ///
+/// Inline hashtags are only extracted from the note body, so YAML comments and
+/// values inside the frontmatter block are not mistaken for tags. Tags declared
+/// in the frontmatter `tags:` key are appended after the inline tags, skipping
+/// any tag that already appears inline.
+///
 /// # Arguments
 ///
 /// * `content` - Complete markdown file content as a string
 ///
 /// # Returns
 ///
-/// A `ParsedContent` structure containing vectors of all found wiki-links and hashtags.
+/// A `ParsedContent` structure containing vectors of all found wiki-links and hashtags,
+/// plus the note's top-level headings (`sections`).
 ///
 /// # Performance
 ///
@@ -61,13 +269,278 @@ pub struct ParsedContent {
 /// assert_eq!(parsed.hashtags, vec!["tag"]);
 /// ```
 pub fn parse_markdown(content: &str) -> ParsedContent {
-    let wiki_links = extract_wiki_links(content);
-    let hashtags = extract_hashtags(content);
+    parse_markdown_with(content, &LinkSyntax::default())
+}
+
+/// Parses markdown content using a custom wiki-link syntax.
+///
+/// Behaves exactly like `parse_markdown`, except that wiki-links are extracted
+/// with the given `LinkSyntax`.
+///
+/// # Arguments
+///
+/// * `content` - Complete markdown file content as a string
+/// * `syntax` - Wiki-link syntax to extract links with
+pub fn parse_markdown_with(content: &str, syntax: &LinkSyntax) -> ParsedContent {
+    let (_, body) = split_frontmatter(content);
+
+    let wiki_links = syntax.targets(content);
+    let mut hashtags = extract_hashtags(body);
+    let frontmatter = parse_frontmatter(content);
+
+    if let Some(frontmatter) = &frontmatter {
+        for tag in frontmatter.get_list("tags") {
+            let tag = tag.trim_start_matches('#').to_string();
+
+            if !tag.is_empty() && !hashtags.contains(&tag) {
+                hashtags.push(tag);
+            }
+        }
+    }
 
     ParsedContent {
         wiki_links,
         hashtags,
+        frontmatter,
+        sections: extract_sections(content),
+    }
+}
+
+/// A wiki-link or hashtag match together with its location in the content.
+///
+/// # Fields
+///
+/// * `text` - Matched value without syntax (link target as in `link_target`, or tag name)
+/// * `start` - Byte offset of the start of the whole match (e.g. the first `[`)
+/// * `end` - Byte offset just past the end of the whole match
+/// * `line` - 1-based line number of the match start
+/// * `column` - 1-based column of the match start, counted in characters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocatedMatch {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Wiki-links and hashtags of a document with their source positions.
+///
+/// Positions-aware counterpart of `ParsedContent`, intended for editor
+/// integrations such as click-to-source.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedPositions {
+    pub wiki_links: Vec<LocatedMatch>,
+    pub hashtags: Vec<LocatedMatch>,
+}
+
+/// Parses markdown content and returns every wiki-link and hashtag with its position.
+///
+/// Finds the same matches as `parse_markdown_with` with the same `syntax`: links
+/// on blockquote lines are left out if the syntax skips them, and inline hashtags
+/// are only looked for in the body after the frontmatter. Tags declared in the
+/// frontmatter `tags:` key have no inline position and are therefore not
+/// included. Offsets are always relative to the full `content`, including any
+/// frontmatter.
+///
+/// # Arguments
+///
+/// * `content` - Complete markdown file content
+/// * `syntax` - Wiki-link syntax to extract links with
+///
+/// # Returns
+///
+/// A `ParsedPositions` with matches in document order.
+///
+/// # Examples
+///
+/// ```ignore
+/// let parsed = parse_markdown_with_positions("# Title\nSee [[note]]", &LinkSyntax::default());
+/// assert_eq!(parsed.wiki_links[0].line, 2);
+/// assert_eq!(parsed.wiki_links[0].column, 5);
+/// ```
+pub fn parse_markdown_with_positions(content: &str, syntax: &LinkSyntax) -> ParsedPositions {
+    let (_, body) = split_frontmatter(content);
+    let body_offset = content.len() - body.len();
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+        .collect();
+
+    let line_of = |start: usize| line_starts.partition_point(|&line_start| line_start <= start) - 1;
+
+    let locate = |re: &Regex, text: &str, offset: usize, skip_blockquotes: bool| {
+        re.captures_iter(text)
+            .filter_map(|cap| {
+                let whole = cap.get(0)?;
+                let start = offset + whole.start();
+                let line_index = line_of(start);
+                let line_start = line_starts[line_index];
+
+                if skip_blockquotes
+                    && is_blockquote_line(content[line_start..].lines().next().unwrap_or(""))
+                {
+                    return None;
+                }
+
+                Some(LocatedMatch {
+                    text: cap.get(1)?.as_str().to_string(),
+                    start,
+                    end: offset + whole.end(),
+                    line: line_index + 1,
+                    column: content[line_start..start].chars().count() + 1,
+                })
+            })
+            .collect::<Vec<LocatedMatch>>()
+    };
+
+    let mut wiki_links = locate(&syntax.regex, content, 0, syntax.skip_blockquotes);
+
+    for link in &mut wiki_links {
+        link.text = link_target(&link.text).to_string();
+    }
+
+    wiki_links.retain(|link| !link.text.is_empty());
+
+    ParsedPositions {
+        wiki_links,
+        hashtags: locate(&Regex::new(HASHTAG_PATTERN).unwrap(), body, body_offset, false),
+    }
+}
+
+/// Splits markdown content into its frontmatter block and body.
+///
+/// A frontmatter block must start on the very first line with `---` and ends
+/// at the next line consisting of `---` (or `...`). If the opening or closing
+/// delimiter is missing, the whole content is treated as body.
+///
+/// # Arguments
+///
+/// * `content` - Complete markdown file content
+///
+/// # Returns
+///
+/// A tuple of the raw frontmatter text between the delimiters (if any) and the
+/// remaining body after the closing delimiter.
+pub fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let rest = match content
+        .strip_prefix("---\r\n")
+        .or_else(|| content.strip_prefix("---\n"))
+    {
+        Some(rest) => rest,
+        None => return (None, content),
+    };
+
+    let mut offset = 0;
+
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+
+        if trimmed == "---" || trimmed == "..." {
+            let block = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+            return (Some(block), body);
+        }
+
+        offset += line.len();
+    }
+
+    (None, content)
+}
+
+/// Parses the frontmatter block at the start of markdown content.
+///
+/// Understands `key: value` scalars, inline arrays (`key: [a, b]`) and block
+/// sequences (`key:` followed by indented `- item` lines). Surrounding single
+/// or double quotes are removed from values. Comments, blank lines and lines
+/// that don't fit these forms are ignored.
+///
+/// # Arguments
+///
+/// * `content` - Complete markdown file content
+///
+/// # Returns
+///
+/// * `Some(Frontmatter)` - The content starts with a closed frontmatter block
+/// * `None` - The content has no frontmatter
+///
+/// # Examples
+///
+/// ```ignore
+/// let content = "---\ntags: [a, b]\ntitle: Note\n---\nBody";
+/// let frontmatter = parse_frontmatter(content).unwrap();
+/// assert_eq!(frontmatter.get_list("tags"), vec!["a", "b"]);
+/// assert_eq!(frontmatter.get_str("title"), Some("Note"));
+/// ```
+pub fn parse_frontmatter(content: &str) -> Option<Frontmatter> {
+    let (block, _) = split_frontmatter(content);
+    let block = block?;
+
+    let mut frontmatter = Frontmatter::default();
+    let mut current_list: Option<String> = None;
+
+    for line in block.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if trimmed == "-" || trimmed.starts_with("- ") {
+            if let Some(key) = &current_list {
+                if let Some(FrontmatterValue::List(items)) = frontmatter.fields.get_mut(key) {
+                    let item = unquote(trimmed[1..].trim());
+                    if !item.is_empty() {
+                        items.push(item);
+                    }
+                }
+            }
+            continue;
+        }
+
+        current_list = None;
+
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        let key = key.trim().to_string();
+        let value = value.trim();
+
+        if value.is_empty() {
+            frontmatter
+                .fields
+                .insert(key.clone(), FrontmatterValue::List(Vec::new()));
+            current_list = Some(key);
+        } else if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            let items = inner
+                .split(',')
+                .map(|item| unquote(item.trim()))
+                .filter(|item| !item.is_empty())
+                .collect();
+            frontmatter.fields.insert(key, FrontmatterValue::List(items));
+        } else {
+            frontmatter
+                .fields
+                .insert(key, FrontmatterValue::Scalar(unquote(value)));
+        }
     }
+
+    Some(frontmatter)
+}
+
+/// Removes one pair of matching surrounding single or double quotes.
+fn unquote(value: &str) -> String {
+    let unquoted = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')));
+
+    unquoted.unwrap_or(value).to_string()
 }
 
 /// Extracts all wiki-links from markdown content.
@@ -79,6 +552,7 @@ pub fn parse_markdown(content: &str) -> ParsedContent {
 /// # Arguments
 ///
 /// * `content` - Markdown content to search for wiki-links
+/// * `re` - Compiled wiki-link regex (capture group 1 is the target)
 ///
 /// # Returns
 ///
@@ -90,23 +564,108 @@ pub fn parse_markdown(content: &str) -> ParsedContent {
 /// - Matches: `[[text]]`, `[[multi word text]]`, `[[text-with-dashes]]`
 /// - Does not match: `[single bracket]`, `[[nested [[brackets]]]]` (inner brackets)
 ///
-/// # Panics
+/// These apply to the default pattern; custom `LinkSyntax` patterns match
+/// whatever their regex describes.
 ///
-/// Panics if the regex pattern fails to compile, which should never happen with
-/// a valid hard-coded pattern.
-fn extract_wiki_links(content: &str) -> Vec<String> {
-    let re = Regex::new(r"\[\[([^\]]+)\]\]").unwrap();
-
+/// The target of each link is taken from the captured text with `link_target`,
+/// so `[[ note ]]`, `[[note.md]]`, `[[note#Setup]]` and `[[note|Shown]]` all
+/// resolve to the same node as `[[note]]`. Links whose target is empty (`[[ ]]`,
+/// `[[|alias]]`, or `[[#Setup]]` pointing into the same note) are skipped.
+fn extract_wiki_links(content: &str, re: &Regex) -> Vec<String> {
     re.captures_iter(content)
-        .map(|cap| cap[1].to_string())
+        .filter_map(|cap| cap.get(1))
+        .map(|inner| link_target(inner.as_str()))
+        .filter(|target| !target.is_empty())
+        .map(str::to_string)
         .collect()
 }
 
+/// Extracts the target note name from the text inside a wiki-link.
+///
+/// The alias is split off first (see `split_link_alias`), so table pipes around
+/// a link never affect its target. A heading or block reference after `#`
+/// (`note#Setup`, `note#^block`) is removed, then the target is trimmed and a
+/// markdown extension stripped with `normalize_link_target`.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(link_target(" note.md#Setup|Shown "), "note");
+/// assert_eq!(link_target("#Setup"), "");
+/// ```
+pub fn link_target(inner: &str) -> &str {
+    let (target, _) = split_link_alias(inner);
+    let target = target.split_once('#').map_or(target, |(note, _)| note);
+
+    normalize_link_target(target.trim())
+}
+
+/// Returns `true` if a line belongs to a blockquote (starts with `>`).
+fn is_blockquote_line(line: &str) -> bool {
+    line.trim_start().starts_with('>')
+}
+
+/// Splits the text inside a wiki-link into its target and display alias.
+///
+/// `[[note|Shown text]]` links to `note`. Only the captured text between the
+/// link delimiters is split, so in a table row like `| [[note]] | x |` the
+/// column pipes are never mistaken for an alias separator. Inside tables the
+/// alias pipe is usually escaped (`[[note\|Shown text]]`); the backslash is
+/// dropped from the target.
+///
+/// # Returns
+///
+/// The target and the alias, if any. The alias is empty for `[[note|]]`.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(split_link_alias("note|Shown"), ("note", Some("Shown")));
+/// assert_eq!(split_link_alias("note\\|Shown"), ("note", Some("Shown")));
+/// assert_eq!(split_link_alias("note"), ("note", None));
+/// ```
+pub fn split_link_alias(inner: &str) -> (&str, Option<&str>) {
+    match inner.split_once('|') {
+        Some((target, alias)) => (target.strip_suffix('\\').unwrap_or(target), Some(alias)),
+        None => (inner, None),
+    }
+}
+
+/// Strips a trailing markdown file extension from a link target.
+///
+/// Node IDs are file stems, so a link written with its extension (`[[note.md]]`
+/// or `[[note.markdown]]`) would otherwise point to a separate phantom node
+/// `note.md`. Extensions in `MARKDOWN_EXTENSIONS` are matched case-insensitively.
+/// A target consisting only of an extension (`[[.md]]`) is left unchanged.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(normalize_link_target("note.md"), "note");
+/// assert_eq!(normalize_link_target("v1.2"), "v1.2");
+/// ```
+pub fn normalize_link_target(target: &str) -> &str {
+    let Some((stem, extension)) = target.rsplit_once('.') else {
+        return target;
+    };
+
+    let is_markdown = MARKDOWN_EXTENSIONS
+        .iter()
+        .any(|markdown| markdown.eq_ignore_ascii_case(extension));
+
+    if is_markdown && !stem.is_empty() {
+        stem
+    } else {
+        target
+    }
+}
+
 /// Extracts all hashtags from markdown content.
 ///
 /// Finds all occurrences of the pattern `#word` and extracts the word after the
-/// hash symbol. The regex pattern `#(\w+)` matches a hash followed by one or more
-/// word characters (letters, digits, underscores).
+/// hash symbol. The regex pattern `#(\w+(?:/\w+)*)` matches a hash followed by
+/// one or more word characters (letters, digits, underscores), optionally
+/// followed by further `/`-separated segments for hierarchical tags.
 ///
 /// # Arguments
 ///
@@ -119,7 +678,7 @@ fn extract_wiki_links(content: &str) -> Vec<String> {
 ///
 /// # Pattern Details
 ///
-/// - Matches: `#tag`, `#CamelCase`, `#tag_with_underscores`, `#tag123`
+/// - Matches: `#tag`, `#CamelCase`, `#tag_with_underscores`, `#tag123`, `#project/frontend`
 /// - Does not match: `#tag-with-dashes`, `# tag` (space after hash), hashtags in code blocks
 ///
 /// # Note
@@ -133,9 +692,737 @@ fn extract_wiki_links(content: &str) -> Vec<String> {
 /// Panics if the regex pattern fails to compile, which should never happen with
 /// a valid hard-coded pattern.
 fn extract_hashtags(content: &str) -> Vec<String> {
-    let re = Regex::new(r"#(\w+)").unwrap();
+    let re = Regex::new(HASHTAG_PATTERN).unwrap();
 
     re.captures_iter(content)
         .map(|cap| cap[1].to_string())
         .collect()
 }
+
+/// Returns `true` if `tag` is a valid tag name (without the leading `#`).
+///
+/// Valid names consist of word characters, optionally split into hierarchy
+/// levels by single `/` separators, e.g. `project` or `project/frontend`.
+pub fn is_valid_tag(tag: &str) -> bool {
+    Regex::new(TAG_NAME_PATTERN).unwrap().is_match(tag)
+}
+
+/// Returns the key tags are looked up by.
+///
+/// Tags keep their casing everywhere they are displayed, but lookups compare
+/// them by this lowercased key, so querying `Todo` finds notes tagged `todo`.
+pub fn tag_key(tag: &str) -> String {
+    tag.to_lowercase()
+}
+
+/// Renames every occurrence of a hashtag in markdown content.
+///
+/// Rewrites `#old` to `#new` only where `old` is the complete tag or a leading
+/// hierarchy level of it: `#old` and `#old/sub` are renamed (the latter to
+/// `#new/sub`), while `#oldish` is left untouched. Like tag lookups (see
+/// `tag_key`), `old` matches case-insensitively, so `#Old` is renamed too.
+///
+/// Inline tags are renamed in the body, except inside wiki-links and URLs,
+/// where a `#` starts a heading reference or fragment (`[[Note#old]]`,
+/// `https://example.com/page#old`). Entries of the frontmatter `tags:` key are
+/// renamed as well, in every form `parse_frontmatter` understands.
+///
+/// # Arguments
+///
+/// * `content` - Markdown content to rewrite
+/// * `old` - Tag name to replace, without the `#`
+/// * `new` - Replacement tag name, without the `#`
+/// * `syntax` - Wiki-link syntax whose links are left untouched
+///
+/// # Returns
+///
+/// * `Some(String)` - The rewritten content, if at least one tag was renamed
+/// * `None` - The content contains no matching tag
+///
+/// # Examples
+///
+/// ```ignore
+/// let renamed = rename_hashtag("#old #Old/sub #oldish [[a#old]]", "old", "new", &syntax);
+/// assert_eq!(renamed.as_deref(), Some("#new #new/sub #oldish [[a#old]]"));
+/// ```
+pub fn rename_hashtag(content: &str, old: &str, new: &str, syntax: &LinkSyntax) -> Option<String> {
+    let (block, body) = split_frontmatter(content);
+    let body_start = content.len() - body.len();
+    let mut renamed_ranges = Vec::new();
+
+    if let Some(block) = block {
+        let block_start = content.find('\n').map_or(0, |index| index + 1);
+        renamed_ranges.extend(
+            frontmatter_tag_ranges(block, old)
+                .into_iter()
+                .map(|range| block_start + range.start..block_start + range.end),
+        );
+    }
+
+    let skipped: Vec<Range<usize>> = syntax
+        .link_spans(body)
+        .into_iter()
+        .chain(Regex::new(URL_PATTERN).unwrap().find_iter(body).map(|url| url.range()))
+        .collect();
+    let re = Regex::new(&format!(r"(?i)#({})\b", regex::escape(old))).unwrap();
+
+    for cap in re.captures_iter(body) {
+        let start = cap.get(0).unwrap().start();
+
+        if skipped.iter().any(|span| span.contains(&start)) {
+            continue;
+        }
+
+        let tag = cap.get(1).unwrap();
+        renamed_ranges.push(body_start + tag.start()..body_start + tag.end());
+    }
+
+    if renamed_ranges.is_empty() {
+        return None;
+    }
+
+    let mut renamed = String::with_capacity(content.len());
+    let mut copied = 0;
+
+    for range in renamed_ranges {
+        renamed.push_str(&content[copied..range.start]);
+        renamed.push_str(new);
+        copied = range.end;
+    }
+
+    renamed.push_str(&content[copied..]);
+    Some(renamed)
+}
+
+/// Returns the byte ranges of `old` in the `tags:` entries of a frontmatter block.
+///
+/// Walks the block like `parse_frontmatter`, covering scalars (`tags: old`),
+/// inline arrays (`tags: [a, old]`) and block sequences (`- old`). An entry
+/// matches if, after removing quotes and leading `#`s, `old` is its complete
+/// tag or leading hierarchy level, compared case-insensitively. Ranges are
+/// relative to `block` and cover just the `old` part of the entry.
+fn frontmatter_tag_ranges(block: &str, old: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut in_tags_list = false;
+    let mut offset = 0;
+
+    for line in block.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let line = line.trim_end();
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if trimmed == "-" || trimmed.starts_with("- ") {
+            if in_tags_list {
+                let item = trimmed[1..].trim_start();
+                let item_start = line_start + indent + trimmed.len() - item.len();
+                ranges.extend(tag_entry_range(item, item_start, old));
+            }
+            continue;
+        }
+
+        in_tags_list = false;
+
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        if key.trim() != "tags" {
+            continue;
+        }
+
+        let value_start = line_start + key.len() + 1 + value.len() - value.trim_start().len();
+        let value = value.trim();
+
+        if value.is_empty() {
+            in_tags_list = true;
+        } else if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            let mut item_offset = value_start + 1;
+
+            for item in inner.split(',') {
+                let item_start = item_offset + item.len() - item.trim_start().len();
+                ranges.extend(tag_entry_range(item.trim(), item_start, old));
+                item_offset += item.len() + 1;
+            }
+        } else {
+            ranges.extend(tag_entry_range(value, value_start, old));
+        }
+    }
+
+    ranges
+}
+
+/// Returns the range of `old` in a frontmatter tag entry starting at `start`.
+///
+/// See `frontmatter_tag_ranges` for how entries match.
+fn tag_entry_range(entry: &str, start: usize, old: &str) -> Option<Range<usize>> {
+    let quoted = entry.len() >= 2
+        && ((entry.starts_with('"') && entry.ends_with('"'))
+            || (entry.starts_with('\'') && entry.ends_with('\'')));
+    let (tag, start) = if quoted {
+        (&entry[1..entry.len() - 1], start + 1)
+    } else {
+        (entry, start)
+    };
+
+    let hashes = tag.len() - tag.trim_start_matches('#').len();
+    let (tag, start) = (&tag[hashes..], start + hashes);
+
+    let head_matches = tag.get(..old.len()).is_some_and(|head| tag_key(head) == tag_key(old));
+    let rest = tag.get(old.len()..).unwrap_or("");
+
+    (head_matches && (rest.is_empty() || rest.starts_with('/'))).then(|| start..start + old.len())
+}
+
+/// Points every wiki-link to `old` at `new` instead.
+///
+/// Each link is parsed and only its target name is replaced, so everything
+/// around it survives: a display alias (`[[old|Shown]]` becomes
+/// `[[new|Shown]]`), a heading or block reference (`[[old#Setup]]` becomes
+/// `[[new#Setup]]`), a markdown extension (`[[old.md]]`) and surrounding
+/// whitespace. A link matches if its target as returned by `link_target` is
+/// exactly `old`, so `[[older]]` and path links like `[[dir/old]]` are left
+/// untouched, as is plain text mentioning `old`.
+///
+/// # Arguments
+///
+/// * `content` - Markdown content to rewrite
+/// * `old` - Link target to replace (a node ID)
+/// * `new` - New link target
+/// * `syntax` - Wiki-link syntax used to find links
+///
+/// # Returns
+///
+/// * `Some(String)` - The rewritten content, if at least one link was changed
+/// * `None` - The content contains no link to `old`
+///
+/// # Example
+///
+/// ```ignore
+/// let renamed = rename_link_target("[[old|Shown]] [[old#Setup]] old", "old", "new", &syntax);
+/// assert_eq!(renamed.as_deref(), Some("[[new|Shown]] [[new#Setup]] old"));
+/// ```
+pub fn rename_link_target(
+    content: &str,
+    old: &str,
+    new: &str,
+    syntax: &LinkSyntax,
+) -> Option<String> {
+    let mut renamed = String::with_capacity(content.len());
+    let mut copied = 0;
+    let mut changed = false;
+
+    for inner in syntax.regex.captures_iter(content).filter_map(|cap| cap.get(1)) {
+        if link_target(inner.as_str()) != old {
+            continue;
+        }
+
+        let (target, _) = split_link_alias(inner.as_str());
+        let note = target.split_once('#').map_or(target, |(note, _)| note);
+        let start = inner.start() + note.len() - note.trim_start().len();
+
+        renamed.push_str(&content[copied..start]);
+        renamed.push_str(new);
+        copied = start + old.len();
+        changed = true;
+    }
+
+    if !changed {
+        return None;
+    }
+
+    renamed.push_str(&content[copied..]);
+    Some(renamed)
+}
+
+/// Returns the lines of `content` that contain a wiki-link to `target`.
+///
+/// Used to show backlink context: for a note that references `target`, these are
+/// the lines where the reference appears. Lines are returned trimmed and in
+/// document order, each at most once even if it links to `target` several times.
+///
+/// # Arguments
+///
+/// * `content` - Content of the referencing note
+/// * `target` - Link target to look for (a node ID)
+/// * `syntax` - Wiki-link syntax used to find links
+pub fn lines_linking_to<'a>(content: &'a str, target: &str, syntax: &LinkSyntax) -> Vec<&'a str> {
+    content
+        .lines()
+        .filter(|line| syntax.targets(line).iter().any(|link| link == target))
+        .map(str::trim)
+        .collect()
+}
+
+/// Splits content into its set of distinct lowercase words.
+///
+/// A word is a maximal run of alphanumeric characters, so punctuation and
+/// markdown syntax (`#`, `[[`, `*`) act as separators. Frontmatter is not
+/// removed; callers that want body text only should use `split_frontmatter` first.
+///
+/// # Returns
+///
+/// The distinct words of `content`. Empty for content without words.
+pub fn word_set(content: &str) -> HashSet<String> {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// An ATX heading (`#` to `######`) of a note.
+///
+/// # Fields
+///
+/// * `level` - Heading level, 1 for `#` up to 6 for `######`
+/// * `text` - Heading text without the `#` markers and surrounding whitespace
+/// * `line` - 1-based line number in the full content, including any frontmatter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Heading {
+    pub level: usize,
+    pub text: String,
+    pub line: usize,
+}
+
+/// Extracts the ATX headings of markdown content in document order.
+///
+/// A heading is a line starting (after at most three spaces) with one to six
+/// `#` followed by whitespace or the end of the line, e.g. `## Setup`. An
+/// optional closing sequence of `#` is removed (`## Setup ##`). Headings without
+/// text are skipped, as are lines in the frontmatter and inside fenced code
+/// blocks (see `lines_outside_code`), so a `# comment` in a shell snippet is not
+/// a heading. Setext headings (underlined with `===`) are not recognized.
+///
+/// # Examples
+///
+/// ```ignore
+/// let headings = extract_headings("# Title\n\n## Part");
+/// assert_eq!(headings[1].level, 2);
+/// assert_eq!(headings[1].line, 3);
+/// ```
+pub fn extract_headings(content: &str) -> Vec<Heading> {
+    lines_outside_code(content)
+        .into_iter()
+        .filter_map(|(line, text)| {
+            let (level, text) = parse_atx_heading(text)?;
+
+            Some(Heading {
+                level,
+                text: text.to_string(),
+                line,
+            })
+        })
+        .collect()
+}
+
+/// Finds the lines mentioning any of the given terms outside a wiki-link.
+///
+/// Used for "unlinked mentions": places where a note is named in plain text but
+/// not linked. Terms are matched case-insensitively as whole words, so `Rust`
+/// matches `rust,` but not `Rustacean`. A match inside a link of `syntax`
+/// (`[[Rust]]`, `[[Rust|the language]]`) doesn't count. The frontmatter and
+/// fenced code blocks are skipped (see `lines_outside_code`).
+///
+/// # Returns
+///
+/// The 1-based line number (in the full content) and text of each line with at
+/// least one unlinked mention, in document order.
+pub fn unlinked_mentions<'a>(
+    content: &'a str,
+    terms: &[String],
+    syntax: &LinkSyntax,
+) -> Vec<(usize, &'a str)> {
+    let patterns: Vec<Regex> = terms
+        .iter()
+        .map(|term| term.trim())
+        .filter(|term| !term.is_empty())
+        .filter_map(|term| Regex::new(&format!("(?i){}", regex::escape(term))).ok())
+        .collect();
+
+    lines_outside_code(content)
+        .into_iter()
+        .filter(|(_, line)| {
+            let links = syntax.link_spans(line);
+
+            patterns.iter().any(|pattern| {
+                pattern.find_iter(line).any(|found| {
+                    is_word_boundary(line, found.start(), found.end())
+                        && !links
+                            .iter()
+                            .any(|link| link.start <= found.start() && found.end() <= link.end)
+                })
+            })
+        })
+        .collect()
+}
+
+/// Returns `true` if no word character touches the range `start..end` of `text`.
+fn is_word_boundary(text: &str, start: usize, end: usize) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    !text[..start].chars().next_back().is_some_and(is_word)
+        && !text[end..].chars().next().is_some_and(is_word)
+}
+
+/// Deepest heading level treated as a section by `extract_sections`.
+pub const SECTION_MAX_LEVEL: usize = 2;
+
+/// Extracts the top-level headings (H1 and H2) of markdown content.
+///
+/// These are the note's sections, used for navigation and filtering. Headings
+/// are found like in `extract_headings`.
+pub fn extract_sections(content: &str) -> Vec<Heading> {
+    extract_headings(content)
+        .into_iter()
+        .filter(|heading| heading.level <= SECTION_MAX_LEVEL)
+        .collect()
+}
+
+/// Parses a line as an ATX heading, returning its level and text.
+fn parse_atx_heading(line: &str) -> Option<(usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+
+    if indent > 3 {
+        return None;
+    }
+
+    let rest = &line[indent..];
+    let level = rest.len() - rest.trim_start_matches('#').len();
+    let rest = &rest[level..];
+
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    let rest = rest.trim();
+    let without_closing = rest.trim_end_matches('#');
+    let text = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        rest
+    };
+
+    (!text.is_empty()).then_some((level, text))
+}
+
+/// Returns the body lines of markdown content that are not inside fenced code.
+///
+/// Frontmatter is skipped (see `split_frontmatter`). A fence is a line starting
+/// (after at most three spaces) with at least three backticks or tildes; it
+/// lasts until a line with at least as many of the same character, or the end
+/// of the content. Fence lines themselves are left out too.
+///
+/// # Returns
+///
+/// Each remaining line with its 1-based line number in the full content.
+fn lines_outside_code(content: &str) -> Vec<(usize, &str)> {
+    let (_, body) = split_frontmatter(content);
+    let first_line = content[..content.len() - body.len()].matches('\n').count() + 1;
+    let mut fence: Option<(char, usize)> = None;
+    let mut lines = Vec::new();
+
+    for (index, line) in body.lines().enumerate() {
+        let marker = code_fence_marker(line);
+
+        match (fence, marker) {
+            (None, Some(marker)) => fence = Some(marker),
+            (None, None) => lines.push((first_line + index, line)),
+            (Some((open, open_len)), Some((close, close_len)))
+                if close == open && close_len >= open_len =>
+            {
+                fence = None
+            }
+            (Some(_), _) => {}
+        }
+    }
+
+    lines
+}
+
+/// Returns the fence character and length if a line opens or closes a code fence.
+fn code_fence_marker(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.len() - trimmed.trim_start_matches(marker).len();
+
+    (len >= 3).then_some((marker, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frontmatter_tags_merge_with_inline_tags() {
+        let parsed = parse_markdown("---\ntags: [a, \"b\"]\ntitle: Nice\n---\n# H #a #c [[x]]");
+        assert_eq!(parsed.hashtags, vec!["a", "c", "b"]);
+        assert_eq!(parsed.wiki_links, vec!["x"]);
+        assert_eq!(parsed.frontmatter.unwrap().get_str("title"), Some("Nice"));
+
+        let parsed = parse_markdown("---\ntags: solo\n---\nbody");
+        assert_eq!(parsed.hashtags, vec!["solo"]);
+
+        let crlf =
+            "---\r\ntags:\r\n  - one\r\n  - '#two'\r\n# comment\r\nx: y\r\n---\r\nbody #three";
+        assert_eq!(parse_markdown(crlf).hashtags, vec!["three", "one", "two"]);
+
+        let parsed = parse_markdown("---\ntags: a\nno close\n#x");
+        assert!(parsed.frontmatter.is_none());
+        assert_eq!(parsed.hashtags, vec!["x"]);
+    }
+
+    #[test]
+    fn link_positions() {
+        let content = "---\ntags: [x]\n---\n# Title\nSee [[note]] and ä [[other]] #t";
+        let positions = parse_markdown_with_positions(content, &LinkSyntax::default());
+
+        let links = &positions.wiki_links;
+        assert_eq!(links.len(), 2);
+        assert_eq!(
+            (links[0].text.as_str(), links[0].line, links[0].column),
+            ("note", 5, 5)
+        );
+        assert_eq!(
+            (links[1].text.as_str(), links[1].line, links[1].column),
+            ("other", 5, 20)
+        );
+        assert_eq!(&content[links[1].start..links[1].end], "[[other]]");
+
+        let tags = &positions.hashtags;
+        assert_eq!(
+            tags.iter().map(|h| h.text.as_str()).collect::<Vec<_>>(),
+            vec!["t"]
+        );
+        assert_eq!(&content[tags[0].start..tags[0].end], "#t");
+    }
+
+    #[test]
+    fn link_positions_use_the_link_syntax() {
+        let syntax = LinkSyntax::new(r"\(\(([^)]+)\)\)").unwrap();
+        let positions = parse_markdown_with_positions("[[a]]\nsee ((b))", &syntax);
+
+        let links = &positions.wiki_links;
+        assert_eq!(links.len(), 1);
+        assert_eq!(
+            (links[0].text.as_str(), links[0].line, links[0].column),
+            ("b", 2, 5)
+        );
+    }
+
+    #[test]
+    fn rename_hashtag_cases() {
+        let syntax = LinkSyntax::default();
+        assert_eq!(
+            rename_hashtag("a #old b", "old", "new", &syntax).as_deref(),
+            Some("a #new b")
+        );
+        assert_eq!(
+            rename_hashtag("#old/sub x", "old", "new", &syntax).as_deref(),
+            Some("#new/sub x")
+        );
+        assert_eq!(rename_hashtag("#oldish #xold", "old", "new", &syntax), None);
+        assert_eq!(
+            rename_hashtag("#Old [[Note#old]] [[#old|x]]", "old", "new", &syntax).as_deref(),
+            Some("#new [[Note#old]] [[#old|x]]")
+        );
+        assert_eq!(
+            rename_hashtag(
+                "see https://example.com/page#old and [docs](page#old)",
+                "old",
+                "new",
+                &syntax
+            ),
+            None
+        );
+
+        let content = "---\ntags: [a, \"#old\", Old/sub]\nkeywords: old\n---\n#old";
+        assert_eq!(
+            rename_hashtag(content, "old", "new", &syntax).as_deref(),
+            Some("---\ntags: [a, \"#new\", new/sub]\nkeywords: old\n---\n#new")
+        );
+        let content = "---\ntags:\n  - old\n  - oldish\n---\nBody";
+        assert_eq!(
+            rename_hashtag(content, "old", "new", &syntax).as_deref(),
+            Some("---\ntags:\n  - new\n  - oldish\n---\nBody")
+        );
+        assert_eq!(
+            rename_hashtag("---\r\ntags: 'old'\r\n---\r\n", "old", "new", &syntax).as_deref(),
+            Some("---\r\ntags: 'new'\r\n---\r\n")
+        );
+
+        assert!(is_valid_tag("a/b"));
+        assert!(!is_valid_tag("a b"));
+        assert!(!is_valid_tag("a/"));
+        assert!(!is_valid_tag(""));
+        assert_eq!(
+            parse_markdown("#project/frontend").hashtags,
+            vec!["project/frontend"]
+        );
+    }
+
+    #[test]
+    fn custom_link_syntax() {
+        assert_eq!(parse_markdown("[[a]] ((b))").wiki_links, vec!["a"]);
+
+        let syntax = LinkSyntax::new(r"\(\(([^)]+)\)\)").unwrap();
+        assert_eq!(
+            parse_markdown_with("[[a]] ((b))", &syntax).wiki_links,
+            vec!["b"]
+        );
+
+        assert!(LinkSyntax::new(r"\[\[.+\]\]").is_err());
+        assert!(LinkSyntax::new(r"(").is_err());
+    }
+
+    #[test]
+    fn links_inside_tables() {
+        let content = "| Name | Link |\n|---|---|\n| a |[[note]]| \n\
+                       | b | [[other\\|Shown]] |\n|[[x|y]]|[[z.md]]|";
+
+        assert_eq!(
+            parse_markdown(content).wiki_links,
+            vec!["note", "other", "x", "z"]
+        );
+        assert_eq!(split_link_alias("note|Shown"), ("note", Some("Shown")));
+        assert_eq!(split_link_alias("note\\|Shown"), ("note", Some("Shown")));
+        assert_eq!(split_link_alias("note|"), ("note", Some("")));
+        assert_eq!(split_link_alias("note"), ("note", None));
+    }
+
+    #[test]
+    fn empty_link_targets_are_dropped() {
+        assert_eq!(
+            parse_markdown("[[ ]] [[]] [[  note  ]] [[ |x]]").wiki_links,
+            vec!["note"]
+        );
+
+        let syntax = LinkSyntax::new(r"\(\(([^)]*)\)\)").unwrap();
+        assert_eq!(
+            parse_markdown_with("(()) ((   )) (( b.md ))", &syntax).wiki_links,
+            vec!["b"]
+        );
+    }
+
+    #[test]
+    fn blockquote_links_can_be_skipped() {
+        let content = "[[a]]\n> quoted [[b]]\n  > > nested [[c]]\nafter [[d]] > [[e]]\n";
+
+        let all = parse_markdown_with(content, &LinkSyntax::default()).wiki_links;
+        assert_eq!(all, vec!["a", "b", "c", "d", "e"]);
+
+        let skip = LinkSyntax::default().skipping_blockquotes(true);
+        assert_eq!(
+            parse_markdown_with(content, &skip).wiki_links,
+            vec!["a", "d", "e"]
+        );
+    }
+
+    #[test]
+    fn outline_skips_code_blocks() {
+        let content = "---\ntitle: x\n---\n# Title\ntext #tag\n## Part ##\n   ### Deep\n\
+                       ####### no\n#\n## C#\n";
+        let headings = extract_headings(content);
+        let got: Vec<(usize, &str, usize)> = headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str(), h.line))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                (1, "Title", 4),
+                (2, "Part", 6),
+                (3, "Deep", 7),
+                (2, "C#", 10)
+            ]
+        );
+
+        let headings =
+            extract_headings("# A\n```sh\n# comment\n~~~\n```\n## B\n~~~~\n# x\n~~~\n~~~~\n# C");
+        let got: Vec<(&str, usize)> = headings.iter().map(|h| (h.text.as_str(), h.line)).collect();
+        assert_eq!(got, vec![("A", 1), ("B", 6), ("C", 11)]);
+    }
+
+    #[test]
+    fn first_h1_is_the_section_title() {
+        let content = "---\ntitle: x\n---\nintro\n## Setup\n# Main Title\n### Deep\n\
+                       ```\n# not\n```\n# Second\n";
+        let parsed = parse_markdown(content);
+
+        assert_eq!(parsed.section_title(), Some("Main Title"));
+        let texts: Vec<&str> = parsed.sections.iter().map(|h| h.text.as_str()).collect();
+        assert_eq!(texts, vec!["Setup", "Main Title", "Second"]);
+        assert_eq!(parse_markdown("## Only h2").section_title(), None);
+
+        let dto = ParsedContentDto::from(parsed);
+        assert_eq!(dto.section_title.as_deref(), Some("Main Title"));
+    }
+
+    #[test]
+    fn rename_link_target_forms() {
+        let syntax = LinkSyntax::default();
+        let content =
+            "[[old]] [[old|Shown]] [[old#Setup|x]] [[ old.md ]] [[old\\|t]] [[older]] old \
+                       [[dir/old]] [[#old]]";
+        let expected =
+            "[[new]] [[new|Shown]] [[new#Setup|x]] [[ new.md ]] [[new\\|t]] [[older]] old \
+                        [[dir/old]] [[#old]]";
+
+        assert_eq!(
+            rename_link_target(content, "old", "new", &syntax).as_deref(),
+            Some(expected)
+        );
+        assert_eq!(
+            rename_link_target("[[older]] old", "old", "new", &syntax),
+            None
+        );
+        assert_eq!(
+            rename_link_target("[[old#Über]]\n[[old#^blk]]", "old", "Neu", &syntax).as_deref(),
+            Some("[[Neu#Über]]\n[[Neu#^blk]]")
+        );
+    }
+
+    #[test]
+    fn unlinked_mentions_exclude_links() {
+        let content = "---\ntopic: rust\n---\nI like [[Rust]] a lot\nrust is fast\n\
+                       [[Rust|rust]] again, and Rust too\nRustacean\n```\nrust in code\n```\n\
+                       the rust language, Ferris!\n";
+        let terms = vec!["Rust".to_string(), "ferris".to_string()];
+
+        let mentions = unlinked_mentions(content, &terms, &LinkSyntax::default());
+
+        assert_eq!(
+            mentions,
+            vec![
+                (5, "rust is fast"),
+                (6, "[[Rust|rust]] again, and Rust too"),
+                (11, "the rust language, Ferris!")
+            ]
+        );
+    }
+
+    #[test]
+    fn backlink_lines() {
+        let content = "intro\n  see [[a]] here  \nother [[c]]\nagain [[a]] [[a]]";
+
+        assert_eq!(
+            lines_linking_to(content, "a", &LinkSyntax::default()),
+            vec!["see [[a]] here", "again [[a]] [[a]]"]
+        );
+    }
+}
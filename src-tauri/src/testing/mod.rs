@@ -0,0 +1,79 @@
+//! Fixtures shared by the unit tests of the other modules.
+
+use crate::config::{AppConfig, AppState};
+use crate::events::EventEmitter;
+use crate::scanner::MarkdownFile;
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Arc, Mutex};
+use tauri::test::{mock_app, MockRuntime};
+use tauri::{App, Manager};
+
+/// Creates an in-memory markdown file named `name` at `/v/{name}.md`.
+pub fn markdown_file(name: &str, content: &str) -> MarkdownFile {
+    MarkdownFile {
+        path: PathBuf::from(format!("/v/{}.md", name)),
+        content: content.to_string(),
+        name: name.to_string(),
+    }
+}
+
+/// Creates an empty temporary directory unique to `name` and this test run.
+///
+/// Any directory left over from an earlier run with the same process ID is
+/// removed first. Names must be unique across all tests, as tests run in
+/// parallel.
+pub fn temp_vault(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("mdgraph-test-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Creates an `AppState` with the default configuration and `dir` as `root_dir`.
+pub fn state_for(dir: &Path) -> AppState {
+    let config = AppConfig {
+        root_dir: Some(dir.to_string_lossy().to_string()),
+        ..AppConfig::default()
+    };
+
+    AppState::new(config, Default::default())
+}
+
+/// Creates a mock app managing `state`, so commands can be called with
+/// `app.state::<AppState>()`.
+pub fn app_with(state: AppState) -> App<MockRuntime> {
+    let app = mock_app();
+    app.manage(state);
+    app
+}
+
+/// Event emitter recording every event with its JSON payload.
+///
+/// Clones share the recorded events, so a clone can be moved to a background
+/// thread while the original is inspected.
+#[derive(Clone, Default)]
+pub struct Recorder(Arc<Mutex<Vec<(String, serde_json::Value)>>>);
+
+impl Recorder {
+    /// Returns the events recorded so far, oldest first.
+    pub fn events(&self) -> Vec<(String, serde_json::Value)> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Returns the names of the events recorded so far, oldest first.
+    pub fn names(&self) -> Vec<String> {
+        self.events().into_iter().map(|(name, _)| name).collect()
+    }
+}
+
+impl EventEmitter for Recorder {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) -> Result<(), String> {
+        let payload = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+        self.0.lock().unwrap().push((event.to_string(), payload));
+        Ok(())
+    }
+}
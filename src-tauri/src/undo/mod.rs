@@ -0,0 +1,223 @@
+//! Undo support for commands that modify notes on disk.
+//!
+//! Commands that write to the vault (creating notes, appending to notes,
+//! renaming tags, importing notes, organizing notes into tag folders, renaming
+//! notes) record an `Operation` describing how to reverse their file changes.
+//! The operations are kept in a bounded `OperationLog` in `AppState`, and the
+//! `undo_last` command reverses the most recent one.
+//!
+//! # Scope
+//!
+//! The log lives in memory only and is lost when the application exits. Undoing
+//! restores files to their recorded state, discarding any edits made to them in
+//! the meantime.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+
+/// Maximum number of operations kept in the log; older operations are dropped.
+pub const MAX_UNDO_OPERATIONS: usize = 50;
+
+/// A single file change, recorded with what is needed to reverse it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    /// A new file was created; undoing deletes it
+    Created { path: PathBuf },
+    /// An existing file was overwritten or appended to; undoing restores its
+    /// previous content
+    Modified { path: PathBuf, previous: Vec<u8> },
+    /// A file was moved; undoing moves it back
+    Moved { from: PathBuf, to: PathBuf },
+}
+
+impl FileChange {
+    /// Reverses the change on disk.
+    ///
+    /// Deleting a created file that no longer exists is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if the file can't be deleted, written or moved.
+    pub fn revert(&self) -> Result<(), String> {
+        match self {
+            Self::Created { path } => match fs::remove_file(path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(format!("Failed to delete file {:?}: {}", path, e)),
+            },
+            Self::Modified { path, previous } => fs::write(path, previous)
+                .map_err(|e| format!("Failed to restore file {:?}: {}", path, e)),
+            Self::Moved { from, to } => fs::rename(to, from)
+                .map_err(|e| format!("Failed to move file {:?} back to {:?}: {}", to, from, e)),
+        }
+    }
+}
+
+/// A reversible operation performed by a command.
+///
+/// # Fields
+///
+/// * `description` - Human-readable summary, e.g. "Rename tag #todo to #tasks"
+/// * `changes` - File changes in the order they were made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub description: String,
+    pub changes: Vec<FileChange>,
+}
+
+impl Operation {
+    /// Reverses all file changes of the operation, newest first.
+    ///
+    /// Stops at the first change that can't be reverted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error message if a change can't be reverted.
+    pub fn revert(&self) -> Result<(), String> {
+        self.changes.iter().rev().try_for_each(FileChange::revert)
+    }
+}
+
+/// Bounded log of the most recent reversible operations.
+#[derive(Debug, Clone)]
+pub struct OperationLog {
+    operations: VecDeque<Operation>,
+    capacity: usize,
+}
+
+impl Default for OperationLog {
+    fn default() -> Self {
+        Self::with_capacity(MAX_UNDO_OPERATIONS)
+    }
+}
+
+impl OperationLog {
+    /// Creates an empty log keeping at most `capacity` operations.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            operations: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Records an operation, dropping the oldest one if the log is full.
+    ///
+    /// Operations without file changes are ignored.
+    pub fn record(&mut self, operation: Operation) {
+        if operation.changes.is_empty() || self.capacity == 0 {
+            return;
+        }
+
+        if self.operations.len() == self.capacity {
+            self.operations.pop_front();
+        }
+
+        self.operations.push_back(operation);
+    }
+
+    /// Removes and returns the most recent operation, if any.
+    pub fn pop(&mut self) -> Option<Operation> {
+        self.operations.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_vault;
+
+    fn operation(description: &str, changes: Vec<FileChange>) -> Operation {
+        Operation {
+            description: description.to_string(),
+            changes,
+        }
+    }
+
+    #[test]
+    fn file_changes_revert() {
+        let dir = temp_vault("undo-revert");
+        let created = dir.join("created.md");
+        let modified = dir.join("modified.md");
+        let moved = dir.join("moved.md");
+        fs::write(&created, "new").unwrap();
+        fs::write(&modified, "edited").unwrap();
+        fs::write(&moved, "content").unwrap();
+
+        let op = operation(
+            "mixed",
+            vec![
+                FileChange::Created {
+                    path: created.clone(),
+                },
+                FileChange::Modified {
+                    path: modified.clone(),
+                    previous: b"original".to_vec(),
+                },
+                FileChange::Moved {
+                    from: dir.join("origin.md"),
+                    to: moved.clone(),
+                },
+            ],
+        );
+        op.revert().unwrap();
+
+        assert!(!created.exists());
+        assert_eq!(fs::read_to_string(&modified).unwrap(), "original");
+        assert!(!moved.exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("origin.md")).unwrap(),
+            "content"
+        );
+
+        FileChange::Created { path: created }.revert().unwrap();
+    }
+
+    #[test]
+    fn changes_revert_in_reverse_order() {
+        let dir = temp_vault("undo-order");
+        let note = dir.join("a.md");
+        fs::write(&note, "third").unwrap();
+
+        operation(
+            "two edits",
+            vec![
+                FileChange::Modified {
+                    path: note.clone(),
+                    previous: b"first".to_vec(),
+                },
+                FileChange::Modified {
+                    path: note.clone(),
+                    previous: b"second".to_vec(),
+                },
+            ],
+        )
+        .revert()
+        .unwrap();
+
+        assert_eq!(fs::read_to_string(&note).unwrap(), "first");
+    }
+
+    #[test]
+    fn log_is_bounded_and_skips_empty_operations() {
+        let change = || {
+            vec![FileChange::Created {
+                path: PathBuf::from("zz"),
+            }]
+        };
+        let mut log = OperationLog::with_capacity(2);
+
+        for i in 0..3 {
+            log.record(operation(&i.to_string(), change()));
+        }
+        log.record(operation("nothing", Vec::new()));
+
+        assert_eq!(log.pop().unwrap().description, "2");
+        assert_eq!(log.pop().unwrap().description, "1");
+        assert!(log.pop().is_none());
+
+        let mut disabled = OperationLog::with_capacity(0);
+        disabled.record(operation("x", change()));
+        assert!(disabled.pop().is_none());
+    }
+}
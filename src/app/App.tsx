@@ -4,6 +4,7 @@ import { CommandLine } from '../features/command-line/components/CommandLine';
 import { useKeybindings } from '../features/keybindings/hooks/useKeybindings';
 import { useGraphSync } from '../infrastructure/services/GraphSyncService';
 import { TauriCommands } from '../infrastructure/tauri/commands';
+import { TauriEvents } from '../infrastructure/tauri/events';
 import { useGraphStore } from '../features/graph/store/graphStore';
 
 export const App: React.FC = () => {
@@ -15,15 +16,32 @@ export const App: React.FC = () => {
   useGraphSync();
 
   useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
     const initializeApp = async () => {
       try {
         setLoading(true);
 
+        // The backend populates its cache in the background and announces it
+        // with graph-ready; subscribe first so the event can't be missed.
+        unlisten = await TauriEvents.onGraphReady(async ({ error }) => {
+          if (error) {
+            setError(error);
+            return;
+          }
+          setGraphData(await TauriCommands.getCachedGraph());
+        });
+
         const config = await TauriCommands.getConfig();
 
         if (config.root_dir) {
-          const graphData = await TauriCommands.scanFolder(config.root_dir);
-          setGraphData(graphData);
+          const cached = await TauriCommands.getCachedGraph();
+
+          // An empty cache is still being populated, unless startup population
+          // is disabled; graph-ready then delivers the graph.
+          if (cached.nodes.length > 0 || !config.scan_on_startup) {
+            setGraphData(cached);
+          }
         } else {
           setLoading(false);
           console.warn('[App] No root_dir configured');
@@ -35,6 +53,10 @@ export const App: React.FC = () => {
     };
 
     initializeApp();
+
+    return () => {
+      if (unlisten) unlisten();
+    };
   }, [setGraphData, setLoading, setError]);
 
   return (
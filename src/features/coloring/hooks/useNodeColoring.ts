@@ -27,7 +27,7 @@ export const useNodeColoring = (network: Network | null) => {
           return 'outgoing';
         case incomingNodeIds.has(nodeId):
           return 'incoming';
-        case graphData.nodes.find((n) => n.id === nodeId)?.group === 'phantom':
+        case graphData.nodes.find((n) => n.id === nodeId)?.file_path === '':
           return 'phantom';
         default:
           return 'regular';
@@ -79,7 +79,7 @@ export const useGraphNetwork = (
       graphData.nodes.map((node) => {
         const baseNode: any = JSON.parse(JSON.stringify(node));
 
-        if (node.group === 'phantom') {
+        if (node.file_path === '') {
           return { ...baseNode, ...getPhantomNodeStyle() };
         }
 
@@ -60,6 +60,26 @@ export const TauriCommands = {
     return await invoke<GraphData>('scan_folder', { path });
   },
 
+  /**
+   * Retrieves the graph held in the backend cache.
+   *
+   * Invokes the backend `get_cached_graph` command. At startup the backend
+   * restores its cache from disk (or scans `root_dir` if the vault changed), so
+   * this is usually much faster than `scanFolder`.
+   *
+   * @returns Promise resolving to the cached graph, empty if nothing was scanned yet
+   *
+   * @remarks
+   * The cache is populated in the background; wait for the `graph-ready` event
+   * (see `TauriEvents.onGraphReady`) before treating an empty graph as final.
+   *
+   * @example
+   * const cached = await TauriCommands.getCachedGraph();
+   */
+  async getCachedGraph(): Promise<GraphData> {
+    return await invoke<GraphData>('get_cached_graph');
+  },
+
   /**
    * Retrieves the current application configuration.
    *
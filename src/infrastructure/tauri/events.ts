@@ -9,7 +9,7 @@
  */
 
 import { listen, type UnlistenFn } from '@tauri-apps/api/event';
-import type { GraphData } from '../../shared/types';
+import type { GraphData, GraphReady } from '../../shared/types';
 
 /**
  * Collection of type-safe Tauri event listeners.
@@ -71,4 +71,50 @@ export const TauriEvents = {
       callback(event.payload);
     });
   },
+
+  /**
+   * Subscribes to rescan completion events from the backend.
+   *
+   * Emitted exactly once after every full rebuild of the backend graph cache
+   * (e.g. after `scan_folder`). Once received, the cache is consistent again and
+   * cache-backed commands reflect the new graph.
+   *
+   * @param callback - Function called with the node and edge counts of the rebuilt graph
+   * @returns Promise that resolves to an unlisten function
+   *
+   * @example
+   * const unlisten = await TauriEvents.onRescanComplete(({ node_count, edge_count }) => {
+   *   console.log(`Rescan finished: ${node_count} nodes, ${edge_count} edges`);
+   * });
+   */
+  onRescanComplete(
+    callback: (counts: { node_count: number; edge_count: number }) => void,
+  ): Promise<UnlistenFn> {
+    return listen<{ node_count: number; edge_count: number }>('rescan-complete', (event) => {
+      callback(event.payload);
+    });
+  },
+
+  /**
+   * Subscribes to the event sent when the backend finished populating its graph
+   * cache at startup.
+   *
+   * The backend restores or scans the graph on a background thread after the
+   * window appears, and emits this event exactly once per launch, also when
+   * populating failed or `scan_on_startup` is disabled. Subscribe before reading
+   * the cached graph, so the event can't be missed.
+   *
+   * @param callback - Function called with the cache size and outcome
+   * @returns Promise that resolves to an unlisten function
+   *
+   * @example
+   * const unlisten = await TauriEvents.onGraphReady(async ({ error }) => {
+   *   if (!error) setGraphData(await TauriCommands.getCachedGraph());
+   * });
+   */
+  onGraphReady(callback: (ready: GraphReady) => void): Promise<UnlistenFn> {
+    return listen<GraphReady>('graph-ready', (event) => {
+      callback(event.payload);
+    });
+  },
 };
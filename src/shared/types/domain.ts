@@ -21,6 +21,7 @@
  * @property group - Optional grouping identifier (e.g., "phantom" for broken links)
  * @property file_path - Full file system path (empty string for phantom nodes)
  * @property hashtags - List of hashtags found in the file content
+ * @property color - Display color resolved from the node's group via `group_colors`, or null
  * @property x - Optional X coordinate for node positioning (set by vis-network)
  * @property y - Optional Y coordinate for node positioning (set by vis-network)
  */
@@ -31,6 +32,7 @@ export interface Node {
   group: NodeGroup | null;
   file_path: string;
   hashtags: string[];
+  color: string | null;
   x?: number;
   y?: number;
 }
@@ -38,11 +40,14 @@ export interface Node {
 /**
  * Node group classifications for visual distinction.
  *
- * Currently only supports "phantom" nodes, which represent broken wiki-links
- * to non-existent files. These are typically rendered with different colors
- * or styles to indicate missing notes.
+ * The backend assigns "phantom" to nodes that represent broken wiki-links to
+ * non-existent files. Notes may declare any other group through a `group`
+ * frontmatter key (e.g. "important"); "phantom" is reserved and never set on
+ * existing notes. With `namespace_groups`, notes and phantom nodes in a
+ * namespace get the namespace as group instead, so phantom nodes are
+ * identified by their empty `file_path`.
  */
-export type NodeGroup = 'phantom';
+export type NodeGroup = string;
 
 /**
  * Represents a directed edge between two nodes in the graph.
@@ -66,10 +71,13 @@ export interface Edge {
  *
  * @property nodes - Array of all nodes (files and phantoms) in the graph
  * @property edges - Array of all directed connections between nodes
+ * @property directed - False when the backend is configured with `undirected`, meaning
+ *                      edges should be rendered without arrows
  */
 export interface GraphData {
   nodes: Node[];
   edges: Edge[];
+  directed: boolean;
 }
 
 /**
@@ -80,9 +88,26 @@ export interface GraphData {
  *
  * @property root_dir - Path to the root directory containing markdown files to scan,
  *                      or null if not configured
+ * @property scan_on_startup - Whether the backend populates its graph cache at startup
  */
 export interface AppConfig {
   root_dir: string | null;
+  scan_on_startup: boolean;
+}
+
+/**
+ * Payload of the `graph-ready` event, mirroring the Rust `GraphReady` structure.
+ *
+ * @property node_count - Number of nodes in the backend cache
+ * @property edge_count - Number of edges in the backend cache
+ * @property restored - Whether the cache was restored from disk rather than scanned
+ * @property error - Error message if populating the cache failed, or null
+ */
+export interface GraphReady {
+  node_count: number;
+  edge_count: number;
+  restored: boolean;
+  error: string | null;
 }
 
 /**